@@ -42,7 +42,9 @@ pub fn command(options: CompilePackage) -> Result<()> {
     compiler.write_entrypoint = false;
     compiler.write_metadata = true;
     compiler.compile_beam_bytecode = !options.skip_beam_compilation;
-    let _ = compiler.compile(&warnings, &mut type_manifests, &mut defined_modules)?;
+    let _ = compiler
+        .compile(&warnings, &mut type_manifests, &mut defined_modules)
+        .into_result()?;
 
     Ok(())
 }
@@ -12,9 +12,11 @@ use std::{
     ffi::OsStr,
     fmt::Debug,
     fs::File,
-    io::{self, BufRead, BufReader, Write},
+    io::{self, BufRead, BufReader, Read, Write},
     path::{Path, PathBuf},
-    time::SystemTime,
+    sync::{Arc, Mutex},
+    thread::JoinHandle,
+    time::{Duration, Instant, SystemTime},
 };
 
 #[cfg(test)]
@@ -578,6 +580,95 @@ pub fn is_inside_git_work_tree(path: &Path) -> Result<bool, Error> {
     }
 }
 
+/// Run a program, returning whether it exited successfully along with
+/// everything it printed to stdout and stderr. If the program has not exited
+/// by the end of the timeout it is killed, and `None` is returned in place of
+/// whether it was successful.
+///
+pub fn exec_with_output(
+    program: &str,
+    args: &[String],
+    timeout: Duration,
+) -> Result<(Option<bool>, String), Error> {
+    tracing::trace!(program=program, args=?args.join(" "), "command_exec_with_output");
+    let mut child = std::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|error| command_error(program, error))?;
+
+    // The output is read while waiting so the program cannot block on
+    // writing to a full pipe.
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let success = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status.success()),
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            Ok(None) => {
+                tracing::trace!(program = program, "command_exec_timed_out");
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Err(error) => return Err(command_error(program, error)),
+        }
+    };
+
+    // Processes started by the program may keep its output open after it has
+    // exited, so the rest of the output is only waited for briefly.
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while !(stdout.1.is_finished() && stderr.1.is_finished()) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let output = |(bytes, _): &(Arc<Mutex<Vec<u8>>>, JoinHandle<()>)| {
+        let bytes = bytes.lock().map(|bytes| bytes.clone()).unwrap_or_default();
+        String::from_utf8_lossy(&bytes).to_string()
+    };
+    let mut text = output(&stdout);
+    text.push_str(&output(&stderr));
+    Ok((success, text))
+}
+
+/// Reads everything from the pipe in another thread, into the returned
+/// buffer.
+fn read_in_background(
+    pipe: Option<impl Read + Send + 'static>,
+) -> (Arc<Mutex<Vec<u8>>>, JoinHandle<()>) {
+    let bytes = Arc::new(Mutex::new(vec![]));
+    let buffer = bytes.clone();
+    let reader = std::thread::spawn(move || {
+        let mut pipe = match pipe {
+            Some(pipe) => pipe,
+            None => return,
+        };
+        let mut chunk = [0; 4096];
+        while let Ok(read @ 1..) = pipe.read(&mut chunk) {
+            if let (Ok(mut buffer), Some(chunk)) = (buffer.lock(), chunk.get(..read)) {
+                buffer.extend_from_slice(chunk);
+            }
+        }
+    });
+    (bytes, reader)
+}
+
+fn command_error(program: &str, error: io::Error) -> Error {
+    match error.kind() {
+        io::ErrorKind::NotFound => Error::ShellProgramNotFound {
+            program: program.to_string(),
+        },
+
+        other => Error::ShellCommand {
+            program: program.to_string(),
+            err: Some(other),
+        },
+    }
+}
+
 /// Run `git init` in the given path.
 /// If git is not installed then we do nothing.
 pub fn git_init(path: &Path) -> Result<(), Error> {
@@ -59,3 +59,25 @@ fn is_gleam_path_test() {
         Path::new("/some-prefix/")
     ));
 }
+
+#[cfg(unix)]
+#[test]
+fn exec_with_output_kills_program_after_timeout() {
+    let args = vec!["-c".into(), "echo started; sleep 10".into()];
+    let start = std::time::Instant::now();
+    let (success, output) =
+        super::exec_with_output("sh", &args, std::time::Duration::from_millis(200)).unwrap();
+    assert_eq!(success, None);
+    assert_eq!(output, "started\n");
+    assert!(start.elapsed() < std::time::Duration::from_secs(5));
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_with_output_success() {
+    let args = vec!["-c".into(), "echo out; echo err >&2; exit 1".into()];
+    let (success, output) =
+        super::exec_with_output("sh", &args, std::time::Duration::from_secs(5)).unwrap();
+    assert_eq!(success, Some(false));
+    assert_eq!(output, "out\nerr\n");
+}
@@ -5,15 +5,12 @@
 // TODO: Remove all use of the Connection from the LanguageServer. That is the
 // job of the protocol adapter.
 
-// TODO: Make a new router class which finds the root of the project a message
-// is for and dispatches to the correct language server, making one for that
-// root if it does not exist. This will require the compiler to be modified so
-// that it can run on projects where the root is not the cwd.
-
 mod feedback;
 mod progress;
 mod protocol_adapter;
+mod router;
 mod server;
+mod test_runner;
 
 use crate::{
     build_lock::BuildLock, dependencies::UseManifest, fs,
@@ -21,10 +18,14 @@ use crate::{
 };
 use gleam_core::{
     ast::SrcSpan,
-    build::{self, Module, ProjectCompiler},
+    build::{self, Module, Outcome, ProjectCompiler},
     config::PackageConfig,
     diagnostic::{Diagnostic, Level},
     io::{CommandExecutor, FileSystemIO, Stdio},
+    language_server::{
+        completion::Documentation,
+        semantic_tokens::{TokenModifier, TokenType},
+    },
     line_numbers::LineNumbers,
     paths, Error, Result,
 };
@@ -32,6 +33,7 @@ use gleam_core::{build::Mode, warning::VectorWarningEmitterIO};
 use itertools::Itertools;
 use lsp_types::{self as lsp, HoverProviderCapability, Position, Range, Url};
 use std::{
+    cell::RefCell,
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
@@ -45,23 +47,12 @@ use self::feedback::Feedback;
 pub fn main() -> Result<()> {
     tracing::info!("language_server_starting");
 
-    // Read the project config. If we are running in the context of a Gleam
-    // project then there will be one. If not there will not be one and we'll
-    // fall back to a non-compiling mode that can only do formatting.
-    let config = if paths::root_config().exists() {
-        tracing::info!("gleam_project_detected");
-        Some(crate::config::root_config()?)
-    } else {
-        tracing::info!("gleam_project_not_found");
-        None
-    };
-
     // Create the transport. Includes the stdio (stdin and stdout) versions but this could
     // also be implemented to use sockets or HTTP.
     let (connection, io_threads) = lsp_server::Connection::stdio();
 
     // Run the server and wait for the two threads to end (typically by trigger LSP Exit event).
-    LanguageServerProtocolAdapter::new(&connection, config)?.run()?;
+    LanguageServerProtocolAdapter::new(&connection)?.run()?;
     io_threads.join().expect("joining_lsp_threads");
 
     // Shut down gracefully.
@@ -84,44 +75,115 @@ pub fn server_capabilities() -> lsp::ServerCapabilities {
                 )),
             },
         )),
-        selection_range_provider: None,
+        selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
         hover_provider: Some(HoverProviderCapability::Simple(true)),
         completion_provider: Some(lsp::CompletionOptions {
             resolve_provider: None,
             trigger_characters: Some(vec![".".into(), " ".into()]),
             all_commit_characters: None,
+            completion_item: None,
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
+        signature_help_provider: Some(lsp::SignatureHelpOptions {
+            trigger_characters: Some(vec!["(".into(), ",".into()]),
+            retrigger_characters: None,
             work_done_progress_options: lsp::WorkDoneProgressOptions {
                 work_done_progress: None,
             },
         }),
-        signature_help_provider: None,
         definition_provider: Some(lsp::OneOf::Left(true)),
-        type_definition_provider: None,
-        implementation_provider: None,
-        references_provider: None,
-        document_highlight_provider: None,
-        document_symbol_provider: None,
-        workspace_symbol_provider: None,
-        code_action_provider: None,
-        code_lens_provider: None,
+        type_definition_provider: Some(lsp::TypeDefinitionProviderCapability::Simple(true)),
+        implementation_provider: Some(lsp::ImplementationProviderCapability::Simple(true)),
+        references_provider: Some(lsp::OneOf::Left(true)),
+        document_highlight_provider: Some(lsp::OneOf::Left(true)),
+        document_symbol_provider: Some(lsp::OneOf::Left(true)),
+        workspace_symbol_provider: Some(lsp::OneOf::Left(true)),
+        code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+        code_lens_provider: Some(lsp::CodeLensOptions {
+            resolve_provider: Some(false),
+        }),
         document_formatting_provider: Some(lsp::OneOf::Left(true)),
-        document_range_formatting_provider: None,
-        document_on_type_formatting_provider: None,
-        rename_provider: None,
+        document_range_formatting_provider: Some(lsp::OneOf::Left(true)),
+        document_on_type_formatting_provider: Some(lsp::DocumentOnTypeFormattingOptions {
+            first_trigger_character: "}".into(),
+            more_trigger_character: None,
+        }),
+        rename_provider: Some(lsp::OneOf::Right(lsp::RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        })),
         document_link_provider: None,
         color_provider: None,
-        folding_range_provider: None,
+        folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
         declaration_provider: None,
-        execute_command_provider: None,
+        execute_command_provider: Some(lsp::ExecuteCommandOptions {
+            commands: vec![test_runner::RUN_TESTS_COMMAND.into()],
+            work_done_progress_options: lsp::WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         workspace: None,
-        call_hierarchy_provider: None,
-        semantic_tokens_provider: None,
+        call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensOptions {
+                legend: semantic_tokens_legend(),
+                range: Some(true),
+                full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                work_done_progress_options: lsp::WorkDoneProgressOptions {
+                    work_done_progress: None,
+                },
+            }
+            .into(),
+        ),
         moniker_provider: None,
         linked_editing_range_provider: None,
+        inlay_hint_provider: Some(lsp::OneOf::Left(true)),
         experimental: None,
     }
 }
 
+/// The token types and modifiers in the order of `TokenType::ALL` and
+/// `TokenModifier::ALL`, as the tokens sent refer to them by index.
+fn semantic_tokens_legend() -> lsp::SemanticTokensLegend {
+    let token_types = TokenType::ALL
+        .iter()
+        .map(|type_| match type_ {
+            TokenType::Namespace => lsp::SemanticTokenType::NAMESPACE,
+            TokenType::Type => lsp::SemanticTokenType::TYPE,
+            TokenType::TypeParameter => lsp::SemanticTokenType::TYPE_PARAMETER,
+            TokenType::Constructor => lsp::SemanticTokenType::ENUM_MEMBER,
+            TokenType::Function => lsp::SemanticTokenType::FUNCTION,
+            TokenType::Parameter => lsp::SemanticTokenType::PARAMETER,
+            TokenType::Variable => lsp::SemanticTokenType::VARIABLE,
+            TokenType::Property => lsp::SemanticTokenType::PROPERTY,
+            TokenType::Keyword => lsp::SemanticTokenType::KEYWORD,
+            TokenType::Comment => lsp::SemanticTokenType::COMMENT,
+            TokenType::String => lsp::SemanticTokenType::STRING,
+            TokenType::Number => lsp::SemanticTokenType::NUMBER,
+            TokenType::Operator => lsp::SemanticTokenType::OPERATOR,
+        })
+        .collect();
+    let token_modifiers = TokenModifier::ALL
+        .iter()
+        .map(|modifier| match modifier {
+            TokenModifier::Declaration => lsp::SemanticTokenModifier::DECLARATION,
+            TokenModifier::Readonly => lsp::SemanticTokenModifier::READONLY,
+            TokenModifier::Deprecated => lsp::SemanticTokenModifier::DEPRECATED,
+            TokenModifier::Documentation => lsp::SemanticTokenModifier::DOCUMENTATION,
+            TokenModifier::External => lsp::SemanticTokenModifier::new("external"),
+            TokenModifier::Discarded => lsp::SemanticTokenModifier::new("discarded"),
+        })
+        .collect();
+    lsp::SemanticTokensLegend {
+        token_types,
+        token_modifiers,
+    }
+}
+
 #[derive(Debug)]
 pub struct ModuleSourceInformation {
     /// The path to the source file from within the project root
@@ -308,6 +370,11 @@ pub struct LspProjectCompiler<IO> {
     modules: HashMap<String, Module>,
     sources: HashMap<String, ModuleSourceInformation>,
 
+    /// The documentation of the modules of the project and its dependencies,
+    /// worked out when first needed by completion or hover. The entry for a
+    /// module of the root package is removed when that module is recompiled.
+    documentation: RefCell<HashMap<String, Documentation>>,
+
     /// The storage for the warning emitter.
     warnings: Arc<VectorWarningEmitterIO>,
 
@@ -364,18 +431,29 @@ where
             project_compiler,
             modules: HashMap::new(),
             sources: HashMap::new(),
+            documentation: RefCell::new(HashMap::new()),
             build_lock,
             dependencies_compiled: false,
         })
     }
 
-    pub fn compile(&mut self) -> Result<Vec<PathBuf>, Error> {
+    /// Compiles the root package, returning the paths of the modules that
+    /// were compiled.
+    ///
+    /// If a module fails to type check the modules compiled before it are
+    /// still stored, while the information for the failing module and any
+    /// after it is that of their last successful compilation. This keeps
+    /// language server features working while code is being edited.
+    ///
+    pub fn compile(&mut self) -> Outcome<Vec<PathBuf>, Error> {
         // Lock the build directory to ensure to ensure we are the only one compiling
         let _lock = self.build_lock.lock(&NullTelemetry);
 
         if !self.dependencies_compiled {
             // TODO: store compiled module info
-            self.project_compiler.compile_dependencies()?;
+            if let Err(error) = self.project_compiler.compile_dependencies() {
+                return Outcome::TotalFailure(error);
+            }
             self.dependencies_compiled = true;
         }
 
@@ -385,27 +463,28 @@ where
         // Do that there compilation. We don't use `?` to return early in the
         // event of an error because we _always_ want to do the restoration of
         // state afterwards.
-        let result = self.project_compiler.compile_root_package();
+        let outcome = self.project_compiler.compile_root_package();
 
         // Restore the state so that later we can compile the root again
         self.project_compiler.restore(checkpoint);
 
-        // Return any error
-        let package = result?;
-        let mut compiled_modules = Vec::with_capacity(package.modules.len());
-
-        // Store the compiled module information
-        for module in package.modules {
-            let pathbuf = module.input_path.canonicalize().expect("Canonicalize");
-            let path = pathbuf.as_os_str().to_string_lossy().to_string();
-            let line_numbers = LineNumbers::new(&module.code);
-            let source = ModuleSourceInformation { path, line_numbers };
-            _ = self.sources.insert(module.name.to_string(), source);
-            _ = self.modules.insert(module.name.to_string(), module);
-            compiled_modules.push(pathbuf);
-        }
-
-        Ok(compiled_modules)
+        // Store the compiled module information, including that of any modules
+        // compiled before an error
+        outcome.map(|package| {
+            let mut compiled_modules = Vec::with_capacity(package.modules.len());
+            for mut module in package.modules {
+                module.attach_doc_and_module_comments();
+                let pathbuf = module.input_path.canonicalize().expect("Canonicalize");
+                let path = pathbuf.as_os_str().to_string_lossy().to_string();
+                let line_numbers = LineNumbers::new(&module.code);
+                let source = ModuleSourceInformation { path, line_numbers };
+                _ = self.sources.insert(module.name.to_string(), source);
+                _ = self.documentation.get_mut().remove(module.name.as_str());
+                _ = self.modules.insert(module.name.to_string(), module);
+                compiled_modules.push(pathbuf);
+            }
+            compiled_modules
+        })
     }
 }
 
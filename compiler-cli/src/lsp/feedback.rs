@@ -12,6 +12,15 @@ pub struct Feedback {
 }
 
 impl Feedback {
+    /// Feedback for an error that is not for any of the files of a package,
+    /// such as the directory of the package no longer being usable.
+    pub fn error(error: Error) -> Self {
+        Self {
+            diagnostics: HashMap::new(),
+            messages: error.to_diagnostics(),
+        }
+    }
+
     /// Set the diagnostics for a file to an empty vector. This will overwrite
     /// any existing diagnostics on the client.
     pub fn unset_existing_diagnostics(&mut self, path: PathBuf) {
@@ -25,6 +34,17 @@ impl Feedback {
             .push(diagnostic);
     }
 
+    /// Adds the diagnostics and messages of other feedback to this feedback.
+    pub fn append_feedback(&mut self, feedback: Feedback) {
+        for (path, diagnostics) in feedback.diagnostics {
+            self.diagnostics
+                .entry(path)
+                .or_default()
+                .extend(diagnostics);
+        }
+        self.messages.extend(feedback.messages);
+    }
+
     fn append_message(&mut self, diagnostic: Diagnostic) {
         self.messages.push(diagnostic);
     }
@@ -92,21 +112,51 @@ impl FeedbackBookKeeper {
         compiled: impl Iterator<Item = PathBuf>,
         warnings: Vec<Warning>,
     ) -> Feedback {
-        let diagnostic = error.to_diagnostic();
         let mut feedback = self.diagnostics(compiled, warnings);
 
-        match diagnostic.location.as_ref().map(|l| l.path.clone()) {
-            Some(path) => {
-                // TODO: remove this once the compiler is using absolute paths. This
-                // function has side effects so it shouldn't be anyway.
-                let path = path.canonicalize().unwrap_or(path);
-                _ = self.files_with_diagnostics.insert(path.clone());
-                feedback.append_diagnostic(path, diagnostic);
+        for diagnostic in error.to_diagnostics() {
+            match diagnostic.location.as_ref().map(|l| l.path.clone()) {
+                Some(path) => {
+                    // TODO: remove this once the compiler is using absolute paths. This
+                    // function has side effects so it shouldn't be anyway.
+                    let path = path.canonicalize().unwrap_or(path);
+                    _ = self.files_with_diagnostics.insert(path.clone());
+                    feedback.append_diagnostic(path, diagnostic);
+                }
+
+                None => {
+                    feedback.append_message(diagnostic);
+                }
             }
+        }
 
-            None => {
-                feedback.append_message(diagnostic);
-            }
+        feedback
+    }
+
+    /// The tests of a module have been run.
+    ///
+    /// Send diagnostics for the tests that failed. As these replace all the
+    /// diagnostics of the file its warnings are sent again alongside them.
+    ///
+    pub fn test_failures(
+        &mut self,
+        path: PathBuf,
+        warnings: Vec<Warning>,
+        failures: Vec<Diagnostic>,
+    ) -> Feedback {
+        let path = path.canonicalize().unwrap_or(path);
+        let mut feedback = Feedback::default();
+        feedback.unset_existing_diagnostics(path.clone());
+
+        for warning in warnings {
+            self.insert_warning(&mut feedback, warning);
+        }
+
+        if !failures.is_empty() {
+            _ = self.files_with_diagnostics.insert(path.clone());
+        }
+        for failure in failures {
+            feedback.append_diagnostic(path.clone(), failure);
         }
 
         feedback
@@ -251,10 +301,10 @@ mod tests {
         let error = Error::Parse {
             path: file3.clone(),
             src: "blah".into(),
-            error: ParseError {
+            errors: vec1::vec1![ParseError {
                 error: ParseErrorType::ConcatPatternVariableLeftHandSide,
                 location: SrcSpan::new(1, 4),
-            },
+            }],
         };
 
         let feedback = book_keeper.diagnostics_with_error(
@@ -288,4 +338,87 @@ mod tests {
             feedback
         );
     }
+
+    #[test]
+    fn error_with_several_problems() {
+        // An error reporting several problems, such as the syntax errors of a
+        // module, sets a diagnostic for each of them.
+
+        let mut book_keeper = FeedbackBookKeeper::default();
+        let file = PathBuf::from("src/file.gleam");
+
+        let error = Error::Parse {
+            path: file.clone(),
+            src: "blah blah".into(),
+            errors: vec1::vec1![
+                ParseError {
+                    error: ParseErrorType::ConcatPatternVariableLeftHandSide,
+                    location: SrcSpan::new(1, 4),
+                },
+                ParseError {
+                    error: ParseErrorType::ExpectedExpr,
+                    location: SrcSpan::new(6, 9),
+                }
+            ],
+        };
+
+        let feedback =
+            book_keeper.diagnostics_with_error(error.clone(), vec![].into_iter(), vec![]);
+
+        assert_eq!(
+            Feedback {
+                diagnostics: vec![(file, error.to_diagnostics())].into_iter().collect(),
+                messages: vec![],
+            },
+            feedback
+        );
+        assert_eq!(error.to_diagnostics().len(), 2);
+    }
+
+    #[test]
+    fn test_failures() {
+        // The diagnostics of the file are replaced by its warnings and the
+        // failed tests, and removed once the file compiles later.
+
+        let mut book_keeper = FeedbackBookKeeper::default();
+        let file1 = PathBuf::from("test/file1.gleam");
+
+        let warning1 = Warning::Type {
+            path: file1.clone(),
+            src: "src".into(),
+            warning: type_::Warning::NoFieldsRecordUpdate {
+                location: SrcSpan::new(1, 2),
+            },
+        };
+        let failure = Diagnostic {
+            title: "Test failed".into(),
+            text: "Oh no".into(),
+            level: gleam_core::diagnostic::Level::Error,
+            location: None,
+            hint: None,
+        };
+
+        let feedback =
+            book_keeper.test_failures(file1.clone(), vec![warning1.clone()], vec![failure.clone()]);
+
+        assert_eq!(
+            Feedback {
+                diagnostics: vec![(file1.clone(), vec![warning1.to_diagnostic(), failure])]
+                    .into_iter()
+                    .collect(),
+                messages: vec![],
+            },
+            feedback
+        );
+
+        let feedback = book_keeper.diagnostics(vec![file1.clone()].into_iter(), vec![]);
+
+        assert_eq!(
+            Feedback {
+                diagnostics: vec![(file1, vec![]),].into_iter().collect(),
+                messages: vec![],
+            },
+            feedback
+        );
+    }
 }
@@ -9,7 +9,7 @@ const CREATE_COMPILING_PROGRESS_TOKEN: &str = "create-compiling-progress-token";
 
 // Used to publish progress notifications to the client without waiting for
 // the usual request-response loop of the language server.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ProgressReporter<'a> {
     connection: DebugIgnore<&'a lsp_server::Connection>,
 }
@@ -1,21 +1,38 @@
 use super::{
-    convert_response, diagnostic_to_lsp, feedback::Feedback, path_to_uri,
-    progress::ProgressReporter, server::LanguageServer,
+    convert_response, diagnostic_to_lsp,
+    feedback::Feedback,
+    path_to_uri,
+    progress::ProgressReporter,
+    router::{self, Router},
 };
 use gleam_core::{
-    config::PackageConfig,
     diagnostic::{Diagnostic, Level},
-    Result,
+    paths, Result,
+};
+use lsp::{
+    notification::DidOpenTextDocument,
+    request::{GotoDefinition, GotoImplementation, GotoTypeDefinition},
 };
-use lsp::{notification::DidOpenTextDocument, request::GotoDefinition};
 use lsp_types::InitializeParams;
 use lsp_types::{
     self as lsp,
-    notification::{DidChangeTextDocument, DidCloseTextDocument, DidSaveTextDocument},
-    request::{Completion, Formatting, HoverRequest},
-    PublishDiagnosticsParams,
+    notification::{
+        DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument, DidSaveTextDocument,
+    },
+    request::{
+        CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
+        CodeActionRequest, CodeLensRequest, Completion, DocumentHighlightRequest,
+        DocumentSymbolRequest, ExecuteCommand, FoldingRangeRequest, Formatting, HoverRequest,
+        InlayHintRequest, OnTypeFormatting, PrepareRenameRequest, RangeFormatting, References,
+        Rename, SelectionRangeRequest, SemanticTokensFullRequest, SemanticTokensRangeRequest,
+        SignatureHelpRequest, WorkspaceSymbol,
+    },
+    PublishDiagnosticsParams, Url,
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
 };
-use std::{collections::HashMap, path::PathBuf};
 
 /// This class is responsible for handling the language server protocol and
 /// delegating the work to the `LanguageServer` itself.
@@ -25,34 +42,33 @@ use std::{collections::HashMap, path::PathBuf};
 /// - Encoding responses.
 /// - Sending diagnostics and messages to the client.
 /// - Performing the initialisation handshake.
+/// - Routing messages to the server for the package they are for.
 ///
 pub struct LanguageServerProtocolAdapter<'a> {
     initialise_params: InitializeParams,
     connection: &'a lsp_server::Connection,
-    server: LanguageServer<'a>,
+    router: Router<'a>,
 }
 
 impl<'a> LanguageServerProtocolAdapter<'a> {
-    pub fn new(
-        connection: &'a lsp_server::Connection,
-        config: Option<PackageConfig>,
-    ) -> Result<Self> {
+    pub fn new(connection: &'a lsp_server::Connection) -> Result<Self> {
         let initialise_params = initialisation_handshake(connection);
         let reporter = ProgressReporter::new(connection, &initialise_params);
-        let language_server = LanguageServer::new(config, reporter)?;
+        let router = Router::new(package_roots(&initialise_params), reporter)?;
         Ok(Self {
             connection,
             initialise_params,
-            server: language_server,
+            router,
         })
     }
 
     pub fn run(&mut self) -> Result<()> {
         self.start_watching_gleam_toml();
 
-        // Compile the project once so we have all the state and any initial errors
-        let feedback = self.server.compile_please();
-        self.publish_feedback(feedback);
+        // Compile each package once so we have all the state and any initial errors
+        for root in self.router.roots() {
+            self.compile(&root);
+        }
 
         // Enter the message loop, handling each message that comes in from the client
         for message in &self.connection.receiver {
@@ -92,68 +108,220 @@ impl<'a> LanguageServerProtocolAdapter<'a> {
     fn handle_request(&mut self, request: lsp_server::Request) {
         let id = request.id.clone();
         let (payload, feedback) = match request.method.as_str() {
+            "workspace/symbol" => {
+                let params = cast_request::<WorkspaceSymbol>(request);
+                self.workspace_symbol(params)
+            }
+
+            _ => self.handle_package_request(request),
+        };
+
+        self.publish_feedback(feedback);
+
+        let response = lsp_server::Response {
+            id,
+            error: None,
+            result: Some(payload),
+        };
+        self.connection
+            .sender
+            .send(lsp_server::Message::Response(response))
+            .expect("channel send LSP response")
+    }
+
+    /// The symbols of every package in the workspace.
+    fn workspace_symbol(
+        &mut self,
+        params: lsp::WorkspaceSymbolParams,
+    ) -> (serde_json::Value, Feedback) {
+        let mut symbols = vec![];
+        let mut feedback = Feedback::default();
+        for root in self.router.roots() {
+            let (payload, package_feedback) = match self.router.server(&root) {
+                Ok(server) => convert_response(server.workspace_symbol(params.clone())),
+                Err(error) => (serde_json::Value::Null, Feedback::error(error)),
+            };
+            if let serde_json::Value::Array(package_symbols) = payload {
+                symbols.extend(package_symbols);
+            }
+            feedback.append_feedback(package_feedback);
+        }
+        (serde_json::Value::Array(symbols), feedback)
+    }
+
+    /// Handles a request for a document, or a test module in the case of
+    /// `workspace/executeCommand`, using the server of the package that
+    /// contains it.
+    fn handle_package_request(
+        &mut self,
+        request: lsp_server::Request,
+    ) -> (serde_json::Value, Feedback) {
+        let root = self.root_for(&request.params);
+        let server = match self.router.server(&root) {
+            Ok(server) => server,
+            Err(error) => return (serde_json::Value::Null, Feedback::error(error)),
+        };
+        match request.method.as_str() {
             "textDocument/formatting" => {
                 let params = cast_request::<Formatting>(request);
-                convert_response(self.server.format(params))
+                convert_response(server.format(params))
+            }
+
+            "textDocument/rangeFormatting" => {
+                let params = cast_request::<RangeFormatting>(request);
+                convert_response(server.range_format(params))
+            }
+
+            "textDocument/onTypeFormatting" => {
+                let params = cast_request::<OnTypeFormatting>(request);
+                convert_response(server.on_type_format(params))
             }
 
             "textDocument/hover" => {
                 let params = cast_request::<HoverRequest>(request);
-                convert_response(self.server.hover(params))
+                convert_response(server.hover(params))
             }
 
             "textDocument/definition" => {
                 let params = cast_request::<GotoDefinition>(request);
-                convert_response(self.server.goto_definition(params))
+                convert_response(server.goto_definition(params))
+            }
+
+            "textDocument/typeDefinition" => {
+                let params = cast_request::<GotoTypeDefinition>(request);
+                convert_response(server.goto_type_definition(params))
+            }
+
+            "textDocument/implementation" => {
+                let params = cast_request::<GotoImplementation>(request);
+                convert_response(server.goto_implementation(params))
             }
 
             "textDocument/completion" => {
                 let params = cast_request::<Completion>(request);
-                convert_response(self.server.completion(params))
+                convert_response(server.completion(params))
             }
 
-            _ => panic!("Unsupported LSP request"),
-        };
+            "textDocument/codeAction" => {
+                let params = cast_request::<CodeActionRequest>(request);
+                convert_response(server.code_action(params))
+            }
 
-        self.publish_feedback(feedback);
+            "textDocument/inlayHint" => {
+                let params = cast_request::<InlayHintRequest>(request);
+                convert_response(server.inlay_hint(params))
+            }
 
-        let response = lsp_server::Response {
-            id,
-            error: None,
-            result: Some(payload),
-        };
-        self.connection
-            .sender
-            .send(lsp_server::Message::Response(response))
-            .expect("channel send LSP response")
+            "textDocument/semanticTokens/full" => {
+                let params = cast_request::<SemanticTokensFullRequest>(request);
+                convert_response(server.semantic_tokens_full(params))
+            }
+
+            "textDocument/semanticTokens/range" => {
+                let params = cast_request::<SemanticTokensRangeRequest>(request);
+                convert_response(server.semantic_tokens_range(params))
+            }
+
+            "textDocument/foldingRange" => {
+                let params = cast_request::<FoldingRangeRequest>(request);
+                convert_response(server.folding_range(params))
+            }
+
+            "textDocument/selectionRange" => {
+                let params = cast_request::<SelectionRangeRequest>(request);
+                convert_response(server.selection_range(params))
+            }
+
+            "textDocument/documentHighlight" => {
+                let params = cast_request::<DocumentHighlightRequest>(request);
+                convert_response(server.document_highlight(params))
+            }
+
+            "textDocument/prepareCallHierarchy" => {
+                let params = cast_request::<CallHierarchyPrepare>(request);
+                convert_response(server.prepare_call_hierarchy(params))
+            }
+
+            "callHierarchy/incomingCalls" => {
+                let params = cast_request::<CallHierarchyIncomingCalls>(request);
+                convert_response(server.incoming_calls(params))
+            }
+
+            "callHierarchy/outgoingCalls" => {
+                let params = cast_request::<CallHierarchyOutgoingCalls>(request);
+                convert_response(server.outgoing_calls(params))
+            }
+
+            "textDocument/codeLens" => {
+                let params = cast_request::<CodeLensRequest>(request);
+                convert_response(server.code_lens(params))
+            }
+
+            "workspace/executeCommand" => {
+                let params = cast_request::<ExecuteCommand>(request);
+                convert_response(server.execute_command(params))
+            }
+
+            "textDocument/signatureHelp" => {
+                let params = cast_request::<SignatureHelpRequest>(request);
+                convert_response(server.signature_help(params))
+            }
+
+            "textDocument/references" => {
+                let params = cast_request::<References>(request);
+                convert_response(server.find_references(params))
+            }
+
+            "textDocument/prepareRename" => {
+                let params = cast_request::<PrepareRenameRequest>(request);
+                convert_response(server.prepare_rename(params))
+            }
+
+            "textDocument/rename" => {
+                let params = cast_request::<Rename>(request);
+                convert_response(server.rename(params))
+            }
+
+            "textDocument/documentSymbol" => {
+                let params = cast_request::<DocumentSymbolRequest>(request);
+                convert_response(server.document_symbol(params))
+            }
+
+            _ => panic!("Unsupported LSP request"),
+        }
     }
 
     fn handle_notification(&mut self, notification: lsp_server::Notification) {
+        let root = self.root_for(&notification.params);
+        let server = match self.router.server(&root) {
+            Ok(server) => server,
+            Err(error) => return self.publish_feedback(Feedback::error(error)),
+        };
         let feedback = match notification.method.as_str() {
             "textDocument/didOpen" => {
                 let params = cast_notification::<DidOpenTextDocument>(notification);
-                self.server.text_document_did_open(params)
+                server.text_document_did_open(params)
             }
 
             "textDocument/didSave" => {
                 let params = cast_notification::<DidSaveTextDocument>(notification);
-                self.server.text_document_did_save(params)
+                server.text_document_did_save(params)
             }
 
             "textDocument/didClose" => {
                 let params = cast_notification::<DidCloseTextDocument>(notification);
-                self.server.text_document_did_close(params)
+                server.text_document_did_close(params)
             }
 
             "textDocument/didChange" => {
                 let params = cast_notification::<DidChangeTextDocument>(notification);
-                self.server.text_document_did_change(params)
+                server.text_document_did_change(params)
             }
 
             "workspace/didChangeWatchedFiles" => {
-                tracing::info!("gleam_toml_changed_so_recompiling_full_project");
-                self.server.create_new_compiler().expect("create");
-                self.server.compile_please()
+                let params = cast_notification::<DidChangeWatchedFiles>(notification);
+                self.gleam_toml_changed(params);
+                return;
             }
 
             _ => return,
@@ -162,6 +330,55 @@ impl<'a> LanguageServerProtocolAdapter<'a> {
         self.publish_feedback(feedback);
     }
 
+    fn compile(&mut self, root: &Path) {
+        let feedback = match self.router.server(root) {
+            Ok(server) => server.compile_please(),
+            Err(error) => Feedback::error(error),
+        };
+        self.publish_feedback(feedback);
+    }
+
+    /// A `gleam.toml` file has been created, changed, or deleted, so the
+    /// server for its package is created again or removed.
+    fn gleam_toml_changed(&mut self, params: lsp::DidChangeWatchedFilesParams) {
+        for change in params.changes {
+            let root = match change
+                .uri
+                .to_file_path()
+                .ok()
+                .and_then(|path| path.parent()?.canonicalize().ok())
+            {
+                Some(root) => root,
+                None => continue,
+            };
+
+            if change.typ == lsp::FileChangeType::DELETED {
+                tracing::info!(root = ?root, "gleam_toml_deleted_so_removing_package");
+                self.router.remove_package(&root);
+                continue;
+            }
+
+            tracing::info!(root = ?root, "gleam_toml_changed_so_recompiling_full_project");
+            match self.router.add_package(root.clone()) {
+                Ok(()) => self.compile(&root),
+                Err(error) => self.publish_feedback(Feedback::error(error)),
+            }
+        }
+    }
+
+    /// The root of the package containing the file a message is for. Messages
+    /// that are not for a file are handled by the default package.
+    fn root_for(&self, params: &serde_json::Value) -> PathBuf {
+        let path = ["/textDocument/uri", "/item/uri", "/arguments/0"]
+            .iter()
+            .find_map(|pointer| {
+                let uri = params.pointer(pointer)?.as_str()?;
+                Url::parse(uri).ok()?.to_file_path().ok()
+            });
+        self.router
+            .root_for(path.as_deref().unwrap_or(Path::new("")))
+    }
+
     fn publish_feedback(&self, feedback: Feedback) {
         self.publish_diagnostics(feedback.diagnostics);
         self.publish_messages(feedback.messages);
@@ -208,16 +425,21 @@ impl<'a> LanguageServerProtocolAdapter<'a> {
             return;
         }
 
-        // Register gleam.toml as a watched file so we get a notification when
-        // it changes and thus know that we need to rebuild the entire project.
+        // Register the gleam.toml files of the workspace as watched files so we
+        // get a notification when one changes and thus know that we need to
+        // rebuild the entire project.
         let watch_config = lsp::Registration {
             id: "watch-gleam-toml".into(),
             method: "workspace/didChangeWatchedFiles".into(),
             register_options: Some(
                 serde_json::value::to_value(lsp::DidChangeWatchedFilesRegistrationOptions {
                     watchers: vec![lsp::FileSystemWatcher {
-                        glob_pattern: "gleam.toml".into(),
-                        kind: Some(lsp::WatchKind::Change),
+                        glob_pattern: "**/gleam.toml".into(),
+                        kind: Some(
+                            lsp::WatchKind::Create
+                                | lsp::WatchKind::Change
+                                | lsp::WatchKind::Delete,
+                        ),
                     }],
                 })
                 .expect("workspace/didChangeWatchedFiles to json"),
@@ -258,6 +480,36 @@ impl<'a> LanguageServerProtocolAdapter<'a> {
     }
 }
 
+/// The roots of the Gleam packages in the workspace folders opened by the
+/// client. The package in the directory the language server was started in,
+/// if there is one, comes first as it is the default package.
+fn package_roots(params: &InitializeParams) -> Vec<PathBuf> {
+    let folders: Vec<PathBuf> = match &params.workspace_folders {
+        Some(folders) if !folders.is_empty() => folders
+            .iter()
+            .filter_map(|folder| folder.uri.to_file_path().ok())
+            .collect(),
+        _ => params
+            .root_uri
+            .iter()
+            .filter_map(|uri| uri.to_file_path().ok())
+            .collect(),
+    };
+    let mut roots = router::discover_packages(&folders);
+
+    if paths::root_config().exists() {
+        tracing::info!("gleam_project_detected");
+        let current_dir = std::env::current_dir()
+            .and_then(|directory| directory.canonicalize())
+            .expect("Project root");
+        roots.retain(|root| root != &current_dir);
+        roots.insert(0, current_dir);
+    } else {
+        tracing::info!("gleam_project_not_found");
+    }
+    roots
+}
+
 fn initialisation_handshake(connection: &lsp_server::Connection) -> InitializeParams {
     let server_capabilities_json =
         serde_json::to_value(super::server_capabilities()).expect("server_capabilities_serde");
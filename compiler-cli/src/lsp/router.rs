@@ -0,0 +1,169 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use gleam_core::{
+    error::{FileIoAction, FileKind},
+    paths, Error, Result,
+};
+
+use super::{progress::ProgressReporter, server::LanguageServer};
+
+/// The language servers for each of the Gleam packages in the workspace, one
+/// per package root. Messages are routed to the server for the package that
+/// contains the file they are for.
+///
+/// The compiler works with paths relative to the working directory, so the
+/// working directory is changed to the root of a package before its server is
+/// used.
+///
+/// Each package is compiled on its own. Packages only depend on one another
+/// through Hex, as path dependencies are not supported, so a package is never
+/// compiled against the source of another package in the workspace and is not
+/// compiled again when that package changes.
+///
+pub struct Router<'a> {
+    packages: HashMap<PathBuf, LanguageServer<'a>>,
+    /// The root of the package used for messages that are not for a file in
+    /// any package. If there are no packages in the workspace this is the
+    /// directory the language server was started in, and its server can only
+    /// format code.
+    default_root: PathBuf,
+    progress_reporter: ProgressReporter<'a>,
+}
+
+impl<'a> Router<'a> {
+    /// Creates a server for each of the packages with the given roots. The
+    /// first is the default package.
+    pub fn new(roots: Vec<PathBuf>, progress_reporter: ProgressReporter<'a>) -> Result<Self> {
+        let default_root = match roots.first() {
+            Some(root) => root.clone(),
+            None => std::env::current_dir().expect("Project root"),
+        };
+        let mut router = Self {
+            packages: HashMap::new(),
+            default_root: default_root.clone(),
+            progress_reporter,
+        };
+        if roots.is_empty() {
+            router.add_package(default_root)?;
+        }
+        for root in roots {
+            router.add_package(root)?;
+        }
+        Ok(router)
+    }
+
+    /// Creates the server for the package at the root, replacing any existing
+    /// one, for example because its `gleam.toml` has changed.
+    pub fn add_package(&mut self, root: PathBuf) -> Result<()> {
+        tracing::info!(root = ?root, "adding_gleam_package");
+        set_current_dir(&root)?;
+
+        // If the directory is not a Gleam project we fall back to a
+        // non-compiling mode that can only do formatting.
+        let config = if paths::root_config().exists() {
+            Some(crate::config::root_config()?)
+        } else {
+            None
+        };
+        let server = LanguageServer::new(config, self.progress_reporter.clone())?;
+        let _ = self.packages.insert(root, server);
+        Ok(())
+    }
+
+    pub fn remove_package(&mut self, root: &Path) {
+        if root != self.default_root {
+            let _ = self.packages.remove(root);
+        }
+    }
+
+    /// The root of the package containing the path, being the innermost one
+    /// as packages may be nested within one another.
+    pub fn root_for(&self, path: &Path) -> PathBuf {
+        self.packages
+            .keys()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .unwrap_or(&self.default_root)
+            .clone()
+    }
+
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.packages.keys().cloned().collect()
+    }
+
+    /// The server for the package at the root, with the working directory
+    /// changed to the root.
+    ///
+    /// If the directory of the package can no longer be used, for example
+    /// because it has been deleted or moved, the package is removed and an
+    /// error is returned.
+    pub fn server(&mut self, root: &Path) -> Result<&mut LanguageServer<'a>> {
+        let root = match self.packages.contains_key(root) {
+            true => root.to_path_buf(),
+            false => self.default_root.clone(),
+        };
+        if let Err(error) = set_current_dir(&root) {
+            self.remove_package(&root);
+            return Err(error);
+        }
+        Ok(self
+            .packages
+            .get_mut(&root)
+            .expect("Default package server"))
+    }
+}
+
+fn set_current_dir(root: &Path) -> Result<()> {
+    std::env::set_current_dir(root).map_err(|error| Error::FileIo {
+        action: FileIoAction::Open,
+        kind: FileKind::Directory,
+        path: root.to_path_buf(),
+        err: Some(error.to_string()),
+    })
+}
+
+/// Finds the roots of all the Gleam packages within the directories, being
+/// those containing a `gleam.toml` file. Build directories and hidden
+/// directories are not searched as they contain copies of packages rather than
+/// packages of the workspace.
+///
+pub fn discover_packages(directories: &[PathBuf]) -> Vec<PathBuf> {
+    let mut roots: Vec<_> = directories
+        .iter()
+        .flat_map(|directory| {
+            walkdir::WalkDir::new(directory)
+                .follow_links(false)
+                .into_iter()
+                .filter_entry(|entry| {
+                    let name = entry.file_name().to_string_lossy();
+                    entry.depth() == 0
+                        || !(name.starts_with('.') || name == "build" || name == "node_modules")
+                })
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_type().is_file() && entry.file_name() == "gleam.toml")
+                .filter_map(|entry| entry.path().parent().map(Path::to_path_buf))
+                .filter_map(|root| root.canonicalize().ok())
+        })
+        .collect();
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+#[test]
+fn discover_packages_skips_build_and_hidden_directories() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path().canonicalize().unwrap();
+    for directory in ["one", "two/nested", "one/build/packages/dep", ".git/dep"] {
+        std::fs::create_dir_all(root.join(directory)).unwrap();
+        std::fs::write(root.join(directory).join("gleam.toml"), "").unwrap();
+    }
+
+    assert_eq!(
+        discover_packages(&[root.clone()]),
+        vec![root.join("one"), root.join("two/nested")]
+    );
+}
@@ -1,23 +1,43 @@
 use super::feedback::{Feedback, FeedbackBookKeeper};
 use super::progress::ProgressReporter;
-use super::{src_span_to_lsp_range, uri_to_module_name, LspProjectCompiler};
+use super::test_runner::{self, RUN_TESTS_COMMAND};
+use super::{path_to_uri, src_span_to_lsp_range, uri_to_module_name, LspProjectCompiler};
 use crate::fs::ProjectIO;
 use gleam_core::Warning;
 use gleam_core::{ast::Import, io::FileSystemReader, language_server::FileSystemProxy};
 use gleam_core::{
-    ast::Statement,
-    build::{Located, Module},
+    ast::{SrcSpan, Statement},
+    build::{Located, Module, Outcome, Target},
     config::PackageConfig,
+    diagnostic::{self, Diagnostic, Level},
+    language_server::{
+        call_hierarchy::{self, CallHierarchyFunction},
+        code_action::{self, CodeAction, CodeActionKind},
+        code_lens,
+        completion::{self, Completion, CompletionKind, Documentation},
+        definition,
+        folding_range::{self, FoldingRangeKind},
+        formatting, hover, inlay_hints,
+        reference::{self, Reference, ReferenceKind, Symbol},
+        rename, selection_range,
+        semantic_tokens::{self, TokenModifier, TokenType},
+        signature_help::{self, SignatureHelp},
+        symbols::{self, DocumentSymbol, SymbolKind},
+    },
     line_numbers::LineNumbers,
-    type_::pretty::Printer,
-    Error, Result,
+    paths, type_, Error, Result,
 };
+use itertools::Itertools;
 use lsp::DidOpenTextDocumentParams;
 use lsp_types::{
     self as lsp, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
     DidSaveTextDocumentParams, Hover, HoverContents, MarkedString, Position, Range, TextEdit, Url,
 };
-use std::path::{Path, PathBuf};
+use smol_str::SmolStr;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Response<T> {
@@ -42,6 +62,11 @@ pub struct LanguageServer<'a> {
     feedback: FeedbackBookKeeper,
     modules_compiled_since_last_feedback: Vec<PathBuf>,
 
+    /// The error from the last compilation, if it failed, and the warnings
+    /// for each module, kept so that code actions can offer fixes for them.
+    compile_error: Option<Error>,
+    warnings: HashMap<Url, Vec<Warning>>,
+
     // Used to publish progress notifications to the client without waiting for
     // the usual request-response loop.
     progress_reporter: ProgressReporter<'a>,
@@ -55,6 +80,8 @@ impl<'a> LanguageServer<'a> {
         let project_root = std::env::current_dir().expect("Project root");
         let mut language_server = Self {
             modules_compiled_since_last_feedback: vec![],
+            compile_error: None,
+            warnings: HashMap::new(),
             feedback: FeedbackBookKeeper::default(),
             fs_proxy: FileSystemProxy::new(ProjectIO::new()),
             compiler: None,
@@ -73,25 +100,47 @@ impl<'a> LanguageServer<'a> {
     /// Compile the project if we are in one. Otherwise do nothing.
     fn compile(&mut self) -> Result<(), Error> {
         self.progress_reporter.started();
-        let result = match self.compiler.as_mut() {
+        let outcome = match self.compiler.as_mut() {
             Some(compiler) => compiler.compile(),
-            None => Ok(vec![]),
+            None => Outcome::Ok(vec![]),
         };
         self.progress_reporter.finished();
 
-        let modules = result?;
-        self.modules_compiled_since_last_feedback
-            .extend(modules.into_iter());
-
-        Ok(())
+        // The modules compiled before any error are recorded so that their
+        // diagnostics are updated along with the error.
+        let (modules, error) = match outcome {
+            Outcome::Ok(modules) => (modules, None),
+            Outcome::PartialFailure(modules, error) => (modules, Some(error)),
+            Outcome::TotalFailure(error) => (vec![], Some(error)),
+        };
+        self.modules_compiled_since_last_feedback.extend(modules);
+        self.compile_error = error.clone();
+        match error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
     }
 
     fn take_warnings(&mut self) -> Vec<Warning> {
-        if let Some(compiler) = self.compiler.as_mut() {
+        let warnings = if let Some(compiler) = self.compiler.as_mut() {
             compiler.warnings.take()
         } else {
             vec![]
+        };
+
+        // The warnings of any module that has been compiled are replaced by
+        // its new warnings.
+        for path in &self.modules_compiled_since_last_feedback {
+            let _ = self.warnings.remove(&path_to_uri(path.clone()));
         }
+        for warning in &warnings {
+            let Warning::Type { path, .. } = warning;
+            self.warnings
+                .entry(path_to_uri(path.clone()))
+                .or_default()
+                .push(warning.clone());
+        }
+        warnings
     }
 
     pub fn create_new_compiler(&mut self) -> Result<(), Error> {
@@ -205,37 +254,827 @@ impl<'a> LanguageServer<'a> {
         })
     }
 
+    pub fn goto_type_definition(
+        &mut self,
+        params: lsp::GotoDefinitionParams,
+    ) -> Response<Option<lsp::Location>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let (compiler, module) = match (
+                this.compiler.as_ref(),
+                this.module_for_uri(&params.text_document.uri),
+            ) {
+                (Some(compiler), Some(module)) => (compiler, module),
+                _ => return Ok(None),
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let byte_index =
+                line_numbers.byte_index(params.position.line, params.position.character);
+
+            let interfaces = interfaces(compiler);
+            let definition = match definition::type_definition(module, byte_index, &interfaces) {
+                Some(definition) => definition,
+                None => return Ok(None),
+            };
+            let (uri, line_numbers) =
+                match this.module_source(&definition.module, &definition.package) {
+                    Some(source) => source,
+                    None => return Ok(None),
+                };
+            let range = src_span_to_lsp_range(definition.location, &line_numbers);
+            Ok(Some(lsp::Location { uri, range }))
+        })
+    }
+
+    /// Go to the Erlang or JavaScript implementation of an external function,
+    /// if it is in a native file of the project.
+    pub fn goto_implementation(
+        &mut self,
+        params: lsp::GotoDefinitionParams,
+    ) -> Response<Option<lsp::Location>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let (_, node) = match this.node_at_position(&params) {
+                Some(found) => found,
+                None => return Ok(None),
+            };
+            let (native_module, function) = match node {
+                Located::Statement(Statement::ExternalFunction(external)) => {
+                    (&external.module, &external.fun)
+                }
+                _ => return Ok(None),
+            };
+            let module = match this.module_for_uri(&params.text_document.uri) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
+
+            let is_javascript = [".mjs", ".js", ".ts"]
+                .iter()
+                .any(|extension| native_module.ends_with(extension));
+            let (target, path) = if is_javascript {
+                // JavaScript modules are imported relative to the Gleam module
+                let directory = module.input_path.parent().unwrap_or(Path::new(""));
+                (
+                    Target::JavaScript,
+                    Some(directory.join(native_module.as_str())),
+                )
+            } else {
+                // Erlang modules may be anywhere within the source directories
+                let file_name = format!("{native_module}.erl");
+                let path = [paths::src(), paths::test()].iter().find_map(|directory| {
+                    find_file(&this.project_root.join(directory), &file_name)
+                });
+                (Target::Erlang, path)
+            };
+
+            let path = match path {
+                Some(path) => path,
+                None => return Ok(None),
+            };
+            let code = match this.fs_proxy.read(&path) {
+                Ok(code) => code,
+                Err(_) => return Ok(None),
+            };
+            let location = match definition::native_function_location(&code, function, target) {
+                Some(location) => location,
+                None => return Ok(None),
+            };
+            Ok(Some(lsp::Location {
+                uri: path_to_uri(path),
+                range: src_span_to_lsp_range(location, &LineNumbers::new(&code)),
+            }))
+        })
+    }
+
+    pub fn find_references(
+        &mut self,
+        params: lsp::ReferenceParams,
+    ) -> Response<Option<Vec<lsp::Location>>> {
+        self.respond(|this| {
+            let include_declaration = params.context.include_declaration;
+            let params = params.text_document_position;
+            let symbol = match this.reference_at_position(&params) {
+                Some(reference) => reference.symbol,
+                None => return Ok(None),
+            };
+
+            let locations = this
+                .project_references(&symbol)
+                .into_iter()
+                .filter(|(_, reference)| {
+                    include_declaration || reference.kind != ReferenceKind::Definition
+                })
+                .filter_map(|(module, reference)| this.location(module, reference.location))
+                .collect();
+            Ok(Some(locations))
+        })
+    }
+
+    pub fn document_highlight(
+        &mut self,
+        params: lsp::DocumentHighlightParams,
+    ) -> Response<Option<Vec<lsp::DocumentHighlight>>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let module = match this.module_for_uri(&params.text_document.uri) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
+            let symbol = match this.reference_at_position(&params) {
+                Some(reference) => reference.symbol,
+                None => return Ok(None),
+            };
+
+            let line_numbers = LineNumbers::new(&module.code);
+            let highlights = reference::find_references(module, &symbol)
+                .into_iter()
+                .map(|reference| lsp::DocumentHighlight {
+                    range: src_span_to_lsp_range(reference.location, &line_numbers),
+                    kind: Some(match reference.kind {
+                        ReferenceKind::Definition => lsp::DocumentHighlightKind::WRITE,
+                        ReferenceKind::Import | ReferenceKind::Usage => {
+                            lsp::DocumentHighlightKind::READ
+                        }
+                    }),
+                })
+                .collect();
+            Ok(Some(highlights))
+        })
+    }
+
+    pub fn prepare_rename(
+        &mut self,
+        params: lsp::TextDocumentPositionParams,
+    ) -> Response<Option<lsp::PrepareRenameResponse>> {
+        self.respond(|this| {
+            let (compiler, module) = match (
+                this.compiler.as_ref(),
+                this.module_for_uri(&params.text_document.uri),
+            ) {
+                (Some(compiler), Some(module)) => (compiler, module),
+                _ => return Ok(None),
+            };
+            let range = this
+                .reference_at_position(&params)
+                .and_then(|reference| {
+                    rename::prepare_rename(compiler.modules.values(), module, &reference)
+                })
+                .map(|span| src_span_to_lsp_range(span, &LineNumbers::new(&module.code)));
+            Ok(range.map(lsp::PrepareRenameResponse::Range))
+        })
+    }
+
+    pub fn rename(&mut self, params: lsp::RenameParams) -> Response<Option<lsp::WorkspaceEdit>> {
+        self.respond(|this| {
+            let position = params.text_document_position;
+            let (compiler, reference) = match (
+                this.compiler.as_ref(),
+                this.reference_at_position(&position),
+            ) {
+                (Some(compiler), Some(reference)) => (compiler, reference),
+                _ => return Ok(None),
+            };
+
+            let modules = compiler
+                .modules
+                .values()
+                .sorted_by_key(|module| module.name.as_str());
+            let renames = rename::rename(modules, &reference.symbol, &params.new_name)?;
+
+            let mut changes = HashMap::new();
+            for module_rename in renames {
+                let source = match compiler.sources.get(module_rename.module.as_str()) {
+                    Some(source) => source,
+                    None => continue,
+                };
+                let edits = module_rename
+                    .edits
+                    .into_iter()
+                    .map(|span| TextEdit {
+                        range: src_span_to_lsp_range(span, &source.line_numbers),
+                        new_text: params.new_name.clone(),
+                    })
+                    .collect();
+                let _ = changes.insert(path_to_uri(PathBuf::from(&source.path)), edits);
+            }
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: Some(changes),
+                document_changes: None,
+                change_annotations: None,
+            }))
+        })
+    }
+
+    pub fn document_symbol(
+        &mut self,
+        params: lsp::DocumentSymbolParams,
+    ) -> Response<Option<lsp::DocumentSymbolResponse>> {
+        self.respond(|this| {
+            let module = match this.module_for_uri(&params.text_document.uri) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let symbols = symbols::document_symbols(module)
+                .into_iter()
+                .map(|symbol| lsp_document_symbol(symbol, &line_numbers))
+                .collect();
+            Ok(Some(lsp::DocumentSymbolResponse::Nested(symbols)))
+        })
+    }
+
+    pub fn workspace_symbol(
+        &mut self,
+        params: lsp::WorkspaceSymbolParams,
+    ) -> Response<Option<Vec<lsp::SymbolInformation>>> {
+        self.respond(|this| {
+            let compiler = match this.compiler.as_ref() {
+                Some(compiler) => compiler,
+                None => return Ok(None),
+            };
+
+            // The modules of the root package are not kept in the importable
+            // modules between compilations, so they are taken from the typed
+            // modules instead.
+            let root_modules = compiler
+                .modules
+                .values()
+                .map(|module| &module.ast.type_info);
+            let dependency_modules = compiler
+                .project_compiler
+                .get_importable_modules()
+                .values()
+                .filter(|module| {
+                    !module.package.is_empty()
+                        && !compiler.modules.contains_key(module.name.as_str())
+                });
+            let found =
+                symbols::workspace_symbols(root_modules.chain(dependency_modules), &params.query);
+
+            let mut sources = HashMap::new();
+            let symbols = found
+                .into_iter()
+                .filter_map(|symbol| {
+                    let (uri, line_numbers) = sources
+                        .entry(symbol.module.clone())
+                        .or_insert_with(|| this.module_source(&symbol.module, &symbol.package))
+                        .as_ref()?;
+                    #[allow(deprecated)]
+                    Some(lsp::SymbolInformation {
+                        name: symbol.name.to_string(),
+                        kind: lsp_symbol_kind(symbol.kind),
+                        tags: None,
+                        deprecated: None,
+                        location: lsp::Location {
+                            uri: uri.clone(),
+                            range: src_span_to_lsp_range(symbol.location, line_numbers),
+                        },
+                        container_name: Some(symbol.module.to_string()),
+                    })
+                })
+                .collect();
+            Ok(Some(symbols))
+        })
+    }
+
     // TODO: function & constructor labels
-    // TODO: module types (including private)
-    // TODO: module values (including private)
-    // TODO: locally defined variables
-    // TODO: imported module values
-    // TODO: imported module types
-    // TODO: record accessors
     pub fn completion(
         &mut self,
         params: lsp::CompletionParams,
     ) -> Response<Option<Vec<lsp::CompletionItem>>> {
         self.respond(|this| {
-            let found = this
-                .node_at_position(&params.text_document_position)
-                .map(|(_, found)| found);
-
-            Ok(match found {
-                // TODO: test
-                None | Some(Located::Statement(Statement::Import(Import { .. }))) => {
-                    this.completion_for_import()
+            let params = params.text_document_position;
+            let path = PathBuf::from(params.text_document.uri.path());
+            let src = this.fs_proxy.read(&path)?;
+            let line_numbers = LineNumbers::new(&src);
+            let cursor = line_numbers.byte_index(params.position.line, params.position.character);
+
+            // The module may not have been compiled yet, or may no longer
+            // compile due to the edits being made, in which case the
+            // completions are based on the last successfully compiled version.
+            let line_start = src
+                .get(..cursor as usize)
+                .and_then(|before| before.rfind('\n'))
+                .map(|index| index + 1)
+                .unwrap_or(0);
+            let line = src.get(line_start..cursor as usize).unwrap_or_default();
+            let module = this.module_for_uri(&params.text_document.uri);
+            let (compiler, module) = match (this.compiler.as_ref(), module) {
+                _ if line.starts_with("import") => return Ok(this.completion_for_import()),
+                (Some(compiler), Some(module)) => (compiler, module),
+                _ => return Ok(None),
+            };
+
+            let interfaces = interfaces(compiler);
+            let documentation = this.imported_documentation(module, &interfaces);
+
+            let items = completion::completions(module, &src, cursor, &interfaces, &documentation)
+                .into_iter()
+                .map(lsp_completion_item)
+                .collect();
+            Ok(Some(items))
+        })
+    }
+
+    pub fn code_action(
+        &mut self,
+        params: lsp::CodeActionParams,
+    ) -> Response<Option<Vec<lsp::CodeActionOrCommand>>> {
+        self.respond(|this| {
+            let uri = &params.text_document.uri;
+            let mut actions = vec![];
+
+            if let Some(Error::Type { path, src, errors }) = &this.compile_error {
+                if path_to_uri(path.clone()) == *uri {
+                    let fixes = errors
+                        .iter()
+                        .flat_map(|error| code_action::error_actions(error, src))
+                        .collect();
+                    actions.push((src, fixes));
+                }
+            }
+
+            let warnings = this
+                .warnings
+                .get(uri)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            if let Some(Warning::Type { src, .. }) = warnings.first() {
+                let warnings: Vec<_> = warnings
+                    .iter()
+                    .map(|Warning::Type { warning, .. }| warning.clone())
+                    .collect();
+                actions.push((src, code_action::warning_actions(&warnings, src)));
+            }
+
+            let actions = actions
+                .into_iter()
+                .flat_map(|(src, actions)| {
+                    let line_numbers = LineNumbers::new(src);
+                    actions
+                        .into_iter()
+                        .filter(|action| {
+                            let range = src_span_to_lsp_range(action.location, &line_numbers);
+                            range.start <= params.range.end && params.range.start <= range.end
+                        })
+                        .map(|action| lsp_code_action(action, uri, &line_numbers))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            Ok(Some(actions))
+        })
+    }
+
+    pub fn inlay_hint(
+        &mut self,
+        params: lsp::InlayHintParams,
+    ) -> Response<Option<Vec<lsp::InlayHint>>> {
+        self.respond(|this| {
+            let uri = &params.text_document.uri;
+            let module = match this.module_for_uri(uri) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
+
+            // The hints would be shown in the wrong places if the module has
+            // been edited since it was last compiled.
+            let src = this.fs_proxy.read(Path::new(uri.path()))?;
+            if src != module.code.as_str() {
+                return Ok(None);
+            }
+
+            let line_numbers = LineNumbers::new(&module.code);
+            let hints = inlay_hints::inlay_hints(module)
+                .into_iter()
+                .map(|hint| {
+                    let location = SrcSpan::new(hint.position, hint.position);
+                    lsp::InlayHint {
+                        position: src_span_to_lsp_range(location, &line_numbers).start,
+                        label: lsp::InlayHintLabel::String(hint.label),
+                        kind: Some(lsp::InlayHintKind::TYPE),
+                        text_edits: None,
+                        tooltip: None,
+                        padding_left: Some(hint.padding_left),
+                        padding_right: None,
+                    }
+                })
+                .filter(|hint| {
+                    params.range.start <= hint.position && hint.position <= params.range.end
+                })
+                .collect();
+            Ok(Some(hints))
+        })
+    }
+
+    pub fn semantic_tokens_full(
+        &mut self,
+        params: lsp::SemanticTokensParams,
+    ) -> Response<Option<lsp::SemanticTokensResult>> {
+        self.respond(|this| {
+            let tokens = this.semantic_tokens(&params.text_document.uri, None)?;
+            Ok(tokens.map(lsp::SemanticTokensResult::Tokens))
+        })
+    }
+
+    pub fn semantic_tokens_range(
+        &mut self,
+        params: lsp::SemanticTokensRangeParams,
+    ) -> Response<Option<lsp::SemanticTokensRangeResult>> {
+        self.respond(|this| {
+            let tokens = this.semantic_tokens(&params.text_document.uri, Some(params.range))?;
+            Ok(tokens.map(lsp::SemanticTokensRangeResult::Tokens))
+        })
+    }
+
+    fn semantic_tokens(
+        &self,
+        uri: &Url,
+        range: Option<Range>,
+    ) -> Result<Option<lsp::SemanticTokens>> {
+        let (compiler, module) = match (self.compiler.as_ref(), self.module_for_uri(uri)) {
+            (Some(compiler), Some(module)) => (compiler, module),
+            _ => return Ok(None),
+        };
+
+        // As with inlay hints the tokens would be in the wrong places if the
+        // module has been edited since it was last compiled.
+        let src = self.fs_proxy.read(Path::new(uri.path()))?;
+        if src != module.code.as_str() {
+            return Ok(None);
+        }
+
+        let line_numbers = LineNumbers::new(&module.code);
+        let tokens = semantic_tokens::semantic_tokens(module, &interfaces(compiler));
+        let mut data = vec![];
+        let mut previous = Position::new(0, 0);
+        for token in tokens {
+            for location in token_lines(&module.code, token.location) {
+                let position = src_span_to_lsp_range(location, &line_numbers).start;
+                let outside =
+                    range.is_some_and(|range| position < range.start || range.end <= position);
+                if outside {
+                    continue;
                 }
+                let delta_start = if position.line == previous.line {
+                    position.character - previous.character
+                } else {
+                    position.character
+                };
+                data.push(lsp::SemanticToken {
+                    delta_line: position.line - previous.line,
+                    delta_start,
+                    length: location.end - location.start,
+                    token_type: lsp_token_type(token.type_),
+                    token_modifiers_bitset: lsp_token_modifiers(&token.modifiers),
+                });
+                previous = position;
+            }
+        }
+        Ok(Some(lsp::SemanticTokens {
+            result_id: None,
+            data,
+        }))
+    }
 
-                // TODO: autocompletion for other statements
-                Some(Located::Statement(_expression)) => None,
+    pub fn folding_range(
+        &mut self,
+        params: lsp::FoldingRangeParams,
+    ) -> Response<Option<Vec<lsp::FoldingRange>>> {
+        self.respond(|this| {
+            let module = match this.module_for_uri(&params.text_document.uri) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
 
-                // TODO: autocompletion for expressions
-                Some(Located::Expression(_expression)) => None,
+            let line_numbers = LineNumbers::new(&module.code);
+            let ranges = folding_range::folding_ranges(module)
+                .into_iter()
+                .map(|folding| {
+                    let range = src_span_to_lsp_range(folding.location, &line_numbers);
+                    lsp::FoldingRange {
+                        start_line: range.start.line,
+                        start_character: None,
+                        end_line: range.end.line,
+                        end_character: None,
+                        kind: Some(match folding.kind {
+                            FoldingRangeKind::Region => lsp::FoldingRangeKind::Region,
+                            FoldingRangeKind::Imports => lsp::FoldingRangeKind::Imports,
+                            FoldingRangeKind::Comment => lsp::FoldingRangeKind::Comment,
+                        }),
+                    }
+                })
+                .collect();
+            Ok(Some(ranges))
+        })
+    }
+
+    pub fn selection_range(
+        &mut self,
+        params: lsp::SelectionRangeParams,
+    ) -> Response<Option<Vec<lsp::SelectionRange>>> {
+        self.respond(|this| {
+            let module = match this.module_for_uri(&params.text_document.uri) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
+
+            let line_numbers = LineNumbers::new(&module.code);
+            let selections = params
+                .positions
+                .iter()
+                .map(|position| {
+                    let byte_index = line_numbers.byte_index(position.line, position.character);
+                    // Each range is given with its parent, so they are built
+                    // from the outermost inwards.
+                    let mut selection = None;
+                    for location in selection_range::selection_ranges(module, byte_index)
+                        .into_iter()
+                        .rev()
+                    {
+                        selection = Some(lsp::SelectionRange {
+                            range: src_span_to_lsp_range(location, &line_numbers),
+                            parent: selection.map(Box::new),
+                        });
+                    }
+                    selection.unwrap_or(lsp::SelectionRange {
+                        range: Range::new(*position, *position),
+                        parent: None,
+                    })
+                })
+                .collect();
+            Ok(Some(selections))
+        })
+    }
+
+    pub fn prepare_call_hierarchy(
+        &mut self,
+        params: lsp::CallHierarchyPrepareParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyItem>>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let (compiler, module) = match (
+                this.compiler.as_ref(),
+                this.module_for_uri(&params.text_document.uri),
+            ) {
+                (Some(compiler), Some(module)) => (compiler, module),
+                _ => return Ok(None),
+            };
+            let line_numbers = LineNumbers::new(&module.code);
+            let byte_index =
+                line_numbers.byte_index(params.position.line, params.position.character);
+
+            let function = call_hierarchy::function_at(module, byte_index, &interfaces(compiler));
+            Ok(function
+                .and_then(|function| this.call_hierarchy_item(function))
+                .map(|item| vec![item]))
+        })
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        params: lsp::CallHierarchyIncomingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyIncomingCall>>> {
+        self.respond(|this| {
+            let compiler = match this.compiler.as_ref() {
+                Some(compiler) => compiler,
+                None => return Ok(None),
+            };
+            let function_module = match call_hierarchy_item_module(&params.item) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
+
+            let mut incoming = vec![];
+            for module in compiler.modules.values() {
+                let line_numbers = LineNumbers::new(&module.code);
+                for calls in
+                    call_hierarchy::incoming_calls(module, function_module, &params.item.name)
+                {
+                    let from_ranges = calls
+                        .locations
+                        .iter()
+                        .map(|location| src_span_to_lsp_range(*location, &line_numbers))
+                        .collect();
+                    if let Some(from) = this.call_hierarchy_item(calls.function) {
+                        incoming.push(lsp::CallHierarchyIncomingCall { from, from_ranges });
+                    }
+                }
+            }
+            Ok(Some(incoming))
+        })
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        params: lsp::CallHierarchyOutgoingCallsParams,
+    ) -> Response<Option<Vec<lsp::CallHierarchyOutgoingCall>>> {
+        self.respond(|this| {
+            // Only the modules of the root package are compiled by the
+            // language server, so there are no calls from other packages.
+            let (compiler, module) = match (
+                this.compiler.as_ref(),
+                call_hierarchy_item_module(&params.item),
+            ) {
+                (Some(compiler), Some(module)) => match compiler.modules.get(module) {
+                    Some(module) => (compiler, module),
+                    None => return Ok(None),
+                },
+                _ => return Ok(None),
+            };
+
+            let line_numbers = LineNumbers::new(&module.code);
+            let outgoing =
+                call_hierarchy::outgoing_calls(module, &params.item.name, &interfaces(compiler))
+                    .into_iter()
+                    .filter_map(|calls| {
+                        let from_ranges = calls
+                            .locations
+                            .iter()
+                            .map(|location| src_span_to_lsp_range(*location, &line_numbers))
+                            .collect();
+                        let to = this.call_hierarchy_item(calls.function)?;
+                        Some(lsp::CallHierarchyOutgoingCall { to, from_ranges })
+                    })
+                    .collect();
+            Ok(Some(outgoing))
+        })
+    }
+
+    fn call_hierarchy_item(
+        &self,
+        function: CallHierarchyFunction,
+    ) -> Option<lsp::CallHierarchyItem> {
+        let (uri, line_numbers) = self.module_source(&function.module, &function.package)?;
+        let range = src_span_to_lsp_range(function.location, &line_numbers);
+        Some(lsp::CallHierarchyItem {
+            name: function.name.to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: Some(function.module.to_string()),
+            uri,
+            range,
+            selection_range: range,
+            data: Some(serde_json::Value::String(function.module.to_string())),
+        })
+    }
+
+    pub fn code_lens(
+        &mut self,
+        params: lsp::CodeLensParams,
+    ) -> Response<Option<Vec<lsp::CodeLens>>> {
+        self.respond(|this| {
+            let module = match this.module_for_uri(&params.text_document.uri) {
+                Some(module) => module,
+                None => return Ok(None),
+            };
+
+            let line_numbers = LineNumbers::new(&module.code);
+            let lenses = code_lens::test_lenses(module)
+                .into_iter()
+                .map(|lens| lsp::CodeLens {
+                    range: src_span_to_lsp_range(lens.location, &line_numbers),
+                    command: Some(lsp::Command {
+                        title: lens.title.into(),
+                        command: RUN_TESTS_COMMAND.into(),
+                        arguments: Some(vec![
+                            serde_json::json!(params.text_document.uri),
+                            serde_json::json!(lens.functions),
+                        ]),
+                    }),
+                    data: None,
+                })
+                .collect();
+            Ok(Some(lenses))
+        })
+    }
+
+    pub fn execute_command(
+        &mut self,
+        params: lsp::ExecuteCommandParams,
+    ) -> Response<Option<serde_json::Value>> {
+        let result = match run_tests_arguments(&params) {
+            Some((uri, functions)) => self.run_tests(&uri, &functions),
+            None => Ok(None),
+        };
+
+        match result {
+            Ok(Some((path, failures, summary))) => {
+                let warnings = self
+                    .warnings
+                    .get(&path_to_uri(path.clone()))
+                    .cloned()
+                    .unwrap_or_default();
+                Response {
+                    payload: Some(Some(summary)),
+                    feedback: self.feedback.test_failures(path, warnings, failures),
+                }
+            }
+            Ok(None) => Response {
+                payload: Some(None),
+                feedback: Feedback::default(),
+            },
+            Err(e) => Response {
+                payload: None,
+                feedback: self
+                    .feedback
+                    .diagnostics_with_error(e, std::iter::empty(), vec![]),
+            },
+        }
+    }
+
+    /// Build the project and run the test functions of a module, returning
+    /// a diagnostic for each test that failed and a summary of the results.
+    fn run_tests(
+        &self,
+        uri: &Url,
+        functions: &[SmolStr],
+    ) -> Result<Option<(PathBuf, Vec<Diagnostic>, serde_json::Value)>> {
+        let (config, module) = match (self.config.as_ref(), self.module_for_uri(uri)) {
+            (Some(config), Some(module)) => (config, module),
+            _ => return Ok(None),
+        };
+
+        let results = test_runner::run_tests(config, &module.name, functions)?;
+        let tests: HashMap<_, _> = code_lens::test_functions(module).into_iter().collect();
+        let (passed, failed): (Vec<_>, Vec<_>) =
+            results.into_iter().partition(|result| result.passed);
+
+        let failures = failed
+            .iter()
+            .filter_map(|result| {
+                let location = tests.get(&result.function)?;
+                Some(Diagnostic {
+                    title: format!("Test `{}` failed", result.function),
+                    text: result.output.trim_end().into(),
+                    level: Level::Error,
+                    location: Some(diagnostic::Location {
+                        src: module.code.clone(),
+                        path: module.input_path.clone(),
+                        label: diagnostic::Label {
+                            text: None,
+                            span: *location,
+                        },
+                        extra_labels: vec![],
+                    }),
+                    hint: None,
+                })
             })
+            .collect();
+        let summary = serde_json::json!({
+            "passed": passed.iter().map(|result| &result.function).collect_vec(),
+            "failed": failed.iter().map(|result| &result.function).collect_vec(),
+        });
+        Ok(Some((module.input_path.clone(), failures, summary)))
+    }
+
+    pub fn signature_help(
+        &mut self,
+        params: lsp::SignatureHelpParams,
+    ) -> Response<Option<lsp::SignatureHelp>> {
+        self.respond(|this| {
+            let params = params.text_document_position_params;
+            let path = PathBuf::from(params.text_document.uri.path());
+            let src = this.fs_proxy.read(&path)?;
+            let line_numbers = LineNumbers::new(&src);
+            let cursor = line_numbers.byte_index(params.position.line, params.position.character);
+
+            // As with completion the call is found in the current source code,
+            // and the function being called in the last compiled version.
+            let module = this.module_for_uri(&params.text_document.uri);
+            let (compiler, module) = match (this.compiler.as_ref(), module) {
+                (Some(compiler), Some(module)) => (compiler, module),
+                _ => return Ok(None),
+            };
+            let interfaces = interfaces(compiler);
+            let documentation = this.imported_documentation(module, &interfaces);
+            let help =
+                signature_help::signature_help(module, &src, cursor, &interfaces, &documentation);
+            Ok(help.map(lsp_signature_help))
         })
     }
 
+    /// The documentation of each of the modules imported by a module.
+    fn imported_documentation(
+        &self,
+        module: &Module,
+        interfaces: &HashMap<SmolStr, &type_::Module>,
+    ) -> HashMap<SmolStr, Documentation> {
+        module
+            .ast
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Import(Import { module, .. }) => {
+                    let interface = interfaces.get(module)?;
+                    let documentation = self.documentation(module, &interface.package);
+                    Some((module.clone(), documentation))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
     fn respond<T>(&mut self, handler: impl FnOnce(&Self) -> Result<T>) -> Response<T> {
         let result = handler(self);
         let warnings = self.take_warnings();
@@ -279,6 +1118,46 @@ impl<'a> LanguageServer<'a> {
         })
     }
 
+    pub fn range_format(
+        &mut self,
+        params: lsp::DocumentRangeFormattingParams,
+    ) -> Response<Vec<TextEdit>> {
+        self.respond(|this| {
+            let path = Path::new(params.text_document.uri.path());
+            let src: SmolStr = this.fs_proxy.read(path)?.into();
+            let line_numbers = LineNumbers::new(&src);
+            let start =
+                line_numbers.byte_index(params.range.start.line, params.range.start.character);
+            let end = line_numbers.byte_index(params.range.end.line, params.range.end.character);
+            let edits = formatting::format_range(&src, path, SrcSpan::new(start, end))?;
+            Ok(lsp_text_edits(edits, &line_numbers))
+        })
+    }
+
+    pub fn on_type_format(
+        &mut self,
+        params: lsp::DocumentOnTypeFormattingParams,
+    ) -> Response<Option<Vec<TextEdit>>> {
+        self.respond(|this| {
+            let params = params.text_document_position;
+            let path = Path::new(params.text_document.uri.path());
+            let src: SmolStr = this.fs_proxy.read(path)?.into();
+            let line_numbers = LineNumbers::new(&src);
+            // The position is just after the `}` typed, so the statement to
+            // format is the one containing the character before it.
+            let byte_index = line_numbers
+                .byte_index(params.position.line, params.position.character)
+                .saturating_sub(1);
+
+            // The code is likely to be incomplete while typing, so rather than
+            // reporting an error nothing is formatted.
+            let location = SrcSpan::new(byte_index, byte_index);
+            Ok(formatting::format_range(&src, path, location)
+                .ok()
+                .map(|edits| lsp_text_edits(edits, &line_numbers)))
+        })
+    }
+
     fn completion_for_import(&self) -> Option<Vec<lsp::CompletionItem>> {
         let compiler = self.compiler.as_ref()?;
         // TODO: Test
@@ -311,27 +1190,42 @@ impl<'a> LanguageServer<'a> {
     pub fn hover(&mut self, params: lsp::HoverParams) -> Response<Option<Hover>> {
         self.respond(|this| {
             let params = params.text_document_position_params;
-
-            let (line_numbers, found) = match this.node_at_position(&params) {
-                Some(value) => value,
-                None => return Ok(None),
+            let (compiler, module) = match (
+                this.compiler.as_ref(),
+                this.module_for_uri(&params.text_document.uri),
+            ) {
+                (Some(compiler), Some(module)) => (compiler, module),
+                _ => return Ok(None),
             };
+            let line_numbers = LineNumbers::new(&module.code);
+            let byte_index =
+                line_numbers.byte_index(params.position.line, params.position.character);
 
-            let expression = match found {
-                Located::Expression(expression) => expression,
-                Located::Statement(_) => return Ok(None),
+            let interfaces = interfaces(compiler);
+            let documentation = |name: &str| {
+                let interface = interfaces.get(name)?;
+                Some(this.documentation(name, &interface.package))
+            };
+            let hover = match hover::hover(module, byte_index, &interfaces, &documentation) {
+                Some(hover) => hover,
+                None => return Ok(None),
             };
 
-            // Show the type of the hovered node to the user
-            let type_ = Printer::new().pretty_print(expression.type_().as_ref(), 0);
-            let contents = format!(
+            // Show the type or definition of the hovered node to the user,
+            // followed by its documentation
+            let mut contents = format!(
                 "```gleam
-{type_}
-```"
+{}
+```",
+                hover.code
             );
+            if let Some(documentation) = hover.documentation {
+                contents.push_str("\n\n");
+                contents.push_str(documentation.trim_end());
+            }
             Ok(Some(Hover {
                 contents: HoverContents::Scalar(MarkedString::String(contents)),
-                range: Some(src_span_to_lsp_range(expression.location(), &line_numbers)),
+                range: Some(src_span_to_lsp_range(hover.location, &line_numbers)),
             }))
         })
     }
@@ -349,11 +1243,308 @@ impl<'a> LanguageServer<'a> {
         Some((line_numbers, node))
     }
 
+    fn reference_at_position(&self, params: &lsp::TextDocumentPositionParams) -> Option<Reference> {
+        let module = self.module_for_uri(&params.text_document.uri)?;
+        let line_numbers = LineNumbers::new(&module.code);
+        let byte_index = line_numbers.byte_index(params.position.line, params.position.character);
+        reference::reference_at(module, byte_index)
+    }
+
+    /// All the occurrences of the symbol in the modules of the root package,
+    /// along with the name of the module they occur in.
+    fn project_references(&self, symbol: &Symbol) -> Vec<(&str, Reference)> {
+        let compiler = match self.compiler.as_ref() {
+            Some(compiler) => compiler,
+            None => return vec![],
+        };
+        compiler
+            .modules
+            .iter()
+            .sorted_by_key(|(name, _)| name.as_str())
+            .flat_map(|(name, module)| {
+                reference::find_references(module, symbol)
+                    .into_iter()
+                    .map(move |reference| (name.as_str(), reference))
+            })
+            .collect()
+    }
+
+    fn location(&self, module: &str, span: SrcSpan) -> Option<lsp::Location> {
+        let source = self.compiler.as_ref()?.sources.get(module)?;
+        Some(lsp::Location {
+            uri: path_to_uri(PathBuf::from(&source.path)),
+            range: src_span_to_lsp_range(span, &source.line_numbers),
+        })
+    }
+
+    /// The URI and line numbers of the source file of a module, which may
+    /// belong to the root package or to one of its dependencies.
+    fn module_source(&self, module: &str, package: &str) -> Option<(Url, LineNumbers)> {
+        if let Some(source) = self
+            .compiler
+            .as_ref()
+            .and_then(|compiler| compiler.sources.get(module))
+        {
+            let uri = path_to_uri(PathBuf::from(&source.path));
+            return Some((uri, source.line_numbers.clone()));
+        }
+
+        let path = self
+            .project_root
+            .join(paths::build_deps_package_src(package))
+            .join(format!("{module}.gleam"));
+        let code = self.fs_proxy.read(&path).ok()?;
+        Some((path_to_uri(path), LineNumbers::new(&code)))
+    }
+
+    /// The doc comments of the definitions of a module, which may belong to
+    /// the root package or to one of its dependencies. These are cached as
+    /// the source code of dependencies has to be read and parsed to find them.
+    fn documentation(&self, module: &str, package: &str) -> Documentation {
+        let compiler = match self.compiler.as_ref() {
+            Some(compiler) => compiler,
+            None => return Documentation::default(),
+        };
+        if let Some(documentation) = compiler.documentation.borrow().get(module) {
+            return documentation.clone();
+        }
+
+        let documentation = match compiler.modules.get(module) {
+            Some(module) => Documentation::from_module(module),
+            None => {
+                let path = self
+                    .project_root
+                    .join(paths::build_deps_package_src(package))
+                    .join(format!("{module}.gleam"));
+                match self.fs_proxy.read(&path) {
+                    Ok(code) => Documentation::parse(&code),
+                    Err(_) => Documentation::default(),
+                }
+            }
+        };
+        _ = compiler
+            .documentation
+            .borrow_mut()
+            .insert(module.into(), documentation.clone());
+        documentation
+    }
+
     fn module_for_uri(&self, uri: &Url) -> Option<&Module> {
         self.compiler.as_ref().and_then(|compiler| {
-            let module_name =
-                uri_to_module_name(uri, &self.project_root).expect("uri to module name");
+            let module_name = uri_to_module_name(uri, &self.project_root)?;
             compiler.modules.get(&module_name)
         })
     }
 }
+
+/// Finds a file with the given name within the directory or any of its
+/// subdirectories.
+fn find_file(directory: &Path, file_name: &str) -> Option<PathBuf> {
+    walkdir::WalkDir::new(directory)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_type().is_file() && entry.file_name() == file_name)
+        .map(walkdir::DirEntry::into_path)
+}
+
+fn lsp_text_edits(edits: Vec<code_action::Edit>, line_numbers: &LineNumbers) -> Vec<TextEdit> {
+    edits
+        .into_iter()
+        .map(|edit| TextEdit {
+            range: src_span_to_lsp_range(edit.location, line_numbers),
+            new_text: edit.new_text,
+        })
+        .collect()
+}
+
+fn lsp_document_symbol(symbol: DocumentSymbol, line_numbers: &LineNumbers) -> lsp::DocumentSymbol {
+    let children = symbol
+        .children
+        .into_iter()
+        .map(|child| lsp_document_symbol(child, line_numbers))
+        .collect_vec();
+    #[allow(deprecated)]
+    lsp::DocumentSymbol {
+        name: symbol.name.to_string(),
+        detail: symbol.detail,
+        kind: lsp_symbol_kind(symbol.kind),
+        tags: None,
+        deprecated: None,
+        range: src_span_to_lsp_range(symbol.location, line_numbers),
+        selection_range: src_span_to_lsp_range(symbol.name_location, line_numbers),
+        children: (!children.is_empty()).then_some(children),
+    }
+}
+
+fn lsp_symbol_kind(kind: SymbolKind) -> lsp::SymbolKind {
+    match kind {
+        SymbolKind::Function => lsp::SymbolKind::FUNCTION,
+        SymbolKind::Constant => lsp::SymbolKind::CONSTANT,
+        SymbolKind::Type => lsp::SymbolKind::CLASS,
+        SymbolKind::Constructor => lsp::SymbolKind::CONSTRUCTOR,
+        SymbolKind::Field => lsp::SymbolKind::FIELD,
+    }
+}
+
+/// The interfaces of all the modules, including those of the root package
+/// which are not held by the project compiler once compiled.
+fn interfaces(
+    compiler: &LspProjectCompiler<FileSystemProxy<ProjectIO>>,
+) -> HashMap<SmolStr, &type_::Module> {
+    let mut interfaces: HashMap<_, _> = compiler
+        .project_compiler
+        .get_importable_modules()
+        .iter()
+        .map(|(name, interface)| (name.clone(), interface))
+        .collect();
+    for module in compiler.modules.values() {
+        let _ = interfaces.insert(module.name.clone(), &module.ast.type_info);
+    }
+    interfaces
+}
+
+/// Editors cannot show tokens spanning multiple lines, such as multi-line
+/// strings, so they are split into one token per line.
+fn token_lines(code: &str, location: SrcSpan) -> Vec<SrcSpan> {
+    let text = code
+        .get(location.start as usize..location.end as usize)
+        .unwrap_or_default();
+    let mut start = location.start;
+    let mut lines = vec![];
+    for line in text.split('\n') {
+        let end = start + line.len() as u32;
+        if end > start {
+            lines.push(SrcSpan::new(start, end));
+        }
+        start = end + 1;
+    }
+    lines
+}
+
+/// The index of the token type in the legend given in the server capabilities.
+fn lsp_token_type(type_: TokenType) -> u32 {
+    TokenType::ALL
+        .iter()
+        .position(|legend| *legend == type_)
+        .unwrap_or_default() as u32
+}
+
+/// The modifiers as bits set at their indexes in the legend given in the
+/// server capabilities.
+fn lsp_token_modifiers(modifiers: &[TokenModifier]) -> u32 {
+    TokenModifier::ALL
+        .iter()
+        .enumerate()
+        .filter(|(_, legend)| modifiers.contains(legend))
+        .map(|(index, _)| 1 << index)
+        .sum()
+}
+
+/// The document of the test module and the test functions given as the
+/// arguments of the run tests command.
+fn run_tests_arguments(params: &lsp::ExecuteCommandParams) -> Option<(Url, Vec<SmolStr>)> {
+    if params.command != RUN_TESTS_COMMAND {
+        return None;
+    }
+    match params.arguments.as_slice() {
+        [uri, functions] => Some((
+            serde_json::from_value(uri.clone()).ok()?,
+            serde_json::from_value(functions.clone()).ok()?,
+        )),
+        _ => None,
+    }
+}
+
+/// The name of the module defining the function of a call hierarchy item, as
+/// recorded when the item was created.
+fn call_hierarchy_item_module(item: &lsp::CallHierarchyItem) -> Option<&str> {
+    match &item.data {
+        Some(serde_json::Value::String(module)) => Some(module),
+        _ => None,
+    }
+}
+
+fn lsp_code_action(
+    action: CodeAction,
+    uri: &Url,
+    line_numbers: &LineNumbers,
+) -> lsp::CodeActionOrCommand {
+    let edits = lsp_text_edits(action.edits, line_numbers);
+    let kind = match action.kind {
+        CodeActionKind::QuickFix => lsp::CodeActionKind::QUICKFIX,
+        // This version of the protocol types predates `source.fixAll`.
+        CodeActionKind::FixAll => lsp::CodeActionKind::new("source.fixAll"),
+    };
+    lsp::CodeActionOrCommand::CodeAction(lsp::CodeAction {
+        title: action.title,
+        kind: Some(kind),
+        edit: Some(lsp::WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), edits)])),
+            document_changes: None,
+            change_annotations: None,
+        }),
+        is_preferred: Some(action.kind == CodeActionKind::QuickFix),
+        ..Default::default()
+    })
+}
+
+fn lsp_signature_help(help: SignatureHelp) -> lsp::SignatureHelp {
+    // Offsets within the label are in UTF-16 code units.
+    let utf16_offset = |index: usize| {
+        help.label
+            .get(..index)
+            .map(|text| text.encode_utf16().count() as u32)
+            .unwrap_or_default()
+    };
+    let parameters = help
+        .parameters
+        .iter()
+        .map(|(start, end)| lsp::ParameterInformation {
+            label: lsp::ParameterLabel::LabelOffsets([utf16_offset(*start), utf16_offset(*end)]),
+            documentation: None,
+        })
+        .collect();
+    let documentation = help.documentation.map(|documentation| {
+        lsp::Documentation::MarkupContent(lsp::MarkupContent {
+            kind: lsp::MarkupKind::Markdown,
+            value: documentation.to_string(),
+        })
+    });
+    let active_parameter = help.active_parameter.map(|index| index as u32);
+    lsp::SignatureHelp {
+        signatures: vec![lsp::SignatureInformation {
+            label: help.label,
+            documentation,
+            parameters: Some(parameters),
+            active_parameter,
+        }],
+        active_signature: Some(0),
+        active_parameter,
+    }
+}
+
+fn lsp_completion_item(completion: Completion) -> lsp::CompletionItem {
+    let kind = match completion.kind {
+        CompletionKind::Variable => lsp::CompletionItemKind::VARIABLE,
+        CompletionKind::Function => lsp::CompletionItemKind::FUNCTION,
+        CompletionKind::Constant => lsp::CompletionItemKind::CONSTANT,
+        CompletionKind::Constructor => lsp::CompletionItemKind::CONSTRUCTOR,
+        CompletionKind::Field => lsp::CompletionItemKind::FIELD,
+        CompletionKind::Type => lsp::CompletionItemKind::CLASS,
+        CompletionKind::Module => lsp::CompletionItemKind::MODULE,
+    };
+    let documentation = completion.documentation.map(|documentation| {
+        lsp::Documentation::MarkupContent(lsp::MarkupContent {
+            kind: lsp::MarkupKind::Markdown,
+            value: documentation.to_string(),
+        })
+    });
+    lsp::CompletionItem {
+        label: completion.label.to_string(),
+        kind: Some(kind),
+        detail: completion.detail,
+        documentation,
+        ..Default::default()
+    }
+}
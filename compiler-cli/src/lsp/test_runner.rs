@@ -0,0 +1,140 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use gleam_core::{
+    build::{self, Mode, ProjectCompiler, Target},
+    config::PackageConfig,
+    io::Stdio,
+    warning::VectorWarningEmitterIO,
+    Result,
+};
+use smol_str::SmolStr;
+
+use crate::{
+    build_lock::BuildLock, dependencies::UseManifest, fs::ProjectIO, run, telemetry::NullTelemetry,
+};
+
+/// The command run by the code lenses of test modules. Its arguments are the
+/// URI of the test module and a list of the test functions to run.
+pub const RUN_TESTS_COMMAND: &str = "gleam.runTests";
+
+/// How long building the project and running the tests of a command may take
+/// altogether before the tests are stopped. Tests are run while the language
+/// server waits, so they must not stop it from responding for long however
+/// many of them there are.
+const TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    pub function: SmolStr,
+    pub passed: bool,
+    /// Everything printed by the test while running.
+    pub output: String,
+}
+
+/// Compile the project for the configured target and run each of the test
+/// functions of the module in its own process.
+///
+/// Unlike the compilation done by the language server this generates code,
+/// so it is done in the `dev` build directory as with `gleam test`.
+///
+/// The tests that have not finished when the time allowed runs out fail.
+///
+pub fn run_tests(
+    config: &PackageConfig,
+    module: &str,
+    functions: &[SmolStr],
+) -> Result<Vec<TestResult>> {
+    let deadline = Instant::now() + TIMEOUT;
+    build(config)?;
+    functions
+        .iter()
+        .map(|function| run_test(config, module, function, deadline))
+        .collect()
+}
+
+fn build(config: &PackageConfig) -> Result<()> {
+    let telemetry = NullTelemetry;
+    let manifest = crate::dependencies::download(telemetry, None, UseManifest::Yes)?;
+    let build_lock = BuildLock::new_target(Mode::Dev, config.target)?;
+    let _guard = build_lock.lock(&telemetry);
+
+    let options = build::Options {
+        warnings_as_errors: false,
+        mode: Mode::Dev,
+        target: None,
+        codegen: build::Codegen::All,
+    };
+    let mut compiler = ProjectCompiler::new(
+        config.clone(),
+        options,
+        manifest.packages,
+        Box::new(telemetry),
+        Arc::new(VectorWarningEmitterIO::default()),
+        ProjectIO::new(),
+    );
+
+    // The language server is using stdout so the Erlang compiler must not
+    // print to it.
+    compiler.subprocess_stdio = Stdio::Null;
+
+    let _ = compiler.compile()?;
+    Ok(())
+}
+
+fn run_test(
+    config: &PackageConfig,
+    module: &str,
+    function: &str,
+    deadline: Instant,
+) -> Result<TestResult> {
+    let timeout = deadline.saturating_duration_since(Instant::now());
+    if timeout.is_zero() {
+        return Ok(TestResult {
+            function: function.into(),
+            passed: false,
+            output: timed_out("The test was not run"),
+        });
+    }
+
+    let (program, args) = match config.target {
+        Target::Erlang => {
+            let package = &config.name;
+            let module = module.replace('/', "@");
+            let eval = format!(
+                "try \
+                   {{ok, _}} = application:ensure_all_started('{package}'), \
+                   '{module}':'{function}'(), \
+                   erlang:halt(0) \
+                 catch Class:Reason:Stacktrace -> \
+                   io:put_chars(erl_error:format_exception(Class, Reason, Stacktrace)), \
+                   erlang:halt(1) \
+                 end"
+            );
+            ("erl", run::erlang_arguments(eval, vec![])?)
+        }
+        Target::JavaScript => {
+            run::javascript_command(config, config.javascript.runtime, module, function, vec![])?
+        }
+    };
+
+    let (passed, mut output) = crate::fs::exec_with_output(program, &args, timeout)?;
+    if passed.is_none() {
+        output.push('\n');
+        output.push_str(&timed_out("The test was stopped"));
+    }
+    Ok(TestResult {
+        function: function.into(),
+        passed: passed.unwrap_or(false),
+        output,
+    })
+}
+
+fn timed_out(what_happened: &str) -> String {
+    format!(
+        "{what_happened} as the tests did not finish within {} seconds.",
+        TIMEOUT.as_secs()
+    )
+}
@@ -53,16 +53,24 @@ pub fn command(
             }),
             _ => run_erlang(&config.name, &module, arguments),
         },
-        Target::JavaScript => match runtime.unwrap_or(config.javascript.runtime) {
-            Runtime::Deno => run_javascript_deno(&config, &module, arguments),
-            Runtime::NodeJs => run_javascript_node(&config, &module, arguments),
-        },
+        Target::JavaScript => {
+            let runtime = runtime.unwrap_or(config.javascript.runtime);
+            let (program, args) = javascript_command(&config, runtime, &module, "main", arguments)?;
+            ProjectIO::new().exec(program, &args, &[], None, Stdio::Inherit)
+        }
     }?;
 
     std::process::exit(status);
 }
 
 fn run_erlang(package: &str, module: &str, arguments: Vec<String>) -> Result<i32, Error> {
+    let args = erlang_arguments(format!("{package}@@main:run({module})"), arguments)?;
+    ProjectIO::new().exec("erl", &args, &[], None, Stdio::Inherit)
+}
+
+/// The arguments for starting the Erlang VM with the compiled packages
+/// available and evaluating the given expression.
+pub(crate) fn erlang_arguments(eval: String, arguments: Vec<String>) -> Result<Vec<String>, Error> {
     let mut args = vec![];
 
     // Specify locations of .beam files
@@ -74,7 +82,7 @@ fn run_erlang(package: &str, module: &str, arguments: Vec<String>) -> Result<i32
     }
 
     args.push("-eval".into());
-    args.push(format!("{package}@@main:run({module})"));
+    args.push(eval);
 
     // Don't run the Erlang shell
     args.push("-noshell".into());
@@ -85,43 +93,50 @@ fn run_erlang(package: &str, module: &str, arguments: Vec<String>) -> Result<i32
         args.push(argument);
     }
 
-    ProjectIO::new().exec("erl", &args, &[], None, Stdio::Inherit)
+    Ok(args)
 }
 
-fn run_javascript_node(
+/// The program and arguments for calling a function of a compiled module
+/// using the given JavaScript runtime.
+pub(crate) fn javascript_command(
     config: &PackageConfig,
+    runtime: Runtime,
     module: &str,
+    function: &str,
     arguments: Vec<String>,
-) -> Result<i32, Error> {
-    let mut args = vec![];
-    let entry = write_javascript_entrypoint(&config.name, module)?;
+) -> Result<(&'static str, Vec<String>), Error> {
+    let entrypoint = write_javascript_entrypoint(&config.name, module, function)?;
+    let (program, mut args) = match runtime {
+        Runtime::Deno => ("deno", deno_arguments(config)),
+        Runtime::NodeJs => ("node", vec![]),
+    };
 
-    args.push(entry);
+    args.push(entrypoint);
 
     for argument in arguments.into_iter() {
         args.push(argument);
     }
 
-    ProjectIO::new().exec("node", &args, &[], None, Stdio::Inherit)
+    Ok((program, args))
 }
 
-fn write_javascript_entrypoint(package: &str, module: &str) -> Result<String, Error> {
+fn write_javascript_entrypoint(
+    package: &str,
+    module: &str,
+    function: &str,
+) -> Result<String, Error> {
     let entry = paths::build_package(Mode::Dev, Target::JavaScript, package);
     let entrypoint = format!("./{}/gleam.main.mjs", entry.to_string_lossy());
     let module = format!(
-        r#"import {{ main }} from "./{module}.mjs";
-main();
+        r#"import {{ {function} }} from "./{module}.mjs";
+{function}();
 "#,
     );
     crate::fs::write(&PathBuf::from(&entrypoint), &module)?;
     Ok(entrypoint)
 }
 
-fn run_javascript_deno(
-    config: &PackageConfig,
-    module: &str,
-    arguments: Vec<String>,
-) -> Result<i32, Error> {
+fn deno_arguments(config: &PackageConfig) -> Vec<String> {
     let mut args = vec![];
 
     // Run the main function.
@@ -171,14 +186,7 @@ fn run_javascript_deno(
         );
     }
 
-    let entrypoint = write_javascript_entrypoint(&config.name, module)?;
-    args.push(entrypoint);
-
-    for argument in arguments.into_iter() {
-        args.push(argument);
-    }
-
-    ProjectIO::new().exec("deno", &args, &[], None, Stdio::Inherit)
+    args
 }
 
 fn add_deno_flag(args: &mut Vec<String>, flag: &str, flags: &DenoFlag) {
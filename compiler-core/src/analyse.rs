@@ -27,12 +27,72 @@ use crate::{
 use itertools::Itertools;
 use smol_str::SmolStr;
 use std::{collections::HashMap, sync::Arc};
+use vec1::{vec1, Vec1};
 
 // TODO: This takes too many arguments.
 /// Crawl the AST, annotating each node with the inferred type or
-/// returning an error.
+/// returning the first error.
 ///
 pub fn infer_module(
+    target: Target,
+    ids: &UniqueIdGenerator,
+    module: UntypedModule,
+    origin: Origin,
+    package: &SmolStr,
+    modules: &im::HashMap<SmolStr, Module>,
+    warnings: &TypeWarningEmitter,
+) -> Result<TypedModule, Error> {
+    infer_module_with_recovery(target, ids, module, origin, package, modules, warnings)
+        .map_err(|errors| errors.split_off_first().0)
+}
+
+/// Crawl the AST, annotating each node with the inferred type or returning
+/// the errors found.
+///
+/// When a function fails to type check the other functions of the module are
+/// still checked, so that all the independent errors of the module are
+/// returned at once. Errors in the imports, types, and constants of the module
+/// stop type checking.
+///
+pub fn infer_module_with_recovery(
+    target: Target,
+    ids: &UniqueIdGenerator,
+    module: UntypedModule,
+    origin: Origin,
+    package: &SmolStr,
+    modules: &im::HashMap<SmolStr, Module>,
+    warnings: &TypeWarningEmitter,
+) -> Result<TypedModule, Vec1<Error>> {
+    let mut errors = vec![];
+    let result = infer_module_collecting_errors(
+        target,
+        ids,
+        module,
+        origin,
+        package,
+        modules,
+        warnings,
+        &mut errors,
+    );
+    let mut errors = match (result, Vec1::try_from_vec(errors)) {
+        (Ok(module), Err(_)) => return Ok(module),
+        (Ok(_), Ok(errors)) => errors,
+        (Err(error), Ok(mut errors)) => {
+            errors.push(error);
+            errors
+        }
+        (Err(error), Err(_)) => vec1![error],
+    };
+    // Functions are checked in dependency order rather than the order they
+    // are written in, so the errors are sorted to be reported top to bottom.
+    errors.sort_by_key(|error| error.location().map(|location| location.start));
+    Err(errors)
+}
+
+/// Infers the types of a module, adding the errors of any functions that fail
+/// to type check to `errors` and carrying on.
+#[allow(clippy::too_many_arguments)]
+fn infer_module_collecting_errors(
     target: Target,
     ids: &UniqueIdGenerator,
     mut module: UntypedModule,
@@ -40,6 +100,7 @@ pub fn infer_module(
     package: &SmolStr,
     modules: &im::HashMap<SmolStr, Module>,
     warnings: &TypeWarningEmitter,
+    errors: &mut Vec<Error>,
 ) -> Result<TypedModule, Error> {
     let name = module.name.clone();
     let documentation = std::mem::take(&mut module.documentation);
@@ -135,11 +196,18 @@ pub fn infer_module(
         // A group may have multiple functions that depend on each other through
         // mutual recursion.
         for function in group {
+            let function_name = function.name().clone();
             let inferred = match function {
-                ModuleFunction::Internal(f) => infer_function(f, &mut env, &mut hydrators, &name)?,
-                ModuleFunction::External(f) => infer_external_function(f, &mut env)?,
+                ModuleFunction::Internal(f) => infer_function(f, &mut env, &mut hydrators, &name),
+                ModuleFunction::External(f) => infer_external_function(f, &mut env),
             };
-            working_group.push(inferred);
+            match inferred {
+                Ok(inferred) => working_group.push(inferred),
+                Err(error) => {
+                    errors.push(error);
+                    generalise_failed_function(&function_name, &mut env);
+                }
+            }
         }
 
         // Now that the entire group has been inferred, generalise their types.
@@ -149,8 +217,12 @@ pub fn infer_module(
         }
     }
 
-    // Generate warnings for unused items
-    env.convert_unused_to_warnings();
+    // Generate warnings for unused items, unless a function failed to type
+    // check in which case the items it uses may not have been registered as
+    // used
+    if errors.is_empty() {
+        env.convert_unused_to_warnings();
+    }
 
     // Remove private and imported types and values to create the public interface
     env.module_types
@@ -689,6 +761,7 @@ fn infer_function(
         doc,
         location,
         name,
+        name_location,
         public,
         arguments: args,
         body,
@@ -751,6 +824,7 @@ fn infer_function(
         doc,
         location,
         name,
+        name_location,
         public,
         arguments: args,
         end_position: end_location,
@@ -762,6 +836,21 @@ fn infer_function(
     }))
 }
 
+/// Gives a function that failed to type check the type it was registered
+/// with, built from its annotations, generalised so that the functions that
+/// use it can still be type checked without errors caused by the failure.
+fn generalise_failed_function(name: &SmolStr, environment: &mut Environment<'_>) {
+    let _ = environment.ungeneralised_functions.remove(name);
+    if let Some(function) = environment.get_variable(name).cloned() {
+        environment.insert_variable(
+            name.clone(),
+            function.variant,
+            type_::generalise(function.type_),
+            function.public,
+        );
+    }
+}
+
 fn infer_external_function(
     f: ExternalFunction<()>,
     environment: &mut Environment<'_>,
@@ -770,6 +859,7 @@ fn infer_external_function(
         doc,
         location,
         name,
+        name_location,
         public,
         arguments: args,
         return_: retrn,
@@ -794,6 +884,7 @@ fn infer_external_function(
         doc,
         location,
         name,
+        name_location,
         public,
         arguments: args,
         return_: retrn,
@@ -811,6 +902,7 @@ fn insert_type_alias(
         location,
         public,
         alias,
+        name_location,
         parameters: args,
         type_ast: resolved_type,
         ..
@@ -825,6 +917,7 @@ fn insert_type_alias(
         location,
         public,
         alias,
+        name_location,
         parameters: args,
         type_ast: resolved_type,
         type_: typ,
@@ -838,9 +931,11 @@ fn infer_custom_type(
     let CustomType {
         doc,
         location,
+        end_position,
         public,
         opaque,
         name,
+        name_location,
         parameters,
         constructors,
         ..
@@ -904,9 +999,11 @@ fn infer_custom_type(
     Ok(Statement::CustomType(CustomType {
         doc,
         location,
+        end_position,
         public,
         opaque,
         name,
+        name_location,
         parameters,
         constructors,
         typed_parameters,
@@ -922,6 +1019,7 @@ fn hydrate_external_type(
         location,
         public,
         name,
+        name_location,
         arguments: args,
     } = t;
     // Check contained types are valid
@@ -938,6 +1036,7 @@ fn hydrate_external_type(
         location,
         public,
         name,
+        name_location,
         arguments: args,
     }))
 }
@@ -988,6 +1087,7 @@ fn infer_module_constant(
         doc,
         location,
         name,
+        name_location,
         annotation,
         public,
         value,
@@ -1016,6 +1116,7 @@ fn infer_module_constant(
         doc,
         location,
         name,
+        name_location,
         annotation,
         public,
         value: Box::new(typed_expr),
@@ -1117,6 +1218,7 @@ fn generalise_function(
         doc,
         location,
         name,
+        name_location,
         public,
         arguments: args,
         body,
@@ -1159,6 +1261,7 @@ fn generalise_function(
         doc,
         location,
         name,
+        name_location,
         public,
         arguments: args,
         end_position: end_location,
@@ -1253,7 +1356,7 @@ fn custom_type_accessors<A>(
 
 /// Returns the fields that have the same label and type across all variants of
 /// the given type.
-fn get_compatible_record_fields<A>(
+pub(crate) fn get_compatible_record_fields<A>(
     constructors: &[RecordConstructor<A>],
 ) -> Vec<(usize, &SmolStr, &TypeAst)> {
     let mut compatible = vec![];
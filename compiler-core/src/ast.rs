@@ -186,10 +186,22 @@ impl<A> ExternalFnArg<A> {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ArgNames {
-    Discard { name: SmolStr },
-    LabelledDiscard { label: SmolStr, name: SmolStr },
-    Named { name: SmolStr },
-    NamedLabelled { name: SmolStr, label: SmolStr },
+    Discard {
+        name: SmolStr,
+    },
+    LabelledDiscard {
+        label: SmolStr,
+        name: SmolStr,
+    },
+    Named {
+        name: SmolStr,
+    },
+    NamedLabelled {
+        name: SmolStr,
+        label: SmolStr,
+        /// The location of the name, which comes after the label
+        name_location: SrcSpan,
+    },
 }
 
 impl ArgNames {
@@ -377,6 +389,7 @@ pub struct ExternalFunction<T> {
     pub public: bool,
     pub arguments: Vec<ExternalFnArg<T>>,
     pub name: SmolStr,
+    pub name_location: SrcSpan,
     pub return_: TypeAst,
     pub return_type: T,
     pub module: SmolStr,
@@ -399,6 +412,7 @@ pub struct Function<T, Expr> {
     pub location: SrcSpan,
     pub end_position: u32,
     pub name: SmolStr,
+    pub name_location: SrcSpan,
     pub arguments: Vec<Arg<T>>,
     pub body: Expr,
     pub public: bool,
@@ -451,6 +465,7 @@ pub struct ModuleConstant<T, ConstantRecordTag> {
     pub location: SrcSpan,
     pub public: bool,
     pub name: SmolStr,
+    pub name_location: SrcSpan,
     pub annotation: Option<TypeAst>,
     pub value: Box<Constant<T, ConstantRecordTag>>,
     pub type_: T,
@@ -474,7 +489,9 @@ pub struct ModuleConstant<T, ConstantRecordTag> {
 /// ```
 pub struct CustomType<T> {
     pub location: SrcSpan,
+    pub end_position: u32,
     pub name: SmolStr,
+    pub name_location: SrcSpan,
     pub parameters: Vec<SmolStr>,
     pub public: bool,
     pub constructors: Vec<RecordConstructor<T>>,
@@ -497,6 +514,7 @@ pub struct ExternalType {
     pub location: SrcSpan,
     pub public: bool,
     pub name: SmolStr,
+    pub name_location: SrcSpan,
     pub arguments: Vec<SmolStr>,
     pub doc: Option<SmolStr>,
 }
@@ -513,6 +531,7 @@ pub struct ExternalType {
 pub struct TypeAlias<T> {
     pub location: SrcSpan,
     pub alias: SmolStr,
+    pub name_location: SrcSpan,
     pub parameters: Vec<SmolStr>,
     pub type_ast: TypeAst,
     pub type_: T,
@@ -874,6 +893,66 @@ pub enum ClauseGuard<Type, RecordTag> {
         right: Box<Self>,
     },
 
+    AddInt {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    AddFloat {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    SubInt {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    SubFloat {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    MultInt {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    MultFloat {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    DivInt {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    DivFloat {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    RemainderInt {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
+    Concatenate {
+        location: SrcSpan,
+        left: Box<Self>,
+        right: Box<Self>,
+    },
+
     Or {
         location: SrcSpan,
         left: Box<Self>,
@@ -899,6 +978,21 @@ pub enum ClauseGuard<Type, RecordTag> {
         tuple: Box<Self>,
     },
 
+    FieldAccess {
+        location: SrcSpan,
+        /// The index of the field, known once the type of the container has
+        /// been inferred.
+        index: Option<u64>,
+        label: SmolStr,
+        type_: Type,
+        container: Box<Self>,
+    },
+
+    Not {
+        location: SrcSpan,
+        expression: Box<Self>,
+    },
+
     Constant(Constant<Type, RecordTag>),
 }
 
@@ -919,7 +1013,19 @@ impl<A, B> ClauseGuard<A, B> {
             | ClauseGuard::GtFloat { location, .. }
             | ClauseGuard::GtEqFloat { location, .. }
             | ClauseGuard::LtFloat { location, .. }
-            | ClauseGuard::LtEqFloat { location, .. } => *location,
+            | ClauseGuard::LtEqFloat { location, .. }
+            | ClauseGuard::AddInt { location, .. }
+            | ClauseGuard::AddFloat { location, .. }
+            | ClauseGuard::SubInt { location, .. }
+            | ClauseGuard::SubFloat { location, .. }
+            | ClauseGuard::MultInt { location, .. }
+            | ClauseGuard::MultFloat { location, .. }
+            | ClauseGuard::DivInt { location, .. }
+            | ClauseGuard::DivFloat { location, .. }
+            | ClauseGuard::RemainderInt { location, .. }
+            | ClauseGuard::Concatenate { location, .. }
+            | ClauseGuard::FieldAccess { location, .. }
+            | ClauseGuard::Not { location, .. } => *location,
         }
     }
 
@@ -940,9 +1046,24 @@ impl<A, B> ClauseGuard<A, B> {
             | ClauseGuard::LtFloat { .. }
             | ClauseGuard::LtEqFloat { .. } => 4,
 
-            ClauseGuard::Constant(_) | ClauseGuard::Var { .. } | ClauseGuard::TupleIndex { .. } => {
-                5
-            }
+            ClauseGuard::Concatenate { .. } => 5,
+
+            ClauseGuard::AddInt { .. }
+            | ClauseGuard::AddFloat { .. }
+            | ClauseGuard::SubInt { .. }
+            | ClauseGuard::SubFloat { .. } => 7,
+
+            ClauseGuard::MultInt { .. }
+            | ClauseGuard::MultFloat { .. }
+            | ClauseGuard::DivInt { .. }
+            | ClauseGuard::DivFloat { .. }
+            | ClauseGuard::RemainderInt { .. } => 8,
+
+            ClauseGuard::Constant(_)
+            | ClauseGuard::Var { .. }
+            | ClauseGuard::TupleIndex { .. }
+            | ClauseGuard::FieldAccess { .. }
+            | ClauseGuard::Not { .. } => 9,
         }
     }
 }
@@ -952,8 +1073,22 @@ impl TypedClauseGuard {
         match self {
             ClauseGuard::Var { type_, .. } => type_.clone(),
             ClauseGuard::TupleIndex { type_, .. } => type_.clone(),
+            ClauseGuard::FieldAccess { type_, .. } => type_.clone(),
             ClauseGuard::Constant(constant) => constant.type_(),
 
+            ClauseGuard::AddInt { .. }
+            | ClauseGuard::SubInt { .. }
+            | ClauseGuard::MultInt { .. }
+            | ClauseGuard::DivInt { .. }
+            | ClauseGuard::RemainderInt { .. } => type_::int(),
+
+            ClauseGuard::AddFloat { .. }
+            | ClauseGuard::SubFloat { .. }
+            | ClauseGuard::MultFloat { .. }
+            | ClauseGuard::DivFloat { .. } => type_::float(),
+
+            ClauseGuard::Concatenate { .. } => type_::string(),
+
             ClauseGuard::Or { .. }
             | ClauseGuard::And { .. }
             | ClauseGuard::Equals { .. }
@@ -965,12 +1100,13 @@ impl TypedClauseGuard {
             | ClauseGuard::GtFloat { .. }
             | ClauseGuard::GtEqFloat { .. }
             | ClauseGuard::LtFloat { .. }
-            | ClauseGuard::LtEqFloat { .. } => type_::bool(),
+            | ClauseGuard::LtEqFloat { .. }
+            | ClauseGuard::Not { .. } => type_::bool(),
         }
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Default, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Default, Clone, Copy)]
 pub struct SrcSpan {
     pub start: u32,
     pub end: u32,
@@ -93,6 +93,25 @@ impl<A, B> Constant<A, B> {
         }
     }
 
+    /// Whether the constant is an int or a float that is known not to be
+    /// zero.
+    pub fn is_non_zero_number(&self) -> bool {
+        match self {
+            Constant::Int { value, .. } => {
+                let digits = value.trim_start_matches('-');
+                let digits = ["0b", "0o", "0x"]
+                    .iter()
+                    .find_map(|prefix| digits.strip_prefix(prefix))
+                    .unwrap_or(digits);
+                digits.chars().any(|char| char != '0' && char != '_')
+            }
+            Constant::Float { value, .. } => {
+                matches!(value.replace('_', "").parse::<f64>(), Ok(value) if value != 0.0)
+            }
+            _ => false,
+        }
+    }
+
     pub fn is_simple(&self) -> bool {
         matches!(
             self,
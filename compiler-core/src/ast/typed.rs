@@ -140,6 +140,8 @@ pub enum TypedExpr {
     RecordUpdate {
         location: SrcSpan,
         typ: Arc<Type>,
+        /// The record constructor, being a `Var` or a `ModuleSelect`
+        constructor: Box<Self>,
         spread: Box<Self>,
         args: Vec<TypedRecordUpdateArg>,
     },
@@ -157,6 +157,44 @@ fn mode_includes_tests() {
     assert!(!Mode::Prod.includes_tests());
 }
 
+/// The result of compiling some code, where an error may have stopped the
+/// compilation after some of the work had already been done.
+///
+#[derive(Debug)]
+pub enum Outcome<T, E> {
+    Ok(T),
+    /// The work done before the error, such as the modules that were type
+    /// checked before one with an error was found.
+    PartialFailure(T, E),
+    TotalFailure(E),
+}
+
+impl<T, E> Outcome<T, E> {
+    pub fn into_result(self) -> Result<T, E> {
+        match self {
+            Outcome::Ok(value) => Ok(value),
+            Outcome::PartialFailure(_, error) | Outcome::TotalFailure(error) => Err(error),
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Outcome<U, E> {
+        match self {
+            Outcome::Ok(value) => Outcome::Ok(f(value)),
+            Outcome::PartialFailure(value, error) => Outcome::PartialFailure(f(value), error),
+            Outcome::TotalFailure(error) => Outcome::TotalFailure(error),
+        }
+    }
+}
+
+impl<T, E> From<Result<T, E>> for Outcome<T, E> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Outcome::Ok(value),
+            Err(error) => Outcome::TotalFailure(error),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Package {
     pub config: PackageConfig,
@@ -214,40 +252,11 @@ impl Module {
             .map(|span| Comment::from((span, self.code.as_str())).content.into())
             .collect();
 
-        // Order statements to avoid dissociating doc comments from them
-        let mut statements: Vec<_> = self.ast.statements.iter_mut().collect();
-        statements.sort_by(|a, b| a.location().start.cmp(&b.location().start));
-
-        // Doc Comments
-        let mut doc_comments = self.extra.doc_comments.iter().peekable();
-        for statement in &mut statements {
-            let docs: Vec<&str> =
-                comments_before(&mut doc_comments, statement.location().start, &self.code);
-            if !docs.is_empty() {
-                let doc = docs.join("\n").into();
-                statement.put_doc(doc);
-            }
-
-            if let Statement::CustomType(CustomType { constructors, .. }) = statement {
-                for constructor in constructors {
-                    let docs: Vec<&str> =
-                        comments_before(&mut doc_comments, constructor.location.start, &self.code);
-                    if !docs.is_empty() {
-                        let doc = docs.join("\n").into();
-                        constructor.put_doc(doc);
-                    }
-
-                    for argument in constructor.arguments.iter_mut() {
-                        let docs: Vec<&str> =
-                            comments_before(&mut doc_comments, argument.location.start, &self.code);
-                        if !docs.is_empty() {
-                            let doc = docs.join("\n").into();
-                            argument.put_doc(doc);
-                        }
-                    }
-                }
-            }
-        }
+        attach_doc_comments(
+            self.ast.statements.iter_mut(),
+            &self.extra.doc_comments,
+            &self.code,
+        );
     }
 
     pub(crate) fn dependencies_list(&self) -> Vec<SmolStr> {
@@ -292,6 +301,51 @@ impl Origin {
     }
 }
 
+/// Attach the doc comments to the statements, custom type constructors, and
+/// constructor fields that they precede.
+///
+pub(crate) fn attach_doc_comments<'a, A: 'a, B: 'a, C: 'a, D: 'a>(
+    statements: impl Iterator<Item = &'a mut Statement<A, B, C, D>>,
+    doc_comments: &[SrcSpan],
+    code: &str,
+) where
+    A: PartialEq,
+{
+    // Order statements to avoid dissociating doc comments from them
+    let mut statements: Vec<_> = statements.collect();
+    statements.sort_by(|a, b| a.location().start.cmp(&b.location().start));
+
+    // Doc Comments
+    let mut doc_comments = doc_comments.iter().peekable();
+    for statement in &mut statements {
+        let docs: Vec<&str> = comments_before(&mut doc_comments, statement.location().start, code);
+        if !docs.is_empty() {
+            let doc = docs.join("\n").into();
+            statement.put_doc(doc);
+        }
+
+        if let Statement::CustomType(CustomType { constructors, .. }) = statement {
+            for constructor in constructors {
+                let docs: Vec<&str> =
+                    comments_before(&mut doc_comments, constructor.location.start, code);
+                if !docs.is_empty() {
+                    let doc = docs.join("\n").into();
+                    constructor.put_doc(doc);
+                }
+
+                for argument in constructor.arguments.iter_mut() {
+                    let docs: Vec<&str> =
+                        comments_before(&mut doc_comments, argument.location.start, code);
+                    if !docs.is_empty() {
+                        let doc = docs.join("\n").into();
+                        argument.put_doc(doc);
+                    }
+                }
+            }
+        }
+    }
+}
+
 fn comments_before<'a>(
     comment_spans: &mut Peekable<impl Iterator<Item = &'a SrcSpan>>,
     byte: u32,
@@ -9,6 +9,7 @@ use std::{
 
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
+use vec1::Vec1;
 
 use super::{
     package_compiler::{module_name, CacheMetadata, CachedModule, Input, UncompiledModule},
@@ -150,11 +151,15 @@ pub(crate) fn read_source<IO: FileSystemIO + CommandExecutor + Clone>(
 ) -> Result<UncompiledModule> {
     let code: SmolStr = io.read(&path)?.into();
 
-    let (mut ast, extra) = crate::parse::parse_module(&code).map_err(|error| Error::Parse {
-        path: path.clone(),
-        src: code.clone(),
-        error,
-    })?;
+    let parsed = crate::parse::parse_module_with_recovery(&code);
+    if let Ok(errors) = Vec1::try_from_vec(parsed.errors) {
+        return Err(Error::Parse {
+            path,
+            src: code,
+            errors,
+        });
+    }
+    let (mut ast, extra) = (parsed.module, parsed.extra);
 
     let dependencies = ast.dependencies(target);
 
@@ -26,7 +26,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use super::{ErlangAppCodegenConfiguration, TargetCodegenConfiguration};
+use super::{ErlangAppCodegenConfiguration, Outcome, TargetCodegenConfiguration};
 
 #[cfg(not(target_os = "windows"))]
 const ELIXIR_EXECUTABLE: &str = "elixir";
@@ -86,13 +86,16 @@ where
     /// Compile the package.
     /// Returns a list of modules that were compiled. Any modules that were read
     /// from the cache will not be returned.
+    ///
+    /// If a module fails to type check the modules that were type checked
+    /// before it are returned along with the error, and no code is generated.
     // TODO: return the cached modules.
     pub fn compile(
         mut self,
         warnings: &WarningEmitter,
         existing_modules: &mut im::HashMap<SmolStr, type_::Module>,
         already_defined_modules: &mut im::HashMap<SmolStr, PathBuf>,
-    ) -> Result<Vec<Module>, Error> {
+    ) -> Outcome<Vec<Module>, Error> {
         let span = tracing::info_span!("compile", package = %self.config.name.as_str());
         let _enter = span.enter();
 
@@ -102,7 +105,7 @@ where
         } else {
             CodegenRequired::No
         };
-        let loaded = PackageLoader::new(
+        let loaded = match PackageLoader::new(
             self.io.clone(),
             self.ids.clone(),
             self.mode,
@@ -113,7 +116,11 @@ where
             &self.config.name,
             already_defined_modules,
         )
-        .run()?;
+        .run()
+        {
+            Ok(loaded) => loaded,
+            Err(error) => return Outcome::TotalFailure(error),
+        };
 
         // Load the cached modules that have previously been compiled
         for module in loaded.cached.into_iter() {
@@ -122,20 +129,23 @@ where
 
         // Type check the modules that are new or have changed
         tracing::info!(count=%loaded.to_compile.len(), "type_checking_modules");
-        let modules = type_check(
+        let modules = match type_check(
             &self.config.name,
             self.target.target(),
             &self.ids,
             loaded.to_compile,
             existing_modules,
             warnings,
-        )?;
+        ) {
+            Outcome::Ok(modules) => modules,
+            outcome => return outcome,
+        };
 
         tracing::info!("performing_code_generation");
-        self.perform_codegen(&modules)?;
-        self.encode_and_write_metadata(&modules)?;
-
-        Ok(modules)
+        self.perform_codegen(&modules)
+            .and_then(|_| self.encode_and_write_metadata(&modules))
+            .map(|_| modules)
+            .into()
     }
 
     fn compile_erlang_to_beam(&mut self, modules: &HashSet<PathBuf>) -> Result<(), Error> {
@@ -367,7 +377,7 @@ fn type_check(
     mut parsed_modules: Vec<UncompiledModule>,
     module_types: &mut im::HashMap<SmolStr, type_::Module>,
     warnings: &WarningEmitter,
-) -> Result<Vec<Module>, Error> {
+) -> Outcome<Vec<Module>, Error> {
     let mut modules = Vec::with_capacity(parsed_modules.len() + 1);
 
     // Insert the prelude
@@ -391,7 +401,7 @@ fn type_check(
     {
         tracing::debug!(module = ?name, "Type checking");
 
-        let ast = crate::analyse::infer_module(
+        let ast = match crate::analyse::infer_module_with_recovery(
             target,
             ids,
             ast,
@@ -399,12 +409,19 @@ fn type_check(
             package_name,
             module_types,
             &TypeWarningEmitter::new(path.clone(), code.clone(), warnings.clone()),
-        )
-        .map_err(|error| Error::Type {
-            path: path.clone(),
-            src: code.clone(),
-            error,
-        })?;
+        ) {
+            Ok(ast) => ast,
+            // The modules checked so far are kept so that they can be used by
+            // the language server while the error is being fixed.
+            Err(errors) => {
+                let error = Error::Type {
+                    path: path.clone(),
+                    src: code.clone(),
+                    errors,
+                };
+                return Outcome::PartialFailure(modules, error);
+            }
+        };
 
         // Register the types from this module so they can be imported into
         // other modules.
@@ -424,7 +441,7 @@ fn type_check(
         });
     }
 
-    Ok(modules)
+    Outcome::Ok(modules)
 }
 
 pub fn maybe_link_elixir_libs<IO: CommandExecutor + FileSystemIO + Clone>(
@@ -25,7 +25,7 @@ use std::{
     time::Instant,
 };
 
-use super::{Codegen, ErlangAppCodegenConfiguration};
+use super::{Codegen, ErlangAppCodegenConfiguration, Outcome};
 
 // On Windows we have to call rebar3 via a little wrapper script.
 //
@@ -134,7 +134,7 @@ where
             Codegen::All => self.telemetry.compiling_package(&self.config.name),
             Codegen::DepsOnly | Codegen::None => self.telemetry.checking_package(&self.config.name),
         }
-        let result = self.compile_root_package();
+        let result = self.compile_root_package().into_result();
 
         // TODO: test
         if self.options.warnings_as_errors && self.warnings.count() > 0 {
@@ -146,11 +146,12 @@ where
         result
     }
 
-    pub fn compile_root_package(&mut self) -> Result<Package, Error> {
+    /// Compiles the root package. If a module fails to type check the
+    /// modules that were type checked before it are returned with the error.
+    pub fn compile_root_package(&mut self) -> Outcome<Package, Error> {
         let config = self.config.clone();
-        let modules = self.compile_gleam_package(&config, true, paths::root())?;
-
-        Ok(Package { config, modules })
+        self.compile_gleam_package(&config, true, paths::root())
+            .map(|modules| Package { config, modules })
     }
 
     /// Checks that version file found in the build directory matches the
@@ -373,6 +374,7 @@ where
         let config = PackageConfig::read(config_path, &self.io)?;
         let root = paths::build_deps_package(&package.name);
         self.compile_gleam_package(&config, false, root)
+            .into_result()
             .map(|_| ())?;
         Ok(())
     }
@@ -399,7 +401,7 @@ where
         config: &PackageConfig,
         is_root: bool,
         root_path: PathBuf,
-    ) -> Result<Vec<Module>, Error> {
+    ) -> Outcome<Vec<Module>, Error> {
         let out_path = paths::build_package(self.mode(), self.target(), &config.name);
         let lib_path = paths::build_packages(self.mode(), self.target());
         let mode = self.mode();
@@ -430,13 +432,11 @@ where
         compiler.subprocess_stdio = self.subprocess_stdio;
 
         // Compile project to Erlang or JavaScript source code
-        let compiled = compiler.compile(
+        compiler.compile(
             &mut self.warnings,
             &mut self.importable_modules,
             &mut self.defined_modules,
-        )?;
-
-        Ok(compiled)
+        )
     }
 }
 
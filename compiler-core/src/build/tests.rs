@@ -56,3 +56,53 @@ fn usable_build_tool_mix_then_rebar3() {
         Ok(BuildTool::Rebar3)
     )
 }
+
+#[test]
+fn compile_keeps_modules_checked_before_a_type_error() {
+    use super::{Mode, Outcome, PackageCompiler, TargetCodegenConfiguration};
+    use crate::{
+        config::PackageConfig,
+        io::{memory::InMemoryFileSystem, FileSystemWriter},
+        uid::UniqueIdGenerator,
+        warning::{NullWarningEmitterIO, WarningEmitter},
+    };
+    use std::{path::Path, sync::Arc};
+
+    let io = InMemoryFileSystem::new();
+    io.write(Path::new("/src/one.gleam"), "pub fn one() { 1 }")
+        .expect("Writing one.gleam failed");
+    io.write(
+        Path::new("/src/two.gleam"),
+        "import one\npub fn two() { one.one() + 1.0 }",
+    )
+    .expect("Writing two.gleam failed");
+
+    let config = PackageConfig::default();
+    let target = TargetCodegenConfiguration::JavaScript {
+        emit_typescript_definitions: false,
+    };
+    let compiler = PackageCompiler::new(
+        &config,
+        Mode::Dev,
+        Path::new("/"),
+        Path::new("/out/lib/the_package"),
+        Path::new("/out/lib"),
+        &target,
+        UniqueIdGenerator::new(),
+        io,
+    );
+    let outcome = compiler.compile(
+        &WarningEmitter::new(Arc::new(NullWarningEmitterIO)),
+        &mut im::HashMap::new(),
+        &mut im::HashMap::new(),
+    );
+
+    match outcome {
+        Outcome::PartialFailure(modules, Error::Type { path, .. }) => {
+            let names: Vec<_> = modules.iter().map(|module| module.name.as_str()).collect();
+            assert_eq!(names, vec!["one"]);
+            assert_eq!(path, Path::new("/src/two.gleam"));
+        }
+        outcome => panic!("Expected a partial failure, got {outcome:?}"),
+    }
+}
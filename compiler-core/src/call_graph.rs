@@ -328,7 +328,17 @@ impl<'a> CallGraphBuilder<'a> {
             | ClauseGuard::LtFloat { left, right, .. }
             | ClauseGuard::LtEqFloat { left, right, .. }
             | ClauseGuard::Or { left, right, .. }
-            | ClauseGuard::And { left, right, .. } => {
+            | ClauseGuard::And { left, right, .. }
+            | ClauseGuard::AddInt { left, right, .. }
+            | ClauseGuard::AddFloat { left, right, .. }
+            | ClauseGuard::SubInt { left, right, .. }
+            | ClauseGuard::SubFloat { left, right, .. }
+            | ClauseGuard::MultInt { left, right, .. }
+            | ClauseGuard::MultFloat { left, right, .. }
+            | ClauseGuard::DivInt { left, right, .. }
+            | ClauseGuard::DivFloat { left, right, .. }
+            | ClauseGuard::RemainderInt { left, right, .. }
+            | ClauseGuard::Concatenate { left, right, .. } => {
                 self.guard(left);
                 self.guard(right);
             }
@@ -337,6 +347,10 @@ impl<'a> CallGraphBuilder<'a> {
 
             ClauseGuard::TupleIndex { tuple, .. } => self.guard(tuple),
 
+            ClauseGuard::FieldAccess { container, .. } => self.guard(container),
+
+            ClauseGuard::Not { expression, .. } => self.guard(expression),
+
             ClauseGuard::Constant(constant) => self.constant(constant),
         }
     }
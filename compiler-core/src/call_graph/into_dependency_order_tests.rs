@@ -13,6 +13,7 @@ fn parse_and_order(functions: &[Input]) -> Result<Vec<Vec<SmolStr>>, Error> {
         .map(|input| match input {
             Input::Internal(name, arguments, src) => ModuleFunction::Internal(Function {
                 name: name.into(),
+                name_location: Default::default(),
                 arguments: arguments
                     .iter()
                     .map(|name| Arg {
@@ -32,6 +33,7 @@ fn parse_and_order(functions: &[Input]) -> Result<Vec<Vec<SmolStr>>, Error> {
             }),
             Input::External(name) => ModuleFunction::External(ExternalFunction {
                 name: name.into(),
+                name_location: Default::default(),
                 arguments: vec![],
                 module: "themodule".into(),
                 fun: name.into(),
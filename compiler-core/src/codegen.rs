@@ -1,6 +1,11 @@
 use crate::{
-    build::Module, config::PackageConfig, erlang, io::FileSystemWriter, javascript,
-    line_numbers::LineNumbers, Result,
+    build::Module,
+    config::PackageConfig,
+    erlang,
+    io::{FileSystemIO, FileSystemWriter},
+    javascript,
+    line_numbers::LineNumbers,
+    Error, Result,
 };
 use itertools::Itertools;
 use std::{fmt::Debug, path::Path};
@@ -43,7 +48,14 @@ impl<'a> Erlang<'a> {
         let name = format!("{erl_name}.erl");
         let path = self.build_directory.join(&name);
         let line_numbers = LineNumbers::new(&module.code);
-        let output = erlang::module(&module.ast, &line_numbers);
+        let src_path = module.input_path.to_string_lossy();
+        let src_path = src_path.trim_start_matches("./");
+        let output =
+            erlang::module(&module.ast, &line_numbers, src_path).map_err(|error| Error::Erlang {
+                path: module.input_path.clone(),
+                src: module.code.clone(),
+                error,
+            });
         tracing::debug!(name = ?name, "Generated Erlang module");
         writer.write(&path, &output?)
     }
@@ -159,13 +171,14 @@ impl<'a> JavaScript<'a> {
         }
     }
 
-    pub fn render(&self, writer: &impl FileSystemWriter, modules: &[Module]) -> Result<()> {
+    pub fn render(&self, writer: &impl FileSystemIO, modules: &[Module]) -> Result<()> {
+        let current_directory = writer.current_dir()?;
         for module in modules {
             let js_name = module.name.clone();
             if self.typescript == TypeScriptDeclarations::Emit {
                 self.ts_declaration(writer, module, &js_name)?;
             }
-            self.js_module(writer, module, &js_name)?
+            self.js_module(writer, module, &js_name, &current_directory)?
         }
         self.write_prelude(writer)?;
         Ok(())
@@ -205,13 +218,24 @@ impl<'a> JavaScript<'a> {
         writer: &impl FileSystemWriter,
         module: &Module,
         js_name: &str,
+        current_directory: &Path,
     ) -> Result<()> {
         let name = format!("{js_name}.mjs");
         let path = self.output_directory.join(name);
         let line_numbers = LineNumbers::new(&module.code);
-        let output =
-            javascript::module(&module.ast, &line_numbers, &module.input_path, &module.code);
+        let (code, source_map) = javascript::module_with_source_map(
+            &module.ast,
+            &line_numbers,
+            &module.input_path,
+            &module.code,
+            &path,
+            current_directory,
+        )?;
         tracing::debug!(name = ?js_name, "Generated js module");
-        writer.write(&path, &output?)
+        writer.write(&path, &code)?;
+        writer.write(
+            &self.output_directory.join(format!("{js_name}.mjs.map")),
+            &source_map,
+        )
     }
 }
@@ -17,7 +17,6 @@ use crate::{
         ModuleValueConstructor, PatternConstructor, Type, TypeVar, ValueConstructor,
         ValueConstructorVariant,
     },
-    Result,
 };
 use heck::ToSnakeCase;
 use itertools::Itertools;
@@ -37,6 +36,13 @@ fn module_name_atom(module: &str) -> Document<'static> {
     atom(module.replace('/', "@"))
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The index of a record field accessed in a guard was not recorded when
+    /// the guard was type checked.
+    UnknownFieldIndex { label: SmolStr, location: SrcSpan },
+}
+
 #[derive(Debug, Clone)]
 struct Env<'a> {
     module: &'a str,
@@ -44,6 +50,9 @@ struct Env<'a> {
     line_numbers: &'a LineNumbers,
     current_scope_vars: im::HashMap<String, usize>,
     erl_function_scope_vars: im::HashMap<String, usize>,
+    /// The first error found while generating the function. Code generation
+    /// carries on after it, but the code is not used.
+    error: Option<Error>,
 }
 
 impl<'env> Env<'env> {
@@ -55,6 +64,7 @@ impl<'env> Env<'env> {
             line_numbers,
             function,
             module,
+            error: None,
         }
     }
 
@@ -142,14 +152,22 @@ pub fn record_definition(name: &str, fields: &[(&str, Arc<Type>)]) -> String {
     .to_pretty_string(MAX_COLUMNS)
 }
 
-pub fn module<'a>(module: &'a TypedModule, line_numbers: &'a LineNumbers) -> Result<String> {
-    Ok(module_document(module, line_numbers)?.to_pretty_string(MAX_COLUMNS))
+/// Generates the Erlang for a module. `src_path` is the path of the Gleam
+/// source file, which is given to the Erlang compiler with `-file` attributes
+/// so that stack traces and cover reports refer to the Gleam code.
+pub fn module<'a>(
+    module: &'a TypedModule,
+    line_numbers: &'a LineNumbers,
+    src_path: &'a str,
+) -> Result<String, Error> {
+    Ok(module_document(module, line_numbers, src_path)?.to_pretty_string(MAX_COLUMNS))
 }
 
 fn module_document<'a>(
     module: &'a TypedModule,
     line_numbers: &'a LineNumbers,
-) -> Result<Document<'a>> {
+    src_path: &'a str,
+) -> Result<Document<'a>, Error> {
     let mut exports = vec![];
     let mut type_defs = vec![];
     let mut type_exports = vec![];
@@ -214,11 +232,13 @@ fn module_document<'a>(
         concat(Itertools::intersperse(type_defs.into_iter(), lines(2))).append(lines(2))
     };
 
+    let statements: Vec<_> = module
+        .statements
+        .iter()
+        .map(|s| statement(&module.name, s, &module.name, line_numbers, src_path))
+        .collect::<Result<_, _>>()?;
     let statements = concat(Itertools::intersperse(
-        module
-            .statements
-            .iter()
-            .flat_map(|s| statement(&module.name, s, &module.name, line_numbers)),
+        statements.into_iter().flatten(),
         lines(2),
     ));
 
@@ -379,8 +399,9 @@ fn statement<'a>(
     statement: &'a TypedStatement,
     module: &'a str,
     line_numbers: &'a LineNumbers,
-) -> Vec<Document<'a>> {
-    match statement {
+    src_path: &'a str,
+) -> Result<Vec<Document<'a>>, Error> {
+    Ok(match statement {
         Statement::TypeAlias(TypeAlias { .. })
         | Statement::CustomType(CustomType { .. })
         | Statement::Import(Import { .. })
@@ -389,14 +410,24 @@ fn statement<'a>(
         | Statement::ExternalFunction(ExternalFunction { public: false, .. }) => vec![],
 
         Statement::Function(Function {
+            location,
             arguments: args,
             name,
             body,
             return_type,
             ..
-        }) => vec![mod_fun(name, args, body, module, return_type, line_numbers)],
+        }) => vec![mod_fun(
+            name,
+            args,
+            body,
+            module,
+            return_type,
+            line_numbers,
+            file_attribute(src_path, *location, line_numbers),
+        )?],
 
         Statement::ExternalFunction(ExternalFunction {
+            location,
             fun,
             module,
             arguments: args,
@@ -410,8 +441,9 @@ fn statement<'a>(
             fun,
             args,
             return_type,
+            file_attribute(src_path, *location, line_numbers),
         )],
-    }
+    })
 }
 
 fn mod_fun<'a>(
@@ -421,7 +453,8 @@ fn mod_fun<'a>(
     module: &'a str,
     return_type: &'a Arc<Type>,
     line_numbers: &'a LineNumbers,
-) -> Document<'a> {
+    file_attribute: Document<'a>,
+) -> Result<Document<'a>, Error> {
     let mut env = Env::new(module, name, line_numbers);
     let var_usages = collect_type_var_usages(
         HashMap::new(),
@@ -432,11 +465,18 @@ fn mod_fun<'a>(
     let return_spec = type_printer.print(return_type);
     let spec = fun_spec(name, args_spec, return_spec);
 
-    spec.append(atom(name.to_string()))
-        .append(fun_args(args, &mut env))
+    let arguments = fun_args(args, &mut env);
+    let body = expr(body, &mut env);
+    if let Some(error) = env.error {
+        return Err(error);
+    }
+    Ok(spec
+        .append(file_attribute)
+        .append(atom(name.to_string()))
+        .append(arguments)
         .append(" ->")
-        .append(line().append(expr(body, &mut env)).nest(INDENT).group())
-        .append(".")
+        .append(line().append(body).nest(INDENT).group())
+        .append("."))
 }
 
 fn fun_args<'a>(args: &'a [TypedArg], env: &mut Env<'a>) -> Document<'a> {
@@ -479,6 +519,29 @@ fn fun_spec<'a>(
         .group()
 }
 
+/// A `-file` attribute giving the Gleam source location of the function that
+/// follows it. The lines of the function are counted from the given line.
+///
+/// Erlang only accepts `-file` attributes between forms, so there cannot be
+/// one for each expression. The lines within a function match the Gleam ones
+/// while the generated code takes as many lines as the Gleam code, and are
+/// later than them after it takes more, such as for each clause of a `case`.
+fn file_attribute<'a>(
+    src_path: &str,
+    location: SrcSpan,
+    line_numbers: &LineNumbers,
+) -> Document<'a> {
+    let path = src_path.replace('\\', "/").replace('"', "\\\"");
+    docvec![
+        "-file(\"",
+        Document::String(path),
+        "\", ",
+        line_numbers.line_number(location.start),
+        ").",
+        line()
+    ]
+}
+
 fn atom(value: String) -> Document<'static> {
     Document::String(escape_atom(value))
 }
@@ -1107,16 +1170,88 @@ fn bare_clause_guard<'a>(guard: &'a TypedClauseGuard, env: &mut Env<'a>) -> Docu
             .append(" =< ")
             .append(clause_guard(right, env)),
 
+        ClauseGuard::AddInt { left, right, .. } => clause_guard(left, env)
+            .append(" + ")
+            .append(clause_guard(right, env)),
+
+        ClauseGuard::AddFloat { left, right, .. } => clause_guard(left, env)
+            .append(" + ")
+            .append(clause_guard(right, env)),
+
+        ClauseGuard::SubInt { left, right, .. } => clause_guard(left, env)
+            .append(" - ")
+            .append(clause_guard(right, env)),
+
+        ClauseGuard::SubFloat { left, right, .. } => clause_guard(left, env)
+            .append(" - ")
+            .append(clause_guard(right, env)),
+
+        ClauseGuard::MultInt { left, right, .. } => clause_guard(left, env)
+            .append(" * ")
+            .append(clause_guard(right, env)),
+
+        ClauseGuard::MultFloat { left, right, .. } => clause_guard(left, env)
+            .append(" * ")
+            .append(clause_guard(right, env)),
+
+        ClauseGuard::DivInt { left, right, .. } => {
+            clause_guard_division(left, right, "div", "0", "1", env)
+        }
+
+        ClauseGuard::DivFloat { left, right, .. } => {
+            clause_guard_division(left, right, "/", "0.0", "1.0", env)
+        }
+
+        ClauseGuard::RemainderInt { left, right, .. } => {
+            clause_guard_division(left, right, "rem", "0", "1", env)
+        }
+
+        ClauseGuard::Concatenate { left, right, .. } => bit_string([
+            clause_guard_concatenate_argument(left, env),
+            clause_guard_concatenate_argument(right, env),
+        ]),
+
+        ClauseGuard::Not { expression, .. } => docvec!["not ", clause_guard(expression, env)],
+
         // Only local variables are supported and the typer ensures that all
         // ClauseGuard::Vars are local variables
         ClauseGuard::Var { name, .. } => env.local_var_name(name),
 
         ClauseGuard::TupleIndex { tuple, index, .. } => tuple_index_inline(tuple, *index, env),
 
+        ClauseGuard::FieldAccess {
+            container,
+            index: Some(index),
+            ..
+        } => tuple_index_inline(container, index + 1, env),
+
+        ClauseGuard::FieldAccess {
+            location,
+            label,
+            index: None,
+            ..
+        } => {
+            let _ = env.error.get_or_insert(Error::UnknownFieldIndex {
+                label: label.clone(),
+                location: *location,
+            });
+            nil()
+        }
+
         ClauseGuard::Constant(constant) => const_inline(constant, env),
     }
 }
 
+fn clause_guard_concatenate_argument<'a>(
+    guard: &'a TypedClauseGuard,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    match guard {
+        ClauseGuard::Constant(Constant::String { value, .. }) => docvec!['"', value, "\"/utf8"],
+        _ => docvec![clause_guard(guard, env), "/binary"],
+    }
+}
+
 fn tuple_index_inline<'a>(
     tuple: &'a TypedClauseGuard,
     index: u64,
@@ -1129,6 +1264,48 @@ fn tuple_index_inline<'a>(
         .append(wrap_args([index_doc, tuple_doc]))
 }
 
+/// Dividing by zero in Gleam returns zero, while in Erlang it raises an
+/// error, which would make the guard fail. As a `case` cannot be used in a
+/// guard the operands are instead picked with `erlang:map_get`, dividing zero
+/// by one when the divisor is zero.
+fn clause_guard_division<'a>(
+    left: &'a TypedClauseGuard,
+    right: &'a TypedClauseGuard,
+    op: &'static str,
+    zero: &'static str,
+    one: &'static str,
+    env: &mut Env<'a>,
+) -> Document<'a> {
+    if matches!(right, ClauseGuard::Constant(constant) if constant.is_non_zero_number()) {
+        return docvec![
+            clause_guard(left, env),
+            " ",
+            op,
+            " ",
+            clause_guard(right, env)
+        ];
+    }
+    // `==` is used rather than `=:=` so that `-0.0` is also taken to be zero
+    let is_zero = docvec![clause_guard(right, env), " == ", zero];
+    docvec![
+        "erlang:map_get(",
+        is_zero.clone(),
+        ", #{true => ",
+        zero,
+        ", false => ",
+        clause_guard(left, env),
+        "}) ",
+        op,
+        " erlang:map_get(",
+        is_zero,
+        ", #{true => ",
+        one,
+        ", false => ",
+        clause_guard(right, env),
+        "})"
+    ]
+}
+
 fn clause_guard<'a>(guard: &'a TypedClauseGuard, env: &mut Env<'a>) -> Document<'a> {
     match guard {
         // Binary ops are wrapped in parens
@@ -1143,15 +1320,27 @@ fn clause_guard<'a>(guard: &'a TypedClauseGuard, env: &mut Env<'a>) -> Document<
         | ClauseGuard::GtFloat { .. }
         | ClauseGuard::GtEqFloat { .. }
         | ClauseGuard::LtFloat { .. }
-        | ClauseGuard::LtEqFloat { .. } => "("
+        | ClauseGuard::LtEqFloat { .. }
+        | ClauseGuard::AddInt { .. }
+        | ClauseGuard::AddFloat { .. }
+        | ClauseGuard::SubInt { .. }
+        | ClauseGuard::SubFloat { .. }
+        | ClauseGuard::MultInt { .. }
+        | ClauseGuard::MultFloat { .. }
+        | ClauseGuard::DivInt { .. }
+        | ClauseGuard::DivFloat { .. }
+        | ClauseGuard::RemainderInt { .. }
+        | ClauseGuard::Not { .. } => "("
             .to_doc()
             .append(bare_clause_guard(guard, env))
             .append(")"),
 
         // Values are not wrapped
-        ClauseGuard::Constant(_) | ClauseGuard::Var { .. } | ClauseGuard::TupleIndex { .. } => {
-            bare_clause_guard(guard, env)
-        }
+        ClauseGuard::Constant(_)
+        | ClauseGuard::Var { .. }
+        | ClauseGuard::TupleIndex { .. }
+        | ClauseGuard::FieldAccess { .. }
+        | ClauseGuard::Concatenate { .. } => bare_clause_guard(guard, env),
     }
 }
 
@@ -1601,6 +1790,7 @@ fn external_fun<'a>(
     fun: &'a str,
     args: &'a [TypedExternalFnArg],
     return_type: &'a Arc<Type>,
+    file_attribute: Document<'a>,
 ) -> Document<'a> {
     let chars: String = incrementing_args_list(args.len());
     let var_usages = collect_type_var_usages(
@@ -1614,16 +1804,18 @@ fn external_fun<'a>(
     let return_spec = type_printer.print(return_type);
     let spec = fun_spec(name, args_spec, return_spec);
 
-    spec.append(atom(name.to_string())).append(
-        Document::String(format!("({chars}) ->"))
-            .append(line())
-            .append(atom(module.to_string()))
-            .append(":")
-            .append(atom(fun.to_string()))
-            .append(Document::String(format!("({chars}).")))
-            .nest(INDENT)
-            .group(),
-    )
+    spec.append(file_attribute)
+        .append(atom(name.to_string()))
+        .append(
+            Document::String(format!("({chars}) ->"))
+                .append(line())
+                .append(atom(module.to_string()))
+                .append(":")
+                .append(atom(fun.to_string()))
+                .append(Document::String(format!("({chars}).")))
+                .nest(INDENT)
+                .group(),
+        )
 }
 
 fn variable_name(name: &str) -> String {
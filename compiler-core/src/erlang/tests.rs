@@ -53,40 +53,48 @@ macro_rules! assert_erl {
         )
         .expect("should successfully infer");
         let line_numbers = LineNumbers::new($src);
-        let output = module(&ast, &line_numbers).unwrap();
+        let src_path = format!("src/{}.gleam", ast.name);
+        let output = module(&ast, &line_numbers, &src_path).unwrap();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 
     ($src:expr $(,)?) => {{
-        use $crate::{
-            build::Origin, erlang::module, line_numbers::LineNumbers, type_::build_prelude,
-            uid::UniqueIdGenerator,
-        };
-        let (mut ast, _) = $crate::parse::parse_module($src).expect("syntax error");
-        ast.name = "the_app".into();
-        let mut modules = im::HashMap::new();
-        let ids = UniqueIdGenerator::new();
-        // DUPE: preludeinsertion
-        // TODO: Currently we do this here and also in the tests. It would be better
-        // to have one place where we create all this required state for use in each
-        // place.
-        let _ = modules.insert("gleam".into(), build_prelude(&ids));
-        let ast = $crate::analyse::infer_module(
-            $crate::build::Target::Erlang,
-            &ids,
-            ast,
-            Origin::Src,
-            &"thepackage".into(),
-            &modules,
-            &$crate::warning::TypeWarningEmitter::null(),
-        )
-        .expect("should successfully infer");
-        let line_numbers = LineNumbers::new($src);
-        let output = module(&ast, &line_numbers).unwrap();
+        let output = $crate::erlang::tests::compile_test_project($src);
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
     }};
 }
 
+/// The Erlang generated for the Gleam source, compiled as the `the_app`
+/// module from `src/the_app.gleam`.
+pub fn compile_test_project(src: &str) -> String {
+    use crate::{
+        build::Origin, erlang::module, line_numbers::LineNumbers, type_::build_prelude,
+        uid::UniqueIdGenerator,
+    };
+    let (mut ast, _) = crate::parse::parse_module(src).expect("syntax error");
+    ast.name = "the_app".into();
+    let mut modules = im::HashMap::new();
+    let ids = UniqueIdGenerator::new();
+    // DUPE: preludeinsertion
+    // TODO: Currently we do this here and also in the tests. It would be better
+    // to have one place where we create all this required state for use in each
+    // place.
+    let _ = modules.insert("gleam".into(), build_prelude(&ids));
+    let ast = crate::analyse::infer_module(
+        crate::build::Target::Erlang,
+        &ids,
+        ast,
+        Origin::Src,
+        &"thepackage".into(),
+        &modules,
+        &crate::warning::TypeWarningEmitter::null(),
+    )
+    .expect("should successfully infer");
+    let line_numbers = LineNumbers::new(src);
+    let src_path = format!("src/{}.gleam", ast.name);
+    module(&ast, &line_numbers, &src_path).expect("should generate")
+}
+
 #[test]
 fn integration_test() {
     assert_erl!(
@@ -188,7 +196,7 @@ pub fn tail(list) { case list { [x, ..xs] -> xs z -> list } }
 
 #[test]
 fn integration_test5() {
-    assert_erl!("pub fn tail(list) { case list { [x, ..] -> x } }");
+    assert_erl!("pub fn tail(list, default) { case list { [x, ..] -> x [] -> default } }");
 }
 
 #[test]
@@ -540,6 +548,7 @@ fn guard_variable_rewriting() {
       let a = a
       a
     }
+    _ -> 0.0
   }
 }
 "
@@ -560,3 +569,37 @@ pub type Box {
 "
     )
 }
+
+/// The Gleam line the Erlang compiler gives for the first line of the
+/// generated code containing `code`, being the line of the `-file` attribute
+/// before it plus the number of lines since.
+fn reported_line(src: &str, code: &str) -> Option<usize> {
+    let output = compile_test_project(src);
+    let mut file_attribute = None;
+    for (index, line) in output.lines().enumerate() {
+        if let Some(attribute) = line.strip_prefix("-file(\"src/the_app.gleam\", ") {
+            let gleam_line: usize = attribute.trim_end_matches(").").parse().ok()?;
+            file_attribute = Some((index, gleam_line));
+        } else if line.contains(code) {
+            let (attribute_index, gleam_line) = file_attribute?;
+            return Some(gleam_line + index - attribute_index - 1);
+        }
+    }
+    None
+}
+
+#[test]
+fn file_attribute_lines_within_function_body() {
+    let src = "pub fn main() {
+  let x = 1
+  let y = x + 1
+  y
+}
+
+pub fn other() {
+  main()
+}
+";
+    assert_eq!(reported_line(src, "Y = X + 1"), Some(3));
+    assert_eq!(reported_line(src, "main()."), Some(8));
+}
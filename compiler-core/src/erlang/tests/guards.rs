@@ -150,6 +150,7 @@ pub fn main() {
   let x = 0.123
   case x {
     _ if x == 3.14 -> 1
+    _ -> 0
   }
 }
 "#
@@ -164,6 +165,7 @@ pub fn main() {
   let x = 0.123
   case x {
     _ if 0.123 <. x -> 1
+    _ -> 0
   }
 }
 "#
@@ -177,6 +179,7 @@ fn clause_guards21() {
 pub fn main(x) {
   case x {
     _ if x == [1, 2, 3] -> 1
+    _ -> 0
   }
 }
 "#
@@ -241,6 +244,7 @@ pub fn main() {
   let x = 0
   case x {
     _ if x == 0 -> 1
+    _ -> 0
   }
 }
 "#
@@ -255,6 +259,7 @@ pub fn main() {
   let x = 0
   case x {
     _ if 0 < x -> 1
+    _ -> 0
   }
 }
 "#
@@ -270,6 +275,7 @@ fn clause_guards27() {
 pub fn main() {
   case "test" {
     x if x == "test" -> 1
+    _ -> 0
   }
 }
 "#
@@ -440,3 +446,94 @@ pub fn main(arg) {
 "#
     );
 }
+
+#[test]
+fn int_arithmetic_in_guards() {
+    assert_erl!(
+        r#"
+pub fn main(x, y) {
+  case x {
+    _ if x + y * 2 > 10 - y / 2 % 3 -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn float_arithmetic_in_guards() {
+    assert_erl!(
+        r#"
+pub fn main(x, y) {
+  case x {
+    _ if { x +. y } *. 2.0 >. 10.0 -. y /. 2.0 -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+// Dividing by zero returns zero in Gleam, so the guard must not fail when the
+// divisor is zero.
+#[test]
+fn division_by_variable_in_guards() {
+    assert_erl!(
+        r#"
+pub fn main(x, y, z) {
+  case x {
+    _ if x / y == 0 || x % y == 0 -> 1
+    _ if z /. z == 0.0 -> 2
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn string_concatenation_in_guards() {
+    assert_erl!(
+        r#"
+pub fn main(x) {
+  case x {
+    _ if x <> "!" == "Hello!" -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn negation_in_guards() {
+    assert_erl!(
+        r#"
+pub fn main(x, y) {
+  case x {
+    _ if !x && !{ y || x } -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn record_access_in_guards() {
+    assert_erl!(
+        r#"
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main(people: #(Person, Int)) {
+  case people {
+    #(person, limit) if person.age > limit -> person.name
+    _ -> "Unknown"
+  }
+}
+"#
+    );
+}
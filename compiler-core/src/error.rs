@@ -6,7 +6,7 @@ use crate::{ast::BinOp, parse::error::ParseErrorType, type_::Type};
 use crate::{
     bit_string,
     diagnostic::Level,
-    javascript,
+    erlang, javascript,
     type_::{pretty::Printer, UnifyErrorSituation},
 };
 use hexpm::version::pubgrub_report::{DefaultStringReporter, Reporter};
@@ -18,6 +18,7 @@ use std::fmt::Debug;
 use std::path::{Path, PathBuf};
 use termcolor::Buffer;
 use thiserror::Error;
+use vec1::Vec1;
 
 pub type Name = SmolStr;
 
@@ -44,14 +45,14 @@ pub enum Error {
     Parse {
         path: PathBuf,
         src: SmolStr,
-        error: crate::parse::error::ParseError,
+        errors: Vec1<crate::parse::error::ParseError>,
     },
 
     #[error("type checking failed")]
     Type {
         path: PathBuf,
         src: SmolStr,
-        error: crate::type_::Error,
+        errors: Vec1<crate::type_::Error>,
     },
 
     #[error("unknown import {import}")]
@@ -127,6 +128,13 @@ pub enum Error {
         reason: InvalidProjectNameReason,
     },
 
+    #[error("{name} is not a valid name for the renamed {kind}")]
+    InvalidRename {
+        name: String,
+        kind: &'static str,
+        reason: InvalidRenameReason,
+    },
+
     #[error("{input} is not a valid version. {error}")]
     InvalidVersionFormat { input: String, error: String },
 
@@ -152,6 +160,13 @@ pub enum Error {
         error: crate::javascript::Error,
     },
 
+    #[error("erlang codegen failed")]
+    Erlang {
+        path: PathBuf,
+        src: SmolStr,
+        error: erlang::Error,
+    },
+
     #[error("Invalid runtime for {target} target: {invalid_runtime}")]
     InvalidRuntime {
         target: Target,
@@ -274,6 +289,14 @@ pub enum InvalidProjectNameReason {
     GleamReservedModule,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum InvalidRenameReason {
+    Format,
+    ErlangReservedWord,
+    GleamReservedWord,
+    AlreadyInUse,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum StandardIoAction {
     Read,
@@ -353,13 +376,48 @@ fn did_you_mean(name: &str, options: &[SmolStr]) -> Option<String> {
 
 impl Error {
     pub fn pretty_string(&self) -> String {
-        self.to_diagnostic().pretty_string()
+        self.to_diagnostics()
+            .iter()
+            .map(Diagnostic::pretty_string)
+            .join("\n")
     }
 
     pub fn pretty(&self, buffer: &mut Buffer) {
-        self.to_diagnostic().write(buffer)
+        use std::io::Write;
+        for (i, diagnostic) in self.to_diagnostics().iter().enumerate() {
+            if i > 0 {
+                writeln!(buffer).expect("write new line");
+            }
+            diagnostic.write(buffer);
+        }
+    }
+
+    /// The diagnostics for the error, of which there is one for each problem
+    /// in the case of errors that report several problems at once, such as
+    /// syntax and type errors.
+    pub fn to_diagnostics(&self) -> Vec<Diagnostic> {
+        match self {
+            Error::Parse { path, src, errors } => errors
+                .iter()
+                .map(|error| parse_diagnostic(path, src, error))
+                .collect(),
+            Error::Type { path, src, errors } => errors
+                .iter()
+                .map(|error| {
+                    Error::Type {
+                        path: path.clone(),
+                        src: src.clone(),
+                        errors: Vec1::new(error.clone()),
+                    }
+                    .to_diagnostic()
+                })
+                .collect(),
+            _ => vec![self.to_diagnostic()],
+        }
     }
 
+    /// The diagnostic for the error. For errors that report several problems
+    /// this is the diagnostic of the first of them.
     pub fn to_diagnostic(&self) -> Diagnostic {
         use crate::type_::Error as TypeError;
         match self {
@@ -415,6 +473,32 @@ This prefix is intended for official Gleam packages only.",
                 }
             }
 
+            Error::InvalidRename { name, kind, reason } => {
+                let text = wrap_format!(
+                    "This {} cannot be renamed to `{}` as {}",
+                    kind,
+                    name,
+                    match reason {
+                        InvalidRenameReason::Format =>
+                            format!("it does not have the correct format for the name of a {kind}."),
+                        InvalidRenameReason::ErlangReservedWord =>
+                            "it is a reserved word in Erlang.".into(),
+                        InvalidRenameReason::GleamReservedWord =>
+                            "it is a reserved word in Gleam.".into(),
+                        InvalidRenameReason::AlreadyInUse =>
+                            "that name is already in use where it is referenced.".into(),
+                    }
+                );
+
+                Diagnostic {
+                    title: "Invalid name".into(),
+                    text,
+                    hint: None,
+                    level: Level::Error,
+                    location: None,
+                }
+            }
+
             Error::ProjectRootAlreadyExist { path } => Diagnostic {
                 title: "Project folder already exists".into(),
                 text: format!("Project folder root:\n\n  {path}"),
@@ -686,7 +770,7 @@ Second: {}",
                 }
             }
 
-            Error::Type { path, src, error } => match error {
+            Error::Type { path, src, errors } => match errors.first() {
                 TypeError::SrcImportingTest {
                     location,
                     src_module,
@@ -1992,35 +2076,7 @@ value, or use `assert` rather than `let`."
                 },
             },
 
-            Error::Parse { path, src, error } => {
-                let (label, extra) = error.details();
-                let text = extra.join("\n");
-
-                let adjusted_location = if error.error == ParseErrorType::UnexpectedEof {
-                    crate::ast::SrcSpan {
-                        start: (src.len() - 1) as u32,
-                        end: (src.len() - 1) as u32,
-                    }
-                } else {
-                    error.location
-                };
-
-                Diagnostic {
-                    title: "Syntax error".into(),
-                    text,
-                    hint: None,
-                    level: Level::Error,
-                    location: Some(Location {
-                        label: Label {
-                            text: Some(label.to_string()),
-                            span: adjusted_location,
-                        },
-                        path: path.clone(),
-                        src: src.clone(),
-                        extra_labels: vec![],
-                    }),
-                }
-            }
+            Error::Parse { path, src, errors } => parse_diagnostic(path, src, errors.first()),
 
             Error::ImportCycle { modules } => {
                 let mut text = "The import statements for these modules form a cycle:
@@ -2162,6 +2218,27 @@ Fix the warnings and try again."
                 },
             },
 
+            Error::Erlang { src, path, error } => match error {
+                erlang::Error::UnknownFieldIndex { label, location } => Diagnostic {
+                    title: "Unknown record field".into(),
+                    text: format!(
+                        "The position of the field `{label}` in the record could not be found.
+This is a bug in the Gleam compiler, please report it."
+                    ),
+                    hint: None,
+                    level: Level::Error,
+                    location: Some(Location {
+                        label: Label {
+                            text: None,
+                            span: *location,
+                        },
+                        path: path.clone(),
+                        src: src.clone(),
+                        extra_labels: vec![],
+                    }),
+                },
+            },
+
             Error::DownloadPackageError {
                 package_name,
                 package_version,
@@ -2326,6 +2403,40 @@ issue in our tracker: https://github.com/gleam-lang/gleam/issues",
     }
 }
 
+fn parse_diagnostic(
+    path: &Path,
+    src: &SmolStr,
+    error: &crate::parse::error::ParseError,
+) -> Diagnostic {
+    let (label, extra) = error.details();
+    let text = extra.join("\n");
+
+    let adjusted_location = if error.error == ParseErrorType::UnexpectedEof {
+        crate::ast::SrcSpan {
+            start: (src.len() - 1) as u32,
+            end: (src.len() - 1) as u32,
+        }
+    } else {
+        error.location
+    };
+
+    Diagnostic {
+        title: "Syntax error".into(),
+        text,
+        hint: None,
+        level: Level::Error,
+        location: Some(Location {
+            label: Label {
+                text: Some(label.to_string()),
+                span: adjusted_location,
+            },
+            path: path.to_path_buf(),
+            src: src.clone(),
+            extra_labels: vec![],
+        }),
+    }
+}
+
 fn std_io_error_kind_text(kind: &std::io::ErrorKind) -> String {
     use std::io::ErrorKind;
     match kind {
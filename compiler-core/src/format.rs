@@ -21,11 +21,15 @@ use vec1::Vec1;
 const INDENT: isize = 2;
 
 pub fn pretty(writer: &mut impl Utf8Writer, src: &SmolStr, path: &Path) -> Result<()> {
-    let (module, extra) = crate::parse::parse_module(src).map_err(|error| Error::Parse {
-        path: path.to_path_buf(),
-        src: src.clone(),
-        error,
-    })?;
+    let parsed = crate::parse::parse_module_with_recovery(src);
+    if let Ok(errors) = Vec1::try_from_vec(parsed.errors) {
+        return Err(Error::Parse {
+            path: path.to_path_buf(),
+            src: src.clone(),
+            errors,
+        });
+    }
+    let (module, extra) = (parsed.module, parsed.extra);
     let intermediate = Intermediate::from_extra(&extra, src);
     Formatter::with_comments(&intermediate)
         .module(&module)
@@ -1487,6 +1491,36 @@ impl<'comments> Formatter<'comments> {
             ClauseGuard::LtEqFloat { left, right, .. } => {
                 self.clause_guard_bin_op(" <=. ", clause_guard.precedence(), left, right)
             }
+            ClauseGuard::AddInt { left, right, .. } => {
+                self.clause_guard_bin_op(" + ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::AddFloat { left, right, .. } => {
+                self.clause_guard_bin_op(" +. ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::SubInt { left, right, .. } => {
+                self.clause_guard_bin_op(" - ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::SubFloat { left, right, .. } => {
+                self.clause_guard_bin_op(" -. ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::MultInt { left, right, .. } => {
+                self.clause_guard_bin_op(" * ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::MultFloat { left, right, .. } => {
+                self.clause_guard_bin_op(" *. ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::DivInt { left, right, .. } => {
+                self.clause_guard_bin_op(" / ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::DivFloat { left, right, .. } => {
+                self.clause_guard_bin_op(" /. ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::RemainderInt { left, right, .. } => {
+                self.clause_guard_bin_op(" % ", clause_guard.precedence(), left, right)
+            }
+            ClauseGuard::Concatenate { left, right, .. } => {
+                self.clause_guard_bin_op(" <> ", clause_guard.precedence(), left, right)
+            }
 
             ClauseGuard::Var { name, .. } => name.to_doc(),
 
@@ -1494,6 +1528,22 @@ impl<'comments> Formatter<'comments> {
                 self.clause_guard(tuple).append(".").append(*index).to_doc()
             }
 
+            ClauseGuard::FieldAccess {
+                container, label, ..
+            } => self
+                .clause_guard(container)
+                .append(".")
+                .append(label.as_str()),
+
+            ClauseGuard::Not { expression, .. } => {
+                let precedence = expression.precedence();
+                let expression = self.clause_guard(expression);
+                docvec![
+                    "!",
+                    self.operator_side(expression, clause_guard.precedence(), precedence)
+                ]
+            }
+
             ClauseGuard::Constant(constant) => self.const_expr(constant),
         }
     }
@@ -1578,7 +1628,8 @@ impl<'a> Documentable<'a> for &'a ArgNames {
     fn to_doc(self) -> Document<'a> {
         match self {
             ArgNames::Named { name } | ArgNames::Discard { name } => name.to_doc(),
-            ArgNames::LabelledDiscard { label, name } | ArgNames::NamedLabelled { label, name } => {
+            ArgNames::LabelledDiscard { label, name }
+            | ArgNames::NamedLabelled { label, name, .. } => {
                 docvec![label, " ", name]
             }
         }
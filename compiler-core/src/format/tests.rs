@@ -4657,6 +4657,81 @@ fn remove_braces_case_guard_2() {
     );
 }
 
+#[test]
+fn case_guard_arithmetic() {
+    assert_format!(
+        "fn main() {
+  case x {
+    _ if x + 1 > y * 2 -> Nil
+    _ if { x + 1 } * 2 > y % 3 -> Nil
+    _ if x -. 1.0 <. y /. 2.0 -> Nil
+    _ -> Nil
+  }
+}
+"
+    );
+}
+
+#[test]
+fn case_guard_string_concatenation() {
+    assert_format!(
+        r#"fn main() {
+  case x {
+    _ if x <> "!" == "Hi!" -> Nil
+    _ -> Nil
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn case_guard_negation() {
+    assert_format!(
+        "fn main() {
+  case x {
+    _ if !x -> Nil
+    _ if !{ x || y } && !!y -> Nil
+    _ -> Nil
+  }
+}
+"
+    );
+}
+
+#[test]
+fn case_guard_record_access() {
+    assert_format!(
+        "fn main() {
+  case x {
+    _ if x.name == y.inner.name -> Nil
+    _ -> Nil
+  }
+}
+"
+    );
+}
+
+#[test]
+fn remove_braces_case_guard_arithmetic() {
+    assert_format_rewrite!(
+        "fn main() {
+  case x {
+    _ if { x * 2 } + 1 > 0 -> Nil
+    _ -> Nil
+  }
+}
+",
+        "fn main() {
+  case x {
+    _ if x * 2 + 1 > 0 -> Nil
+    _ -> Nil
+  }
+}
+"
+    );
+}
+
 #[test]
 fn const_multi_line_string_breaks() {
     assert_format!(
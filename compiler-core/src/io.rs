@@ -29,6 +29,11 @@ pub trait Utf8Writer: std::fmt::Write {
     }
 
     fn convert_err<E: std::error::Error>(&self, err: E) -> Error;
+
+    /// Called when the document being written reaches a part that was
+    /// generated from the source code at the given byte index. Writers that
+    /// create source maps record the current position, others ignore it.
+    fn source_position(&mut self, _byte_index: u32) {}
 }
 
 impl Utf8Writer for String {
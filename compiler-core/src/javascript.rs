@@ -1,6 +1,7 @@
 mod expression;
 mod import;
 mod pattern;
+mod source_map;
 #[cfg(test)]
 mod tests;
 mod typescript;
@@ -127,6 +128,14 @@ impl<'a> Generator<'a> {
             self.register_prelude_usage(&mut imports, "float64Bits", None);
         };
 
+        if self.tracker.sized_float_segment_used {
+            self.register_prelude_usage(&mut imports, "sizedFloat", None);
+        };
+
+        if self.tracker.native_endianness_used {
+            self.register_prelude_usage(&mut imports, "isNativeBigEndian", None);
+        };
+
         // Put it all together
 
         if imports.is_empty() && statements.is_empty() {
@@ -167,19 +176,21 @@ impl<'a> Generator<'a> {
             Statement::CustomType(CustomType { .. }) => vec![],
 
             Statement::ModuleConstant(ModuleConstant {
+                location,
                 public,
                 name,
                 value,
                 ..
-            }) => vec![self.module_constant(*public, name, value)],
+            }) => vec![self.module_constant(*location, *public, name, value)],
 
             Statement::Function(Function {
+                location,
                 arguments,
                 name,
                 body,
                 public,
                 ..
-            }) => vec![self.module_function(*public, name, arguments, body)],
+            }) => vec![self.module_function(*location, *public, name, arguments, body)],
 
             Statement::ExternalFunction(ExternalFunction {
                 public,
@@ -399,12 +410,14 @@ impl<'a> Generator<'a> {
 
     fn module_constant(
         &mut self,
+        location: SrcSpan,
         public: bool,
         name: &'a str,
         value: &'a TypedConstant,
     ) -> Output<'a> {
         let head = if public { "export const " } else { "const " };
         Ok(docvec![
+            source_position(location.start),
             head,
             maybe_escape_identifier_doc(name),
             " = ",
@@ -419,6 +432,7 @@ impl<'a> Generator<'a> {
 
     fn module_function(
         &mut self,
+        location: SrcSpan,
         public: bool,
         name: &'a SmolStr,
         args: &'a [TypedArg],
@@ -443,6 +457,7 @@ impl<'a> Generator<'a> {
         };
         let body = generator.function_body(body, args)?;
         Ok(docvec![
+            source_position(location.start),
             head,
             maybe_escape_identifier_doc(name),
             fun_args(args, generator.tail_recursion_used),
@@ -515,14 +530,54 @@ pub fn module(
     path: &Path,
     src: &SmolStr,
 ) -> Result<String, crate::Error> {
-    let document = Generator::new(line_numbers, module)
+    let document = module_document(module, line_numbers, path, src)?;
+    Ok(document.to_pretty_string(80))
+}
+
+/// Generates the JavaScript for a module that is to be written to the output
+/// path, along with a source map relating it to the Gleam source. The code
+/// ends with a comment referencing the source map, which is to be written
+/// alongside it with the `.map` extension added. Relative paths are relative
+/// to the current directory.
+pub fn module_with_source_map(
+    module: &TypedModule,
+    line_numbers: &LineNumbers,
+    path: &Path,
+    src: &SmolStr,
+    output_path: &Path,
+    current_directory: &Path,
+) -> Result<(String, String), crate::Error> {
+    let document = module_document(module, line_numbers, path, src)?;
+    let mut writer = source_map::SourceMapWriter::new(src, line_numbers);
+    document.pretty_print(80, &mut writer)?;
+
+    let file = output_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let source = source_map::source_path(
+        current_directory,
+        output_path.parent().unwrap_or(Path::new("")),
+        path,
+    );
+    let (mut code, source_map) = writer.finish(&file, &source);
+    code.push_str(&format!("//# sourceMappingURL={file}.map\n"));
+    Ok((code, source_map))
+}
+
+fn module_document<'a>(
+    module: &'a TypedModule,
+    line_numbers: &'a LineNumbers,
+    path: &Path,
+    src: &SmolStr,
+) -> Result<Document<'a>, crate::Error> {
+    Generator::new(line_numbers, module)
         .compile()
         .map_err(|error| crate::Error::JavaScript {
             path: path.to_path_buf(),
             src: src.clone(),
             error,
-        })?;
-    Ok(document.to_pretty_string(80))
+        })
 }
 
 pub fn ts_declaration(
@@ -717,4 +772,6 @@ pub(crate) struct UsageTracker {
     pub string_bit_string_segment_used: bool,
     pub codepoint_bit_string_segment_used: bool,
     pub float_bit_string_segment_used: bool,
+    pub sized_float_segment_used: bool,
+    pub native_endianness_used: bool,
 }
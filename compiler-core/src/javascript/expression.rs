@@ -165,11 +165,15 @@ impl<'module> Generator<'module> {
 
             TypedExpr::NegateInt { value, .. } => self.negate_with("- ", value),
         }?;
-        Ok(if expression.handles_own_return() {
+        let document = if expression.handles_own_return() {
             document
         } else {
             self.wrap_return(document)
-        })
+        };
+        Ok(docvec![
+            source_position(expression.location().start),
+            document
+        ])
     }
 
     fn negate_with<'a>(&mut self, with: &'static str, value: &'a TypedExpr) -> Output<'a> {
@@ -179,49 +183,23 @@ impl<'module> Generator<'module> {
     fn bit_string<'a>(&mut self, segments: &'a [TypedExprBitStringSegment]) -> Output<'a> {
         self.tracker.bit_string_literal_used = true;
 
-        use BitStringSegmentOption as Opt;
-
         // Collect all the values used in segments.
         let segments_array = array(segments.iter().map(|segment| {
             let value = self.not_in_tail_position(|gen| gen.wrap_expression(&segment.value))?;
-            match segment.options.as_slice() {
-                // Ints
-                [] | [Opt::Int { .. }] => Ok(value),
-
-                // Sized ints
-                [Opt::Size { value: size, .. }] => {
-                    self.tracker.sized_integer_segment_used = true;
+            let size = match segment.options.iter().find_map(|option| option.value()) {
+                Some(size) => {
+                    let is_atomic = matches!(size, TypedExpr::Int { .. } | TypedExpr::Var { .. });
                     let size = self.not_in_tail_position(|gen| gen.wrap_expression(size))?;
-                    Ok(docvec!["sizedInt(", value, ", ", size, ")"])
+                    Some(segment_size_in_bits(size, is_atomic, &segment.options))
                 }
-
-                // Floats
-                [Opt::Float { .. }] => {
-                    self.tracker.float_bit_string_segment_used = true;
-                    Ok(docvec!["float64Bits(", value, ")"])
-                }
-
-                // UTF8 strings
-                [Opt::Utf8 { .. }] => {
-                    self.tracker.string_bit_string_segment_used = true;
-                    Ok(docvec!["stringBits(", value, ")"])
-                }
-
-                // UTF8 codepoints
-                [Opt::Utf8Codepoint { .. }] => {
-                    self.tracker.codepoint_bit_string_segment_used = true;
-                    Ok(docvec!["codepointBits(", value, ")"])
-                }
-
-                // Bit strings
-                [Opt::BitString { .. }] => Ok(docvec![value, ".buffer"]),
-
-                // Anything else
-                _ => Err(Error::Unsupported {
-                    feature: "This bit string segment option".into(),
-                    location: segment.location,
-                }),
-            }
+                None => None,
+            };
+            Ok(bit_string_segment(
+                self.tracker,
+                value,
+                size,
+                &segment.options,
+            ))
         }))?;
 
         Ok(docvec!["toBitString(", segments_array, ")"])
@@ -1141,10 +1119,22 @@ pub(crate) fn constant_expression<'a>(
             Ok(construct_record(module.as_deref(), tag, field_values))
         }
 
-        Constant::BitString { location, .. } => Err(Error::Unsupported {
-            feature: "Bit string syntax".into(),
-            location: *location,
-        }),
+        Constant::BitString { segments, .. } => {
+            tracker.bit_string_literal_used = true;
+            let segments_array = array(segments.iter().map(|segment| {
+                let value = constant_expression(tracker, &segment.value)?;
+                let size = match segment.options.iter().find_map(|option| option.value()) {
+                    Some(size) => {
+                        let is_atomic = matches!(size, Constant::Int { .. } | Constant::Var { .. });
+                        let size = constant_expression(tracker, size)?;
+                        Some(segment_size_in_bits(size, is_atomic, &segment.options))
+                    }
+                    None => None,
+                };
+                Ok(bit_string_segment(tracker, value, size, &segment.options))
+            }))?;
+            Ok(docvec!["toBitString(", segments_array, ")"])
+        }
 
         Constant::Var { name, module, .. } => Ok({
             match module {
@@ -1155,6 +1145,132 @@ pub(crate) fn constant_expression<'a>(
     }
 }
 
+/// The JavaScript for a segment of a bit string value, given its value and
+/// its size in bits if it has one.
+fn bit_string_segment<'a, Value>(
+    tracker: &mut UsageTracker,
+    value: Document<'a>,
+    size: Option<Document<'a>>,
+    options: &[BitStringSegmentOption<Value>],
+) -> Document<'a> {
+    use BitStringSegmentOption as Opt;
+
+    let endianness = segment_endianness(tracker, options);
+    let call = |function: &'a str, arguments: Vec<Document<'a>>| {
+        let arguments = arguments.into_iter().chain(endianness.clone());
+        docvec![function, "(", join(arguments, ", ".to_doc()), ")"]
+    };
+
+    match (segment_type(options), size) {
+        // Floats
+        (Some(Opt::Float { .. }), None) if endianness.is_none() => {
+            tracker.float_bit_string_segment_used = true;
+            docvec!["float64Bits(", value, ")"]
+        }
+        (Some(Opt::Float { .. }), size) => {
+            tracker.sized_float_segment_used = true;
+            let size = size.unwrap_or_else(|| "64".to_doc());
+            call("sizedFloat", vec![value, size])
+        }
+
+        // Strings
+        (Some(Opt::Utf8 { .. }), _) => {
+            tracker.string_bit_string_segment_used = true;
+            docvec!["stringBits(", value, ")"]
+        }
+        (Some(Opt::Utf16 { .. }), _) => {
+            tracker.string_bit_string_segment_used = true;
+            call("stringBits", vec![value, "\"utf16\"".to_doc()])
+        }
+        (Some(Opt::Utf32 { .. }), _) => {
+            tracker.string_bit_string_segment_used = true;
+            call("stringBits", vec![value, "\"utf32\"".to_doc()])
+        }
+
+        // Codepoints
+        (Some(Opt::Utf8Codepoint { .. }), _) => {
+            tracker.codepoint_bit_string_segment_used = true;
+            docvec!["codepointBits(", value, ")"]
+        }
+        (Some(Opt::Utf16Codepoint { .. }), _) => {
+            tracker.codepoint_bit_string_segment_used = true;
+            call("codepointBits", vec![value, "\"utf16\"".to_doc()])
+        }
+        (Some(Opt::Utf32Codepoint { .. }), _) => {
+            tracker.codepoint_bit_string_segment_used = true;
+            call("codepointBits", vec![value, "\"utf32\"".to_doc()])
+        }
+
+        // Bit strings
+        (Some(Opt::BitString { .. } | Opt::Binary { .. }), None) => value,
+        (Some(Opt::BitString { .. } | Opt::Binary { .. }), Some(size)) => {
+            docvec![value, ".sliceBits(0, ", size, ")"]
+        }
+
+        // Ints
+        (_, None) => value,
+        (_, Some(size)) => {
+            tracker.sized_integer_segment_used = true;
+            call("sizedInt", vec![value, size])
+        }
+    }
+}
+
+/// The option giving the type of a bit string segment, if there is one.
+pub(crate) fn segment_type<Value>(
+    options: &[BitStringSegmentOption<Value>],
+) -> Option<&BitStringSegmentOption<Value>> {
+    use BitStringSegmentOption as Opt;
+    options.iter().find(|option| {
+        !matches!(
+            option,
+            Opt::Signed { .. }
+                | Opt::Unsigned { .. }
+                | Opt::Big { .. }
+                | Opt::Little { .. }
+                | Opt::Native { .. }
+                | Opt::Size { .. }
+                | Opt::Unit { .. }
+        )
+    })
+}
+
+/// The argument to pass to the prelude's bit string functions for the
+/// endianness of a segment, or `None` if it is the default of big endian.
+pub(crate) fn segment_endianness<'a, Value>(
+    tracker: &mut UsageTracker,
+    options: &[BitStringSegmentOption<Value>],
+) -> Option<Document<'a>> {
+    options.iter().find_map(|option| match option {
+        BitStringSegmentOption::Little { .. } => Some("false".to_doc()),
+        BitStringSegmentOption::Native { .. } => {
+            tracker.native_endianness_used = true;
+            Some("isNativeBigEndian".to_doc())
+        }
+        _ => None,
+    })
+}
+
+pub(crate) fn segment_unit<Value>(options: &[BitStringSegmentOption<Value>]) -> Option<u8> {
+    options.iter().find_map(|option| match option {
+        BitStringSegmentOption::Unit { value, .. } => Some(*value),
+        _ => None,
+    })
+}
+
+/// The size of a segment in bits, being its size multiplied by its unit.
+fn segment_size_in_bits<'a, Value>(
+    size: Document<'a>,
+    is_atomic: bool,
+    options: &[BitStringSegmentOption<Value>],
+) -> Document<'a> {
+    match segment_unit(options) {
+        None | Some(1) => size,
+        Some(unit) if is_atomic => docvec![size, " * ", unit],
+        Some(unit) => docvec!["(", size, ") * ", unit],
+    }
+}
+
 pub fn string(value: &str) -> Document<'_> {
     if value.contains('\n') {
         Document::String(value.replace('\n', r#"\n"#)).surround("\"", "\"")
@@ -17,6 +17,10 @@ enum Index<'a> {
     FloatAt(usize),
     SliceAfter(usize),
     StringPrefixSlice(usize),
+    BitStringMethod {
+        name: &'static str,
+        arguments: Vec<Document<'a>>,
+    },
 }
 
 #[derive(Debug)]
@@ -33,26 +37,69 @@ pub(crate) struct Generator<'module_ctx, 'expression_gen, 'a> {
     assignments: Vec<Assignment<'a>>,
 }
 
-struct Offset {
-    bytes: usize,
+/// The offset in bits of a segment of a bit string pattern. This is a constant
+/// number of bits plus the sizes of any earlier segments that are only known
+/// at runtime, such as those sized by a variable.
+#[derive(Debug, Clone)]
+struct Offset<'a> {
+    bits: usize,
+    dynamic: Vec<Document<'a>>,
     open_ended: bool,
 }
 
-impl Offset {
+impl<'a> Offset<'a> {
     pub fn new() -> Self {
         Self {
-            bytes: 0,
+            bits: 0,
+            dynamic: vec![],
             open_ended: false,
         }
     }
     // This should never be called on an open ended offset
     // However previous checks ensure bit_string segements without a size are only allowed at the end of a pattern
-    pub fn increment(&mut self, step: usize) {
-        self.bytes += step
+    pub fn increment(&mut self, size: &SegmentSize<'a>) {
+        match size {
+            SegmentSize::Constant(bits) => self.bits += bits,
+            SegmentSize::Dynamic(bits) => self.dynamic.push(bits.clone()),
+        }
     }
     pub fn set_open_ended(&mut self) {
         self.open_ended = true
     }
+    /// The offset in bytes, if it is known at compile time and falls on a
+    /// byte boundary.
+    pub fn constant_bytes(&self) -> Option<usize> {
+        match (self.dynamic.is_empty(), self.bits % 8) {
+            (true, 0) => Some(self.bits / 8),
+            _ => None,
+        }
+    }
+    pub fn to_doc(&self) -> Document<'a> {
+        if self.dynamic.is_empty() {
+            return self.bits.to_doc();
+        }
+        let constant = Some(self.bits.to_doc()).filter(|_| self.bits != 0);
+        join(
+            constant.into_iter().chain(self.dynamic.iter().cloned()),
+            " + ".to_doc(),
+        )
+    }
+}
+
+/// The size in bits of a segment of a bit string pattern.
+#[derive(Debug, Clone)]
+enum SegmentSize<'a> {
+    Constant(usize),
+    Dynamic(Document<'a>),
+}
+
+impl<'a> SegmentSize<'a> {
+    fn to_doc(&self) -> Document<'a> {
+        match self {
+            SegmentSize::Constant(bits) => bits.to_doc(),
+            SegmentSize::Dynamic(bits) => bits.clone(),
+        }
+    }
 }
 
 impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, 'a> {
@@ -103,6 +150,10 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
         self.path.push(Index::SliceAfter(i));
     }
 
+    fn push_bit_string_method(&mut self, name: &'static str, arguments: Vec<Document<'a>>) {
+        self.path.push(Index::BitStringMethod { name, arguments });
+    }
+
     fn push_string_times(&mut self, s: &'a str, times: usize) {
         for _ in 0..times {
             self.push_string(s);
@@ -129,6 +180,9 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
             Index::FloatAt(i) => docvec!(".floatAt(", i, ")"),
             Index::SliceAfter(i) => docvec!(".sliceAfter(", i, ")"),
             Index::StringPrefixSlice(i) => docvec!(".slice(", i, ")"),
+            Index::BitStringMethod { name, arguments } => {
+                docvec!(".", name, "(", join(arguments.clone(), ", ".to_doc()), ")")
+            }
         }))
     }
 
@@ -163,9 +217,14 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
 
     fn wrapped_guard(&mut self, guard: &'a TypedClauseGuard) -> Result<Document<'a>, Error> {
         match guard {
-            ClauseGuard::Var { .. } | ClauseGuard::TupleIndex { .. } | ClauseGuard::Constant(_) => {
-                self.guard(guard)
-            }
+            ClauseGuard::Var { .. }
+            | ClauseGuard::TupleIndex { .. }
+            | ClauseGuard::FieldAccess { .. }
+            | ClauseGuard::Constant(_)
+            | ClauseGuard::Not { .. }
+            | ClauseGuard::DivInt { .. }
+            | ClauseGuard::DivFloat { .. }
+            | ClauseGuard::RemainderInt { .. } => self.guard(guard),
             ClauseGuard::Equals { .. }
             | ClauseGuard::NotEquals { .. }
             | ClauseGuard::GtInt { .. }
@@ -177,7 +236,14 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
             | ClauseGuard::LtFloat { .. }
             | ClauseGuard::LtEqFloat { .. }
             | ClauseGuard::Or { .. }
-            | ClauseGuard::And { .. } => Ok(docvec!("(", self.guard(guard)?, ")")),
+            | ClauseGuard::And { .. }
+            | ClauseGuard::AddInt { .. }
+            | ClauseGuard::AddFloat { .. }
+            | ClauseGuard::SubInt { .. }
+            | ClauseGuard::SubFloat { .. }
+            | ClauseGuard::MultInt { .. }
+            | ClauseGuard::MultFloat { .. }
+            | ClauseGuard::Concatenate { .. } => Ok(docvec!("(", self.guard(guard)?, ")")),
         }
     }
 
@@ -235,6 +301,52 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                 docvec!(left, " <= ", right)
             }
 
+            ClauseGuard::AddFloat { left, right, .. }
+            | ClauseGuard::AddInt { left, right, .. }
+            | ClauseGuard::Concatenate { left, right, .. } => {
+                let left = self.wrapped_guard(left)?;
+                let right = self.wrapped_guard(right)?;
+                docvec!(left, " + ", right)
+            }
+
+            ClauseGuard::SubFloat { left, right, .. } | ClauseGuard::SubInt { left, right, .. } => {
+                let left = self.wrapped_guard(left)?;
+                let right = self.wrapped_guard(right)?;
+                docvec!(left, " - ", right)
+            }
+
+            ClauseGuard::MultFloat { left, right, .. }
+            | ClauseGuard::MultInt { left, right, .. } => {
+                let left = self.wrapped_guard(left)?;
+                let right = self.wrapped_guard(right)?;
+                docvec!(left, " * ", right)
+            }
+
+            ClauseGuard::DivInt { left, right, .. } => {
+                let left = self.guard(left)?;
+                let right = self.guard(right)?;
+                self.expression_generator.tracker.int_division_used = true;
+                docvec!("divideInt", wrap_args([left, right]))
+            }
+
+            ClauseGuard::DivFloat { left, right, .. } => {
+                let left = self.guard(left)?;
+                let right = self.guard(right)?;
+                self.expression_generator.tracker.float_division_used = true;
+                docvec!("divideFloat", wrap_args([left, right]))
+            }
+
+            ClauseGuard::RemainderInt { left, right, .. } => {
+                let left = self.guard(left)?;
+                let right = self.guard(right)?;
+                self.expression_generator.tracker.int_remainder_used = true;
+                docvec!("remainderInt", wrap_args([left, right]))
+            }
+
+            ClauseGuard::Not { expression, .. } => {
+                docvec!("!", self.wrapped_guard(expression)?)
+            }
+
             ClauseGuard::Or { left, right, .. } => {
                 let left = self.wrapped_guard(left)?;
                 let right = self.wrapped_guard(right)?;
@@ -255,6 +367,12 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
                 docvec!(self.guard(tuple)?, "[", index, "]")
             }
 
+            ClauseGuard::FieldAccess {
+                container, label, ..
+            } => {
+                docvec!(self.guard(container)?, ".", label)
+            }
+
             ClauseGuard::Constant(constant) => {
                 return expression::guard_constant_expression(
                     &mut self.assignments,
@@ -422,63 +540,16 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
             }
 
             Pattern::BitString { segments, .. } => {
-                use BitStringSegmentOption as Opt;
-
                 let mut offset = Offset::new();
                 for segment in segments {
-                    match segment.options.as_slice() {
-                        [] | [Opt::Int { .. }] => {
-                            self.push_byte_at(offset.bytes);
-                            self.traverse_pattern(subject, &segment.value)?;
-                            self.pop();
-                            offset.increment(1);
-                            Ok(())
-                        }
-
-                        [Opt::Size { value: size, .. }] => match &**size {
-                            Pattern::Int { value, .. } => {
-                                let start = offset.bytes;
-                                let increment = value
-                                    .parse::<usize>()
-                                    .expect("part of an Int node should always parse as integer");
-                                offset.increment(increment / 8);
-                                let end = offset.bytes;
-
-                                self.push_int_from_slice(start, end);
-                                self.traverse_pattern(subject, &segment.value)?;
-                                self.pop();
-                                Ok(())
-                            }
-                            _ => Err(Error::Unsupported {
-                                feature: "This bit string size option in patterns".into(),
-                                location: segment.location,
-                            }),
-                        },
-
-                        [Opt::Float { .. }] => {
-                            self.push_float_at(offset.bytes);
-                            self.traverse_pattern(subject, &segment.value)?;
-                            self.pop();
-                            offset.increment(8);
-                            Ok(())
-                        }
-
-                        [Opt::Binary { .. }] => {
-                            self.push_rest_from(offset.bytes);
-                            self.traverse_pattern(subject, &segment.value)?;
-                            self.pop();
-                            offset.set_open_ended();
-                            Ok(())
-                        }
-
-                        _ => Err(Error::Unsupported {
-                            feature: "This bit string segment option in patterns".into(),
-                            location: segment.location,
-                        }),
-                    }?;
+                    self.traverse_bit_string_segment(subject, segment, &mut offset)?;
                 }
 
-                self.push_bitstring_length_check(subject.clone(), offset.bytes, offset.open_ended);
+                self.push_bitstring_length_check(
+                    subject.clone(),
+                    offset.to_doc(),
+                    offset.open_ended,
+                );
                 Ok(())
             }
             Pattern::VarUsage { location, .. } => Err(Error::Unsupported {
@@ -488,6 +559,222 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
         }
     }
 
+    fn traverse_bit_string_segment(
+        &mut self,
+        subject: &Document<'a>,
+        segment: &'a TypedPatternBitStringSegment,
+        offset: &mut Offset<'a>,
+    ) -> Result<(), Error> {
+        use BitStringSegmentOption as Opt;
+
+        let options = &segment.options;
+        let endianness = expression::segment_endianness(self.expression_generator.tracker, options);
+        let is_signed = options
+            .iter()
+            .any(|option| matches!(option, Opt::Signed { .. }));
+        let start = offset.to_doc();
+
+        match expression::segment_type(options) {
+            // Floats
+            Some(Opt::Float { .. }) => {
+                let size = self.bit_string_segment_size(segment, 64, 1)?;
+                match (offset.constant_bytes(), &size, &endianness) {
+                    (Some(bytes), SegmentSize::Constant(64), None) => self.push_float_at(bytes),
+                    _ => {
+                        let arguments = vec![start, size.to_doc()];
+                        let arguments = arguments.into_iter().chain(endianness).collect();
+                        self.push_bit_string_method("floatFromBits", arguments)
+                    }
+                }
+                self.traverse_pattern(subject, &segment.value)?;
+                self.pop();
+                offset.increment(&size);
+            }
+
+            // Bit strings
+            Some(option @ (Opt::Binary { .. } | Opt::BitString { .. })) => {
+                let default_unit = if let Opt::Binary { .. } = option {
+                    8
+                } else {
+                    1
+                };
+                if segment
+                    .options
+                    .iter()
+                    .any(|option| option.value().is_some())
+                {
+                    let size = self.bit_string_segment_size(segment, 0, default_unit)?;
+                    let mut end = offset.clone();
+                    end.increment(&size);
+                    self.push_bit_string_method("sliceBits", vec![start, end.to_doc()]);
+                    self.traverse_pattern(subject, &segment.value)?;
+                    self.pop();
+                    offset.increment(&size);
+                } else {
+                    if default_unit == 8 {
+                        self.push_byte_alignment_check(subject.clone(), offset);
+                    }
+                    match offset.constant_bytes() {
+                        Some(bytes) => self.push_rest_from(bytes),
+                        None => self.push_bit_string_method("sliceBits", vec![start]),
+                    }
+                    self.traverse_pattern(subject, &segment.value)?;
+                    self.pop();
+                    offset.set_open_ended();
+                }
+            }
+
+            // Strings, which in patterns are always literals
+            Some(option @ (Opt::Utf8 { .. } | Opt::Utf16 { .. } | Opt::Utf32 { .. })) => {
+                let encoding = utf_encoding(option);
+                let value = match segment.value.as_ref() {
+                    Pattern::String { value, .. } => value,
+                    // A discarded string matches a single codepoint
+                    Pattern::Discard { .. } => {
+                        return self.traverse_codepoint_segment(
+                            subject, segment, encoding, endianness, offset,
+                        )
+                    }
+                    _ => {
+                        return Err(Error::Unsupported {
+                            feature: "This string segment in patterns".into(),
+                            location: segment.location,
+                        })
+                    }
+                };
+                let size = SegmentSize::Constant(encoded_len(value, encoding) * 8);
+                let mut end = offset.clone();
+                end.increment(&size);
+                let arguments = vec![start, end.to_doc()];
+                let arguments = arguments
+                    .into_iter()
+                    .chain(encoding_arguments(encoding, endianness))
+                    .collect();
+                self.push_bit_string_method("stringFromBits", arguments);
+                self.push_equality_check(subject.clone(), expression::string(value));
+                self.pop();
+                offset.increment(&size);
+            }
+
+            // Codepoints
+            Some(
+                option @ (Opt::Utf8Codepoint { .. }
+                | Opt::Utf16Codepoint { .. }
+                | Opt::Utf32Codepoint { .. }),
+            ) => {
+                let encoding = utf_encoding(option);
+                self.traverse_codepoint_segment(subject, segment, encoding, endianness, offset)?;
+            }
+
+            // Ints
+            _ => {
+                let size = self.bit_string_segment_size(segment, 8, 1)?;
+                match (offset.constant_bytes(), &size, &endianness, is_signed) {
+                    (Some(bytes), SegmentSize::Constant(8), None, false) => {
+                        self.push_byte_at(bytes)
+                    }
+                    (Some(bytes), SegmentSize::Constant(bits), None, false) if bits % 8 == 0 => {
+                        self.push_int_from_slice(bytes, bytes + bits / 8)
+                    }
+                    _ => {
+                        let mut arguments = vec![start, size.to_doc()];
+                        if endianness.is_some() || is_signed {
+                            arguments.push(endianness.unwrap_or_else(|| "true".to_doc()));
+                        }
+                        if is_signed {
+                            arguments.push("true".to_doc());
+                        }
+                        self.push_bit_string_method("intFromBits", arguments)
+                    }
+                }
+                self.traverse_pattern(subject, &segment.value)?;
+                self.pop();
+                offset.increment(&size);
+            }
+        }
+        Ok(())
+    }
+
+    fn traverse_codepoint_segment(
+        &mut self,
+        subject: &Document<'a>,
+        segment: &'a TypedPatternBitStringSegment,
+        encoding: &'static str,
+        endianness: Option<Document<'a>>,
+        offset: &mut Offset<'a>,
+    ) -> Result<(), Error> {
+        let arguments: Vec<_> = std::iter::once(offset.to_doc())
+            .chain(encoding_arguments(encoding, endianness))
+            .collect();
+
+        // Codepoints vary in size so we check that there is a valid one and
+        // use its size to find the offset of the following segments.
+        self.push_bit_string_method("codepointSizeFromBits", arguments.clone());
+        let size = docvec![subject.clone(), self.path_document()];
+        self.pop();
+        self.checks.push(Check::Condition {
+            expression: docvec![size.clone(), " > 0"],
+        });
+
+        self.push_bit_string_method("codepointFromBits", arguments);
+        self.traverse_pattern(subject, &segment.value)?;
+        self.pop();
+        offset.increment(&SegmentSize::Dynamic(size));
+        Ok(())
+    }
+
+    /// The size of a segment in bits, being its size option multiplied by its
+    /// unit. Sizes given by a variable are checked to not be negative.
+    fn bit_string_segment_size(
+        &mut self,
+        segment: &'a TypedPatternBitStringSegment,
+        default_size: usize,
+        default_unit: u8,
+    ) -> Result<SegmentSize<'a>, Error> {
+        let unit = expression::segment_unit(&segment.options).unwrap_or(default_unit);
+        let size = match segment.options.iter().find_map(|option| option.value()) {
+            None => return Ok(SegmentSize::Constant(default_size)),
+            Some(size) => size,
+        };
+        match size {
+            Pattern::Int { value, .. } => match value.replace('_', "").parse::<usize>() {
+                Ok(size) => Ok(SegmentSize::Constant(size * unit as usize)),
+                Err(_) => Err(Error::Unsupported {
+                    feature: "This bit string segment size".into(),
+                    location: size.location(),
+                }),
+            },
+
+            Pattern::VarUsage { name, .. } => {
+                let variable = self
+                    .path_doc_from_assignments(name)
+                    .unwrap_or_else(|| self.local_var(name));
+                self.checks.push(Check::Condition {
+                    expression: docvec![variable.clone(), " >= 0"],
+                });
+                Ok(SegmentSize::Dynamic(if unit == 1 {
+                    variable
+                } else {
+                    docvec![variable, " * ", unit]
+                }))
+            }
+
+            _ => Err(Error::Unsupported {
+                feature: "This bit string segment size".into(),
+                location: size.location(),
+            }),
+        }
+    }
+
+    fn push_byte_alignment_check(&mut self, subject: Document<'a>, offset: &Offset<'a>) {
+        let bit_size = docvec![subject, self.path_document(), ".bitSize"];
+        let expression = match offset.constant_bytes() {
+            Some(_) => docvec![bit_size, " % 8 === 0"],
+            None => docvec!["(", bit_size, " - ", offset.to_doc(), ") % 8 === 0"],
+        };
+        self.checks.push(Check::Condition { expression })
+    }
+
     fn push_assignment(&mut self, subject: Document<'a>, name: &'a SmolStr) {
         let var = self.next_local_var(name);
         let path = self.path_document();
@@ -556,11 +843,11 @@ impl<'module_ctx, 'expression_gen, 'a> Generator<'module_ctx, 'expression_gen, '
     fn push_bitstring_length_check(
         &mut self,
         subject: Document<'a>,
-        expected_bytes: usize,
+        expected_bits: Document<'a>,
         has_tail_spread: bool,
     ) {
         self.checks.push(Check::BitStringLength {
-            expected_bytes,
+            expected_bits,
             has_tail_spread,
             subject,
             path: self.path_document(),
@@ -624,7 +911,7 @@ pub enum Check<'a> {
     BitStringLength {
         subject: Document<'a>,
         path: Document<'a>,
-        expected_bytes: usize,
+        expected_bits: Document<'a>,
         has_tail_spread: bool,
     },
     StringPrefix {
@@ -640,6 +927,11 @@ pub enum Check<'a> {
     Guard {
         expression: Document<'a>,
     },
+    /// A condition that does not fit any of the other checks, such as the
+    /// size of a bit string segment not being negative.
+    Condition {
+        expression: Document<'a>,
+    },
 }
 
 impl<'a> Check<'a> {
@@ -653,6 +945,14 @@ impl<'a> Check<'a> {
                 }
             }
 
+            Check::Condition { expression } => {
+                if match_desired {
+                    expression
+                } else {
+                    docvec!["!(", expression, ")"]
+                }
+            }
+
             Check::Booly {
                 expected_to_be_truthy,
                 subject,
@@ -714,14 +1014,14 @@ impl<'a> Check<'a> {
             Check::BitStringLength {
                 subject,
                 path,
-                expected_bytes,
+                expected_bits,
                 has_tail_spread,
             } => {
-                let length_check = Document::String(if has_tail_spread {
-                    format!(".length >= {expected_bytes}")
+                let length_check = if has_tail_spread {
+                    docvec![".bitSize >= ", expected_bits]
                 } else {
-                    format!(".length == {expected_bytes}")
-                });
+                    docvec![".bitSize == ", expected_bits]
+                };
                 if match_desired {
                     docvec![subject, path, length_check,]
                 } else {
@@ -770,8 +1070,51 @@ pub(crate) fn assign_subjects<'a>(
     }
     out
 }
+/// The encoding of a string or codepoint bit string segment, as used by the
+/// prelude.
+fn utf_encoding<Value>(option: &BitStringSegmentOption<Value>) -> &'static str {
+    match option {
+        BitStringSegmentOption::Utf16 { .. } | BitStringSegmentOption::Utf16Codepoint { .. } => {
+            "utf16"
+        }
+        BitStringSegmentOption::Utf32 { .. } | BitStringSegmentOption::Utf32Codepoint { .. } => {
+            "utf32"
+        }
+        _ => "utf8",
+    }
+}
+
+/// The arguments for the encoding and endianness of a string or codepoint
+/// segment, leaving out those that are the prelude's defaults.
+fn encoding_arguments<'a>(
+    encoding: &'static str,
+    endianness: Option<Document<'a>>,
+) -> Vec<Document<'a>> {
+    match (encoding, endianness) {
+        ("utf8", None) => vec![],
+        (encoding, None) => vec![docvec!["\"", encoding, "\""]],
+        (encoding, Some(endianness)) => vec![docvec!["\"", encoding, "\""], endianness],
+    }
+}
+
+/// The length in bytes of a string literal once encoded.
+fn encoded_len(str: &SmolStr, encoding: &str) -> usize {
+    let unescaped = no_escape_str(str);
+    match encoding {
+        "utf16" => unescaped.encode_utf16().count() * 2,
+        "utf32" => unescaped.chars().count() * 4,
+        _ => unescaped.len(),
+    }
+}
+
 // Helper function to calculate length of str as utf16 without escape characters
 fn utf16_no_escape_len(str: &SmolStr) -> usize {
+    no_escape_str(str).encode_utf16().count()
+}
+
+// The escape characters are all a single ASCII character so removing the
+// backslashes leaves a string of the same length as the unescaped one.
+fn no_escape_str(str: &SmolStr) -> String {
     let mut filtered_str = String::new();
     let mut str_iter = str.chars();
     loop {
@@ -784,5 +1127,5 @@ fn utf16_no_escape_len(str: &SmolStr) -> usize {
             None => break,
         }
     }
-    return filtered_str.encode_utf16().count();
+    filtered_str
 }
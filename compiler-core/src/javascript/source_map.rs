@@ -0,0 +1,363 @@
+//! Source maps relating the generated JavaScript back to the Gleam source
+//! code, as described by the [Source Map Revision 3 Proposal][spec].
+//!
+//! [spec]: https://sourcemaps.info/spec.html
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::{io::Utf8Writer, line_numbers::LineNumbers, Error};
+
+/// A writer that records where in the output the source positions of the
+/// document being rendered are reached.
+///
+/// Lines and columns are counted from zero, with columns being counted in
+/// UTF-16 code units as JavaScript does.
+///
+#[derive(Debug)]
+pub struct SourceMapWriter<'a> {
+    src: &'a str,
+    line_numbers: &'a LineNumbers,
+    code: String,
+    line: u32,
+    column: u32,
+    mappings: Vec<Mapping>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Mapping {
+    generated_line: u32,
+    generated_column: u32,
+    source_line: u32,
+    source_column: u32,
+}
+
+impl<'a> SourceMapWriter<'a> {
+    pub fn new(src: &'a str, line_numbers: &'a LineNumbers) -> Self {
+        Self {
+            src,
+            line_numbers,
+            code: String::new(),
+            line: 0,
+            column: 0,
+            mappings: vec![],
+        }
+    }
+
+    /// Returns the generated code and the JSON of its source map. `file` is
+    /// the name of the generated file and `source` is the path of the Gleam
+    /// module relative to it.
+    pub fn finish(self, file: &str, source: &str) -> (String, String) {
+        let source_map = serde_json::json!({
+            "version": 3,
+            "file": file,
+            "sources": [source],
+            "sourcesContent": [self.src],
+            "names": [],
+            "mappings": encode_mappings(&self.mappings),
+        });
+        (self.code, source_map.to_string())
+    }
+
+    fn source_line_and_column(&self, byte_index: u32) -> (u32, u32) {
+        let line = self.line_numbers.line_number(byte_index) - 1;
+        let line_start = self.line_numbers.byte_index(line, 0) as usize;
+        let column = self
+            .src
+            .get(line_start..byte_index as usize)
+            .map(|line| line.encode_utf16().count() as u32)
+            .unwrap_or_default();
+        (line, column)
+    }
+}
+
+impl std::fmt::Write for SourceMapWriter<'_> {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        for char in s.chars() {
+            if char == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += char.len_utf16() as u32;
+            }
+        }
+        self.code.push_str(s);
+        Ok(())
+    }
+}
+
+impl Utf8Writer for SourceMapWriter<'_> {
+    fn convert_err<E: std::error::Error>(&self, error: E) -> Error {
+        self.code.convert_err(error)
+    }
+
+    fn source_position(&mut self, byte_index: u32) {
+        let (source_line, source_column) = self.source_line_and_column(byte_index);
+        let mapping = Mapping {
+            generated_line: self.line,
+            generated_column: self.column,
+            source_line,
+            source_column,
+        };
+        // When several positions are reached before any output is written the
+        // innermost one, being the last, is the most precise.
+        match self.mappings.last_mut() {
+            Some(last)
+                if last.generated_line == mapping.generated_line
+                    && last.generated_column == mapping.generated_column =>
+            {
+                *last = mapping
+            }
+            _ => self.mappings.push(mapping),
+        }
+    }
+}
+
+/// Encodes the mappings as Base64 VLQ segments, each being relative to the
+/// previous one. Lines of the generated code are separated by `;` and the
+/// segments within a line by `,`.
+fn encode_mappings(mappings: &[Mapping]) -> String {
+    let mut encoded = String::new();
+    let mut line = 0;
+    let mut previous_generated_column = 0;
+    let mut previous_source_line = 0;
+    let mut previous_source_column = 0;
+
+    for (index, mapping) in mappings.iter().enumerate() {
+        if mapping.generated_line != line {
+            for _ in line..mapping.generated_line {
+                encoded.push(';');
+            }
+            line = mapping.generated_line;
+            previous_generated_column = 0;
+        } else if index != 0 {
+            encoded.push(',');
+        }
+
+        encode_vlq(
+            &mut encoded,
+            mapping.generated_column as i64 - previous_generated_column as i64,
+        );
+        // All the mappings are for the one source file, which is the first and
+        // only entry of `sources`, so its index is always zero
+        encode_vlq(&mut encoded, 0);
+        encode_vlq(
+            &mut encoded,
+            mapping.source_line as i64 - previous_source_line as i64,
+        );
+        encode_vlq(
+            &mut encoded,
+            mapping.source_column as i64 - previous_source_column as i64,
+        );
+
+        previous_generated_column = mapping.generated_column;
+        previous_source_line = mapping.source_line;
+        previous_source_column = mapping.source_column;
+    }
+    encoded
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Appends the value as a Base64 VLQ, being groups of 5 bits with the least
+/// significant first, the sign in the lowest bit of the first group, and
+/// a continuation bit for each group other than the last.
+fn encode_vlq(encoded: &mut String, value: i64) {
+    let mut value = if value < 0 {
+        ((-value as u64) << 1) | 1
+    } else {
+        (value as u64) << 1
+    };
+    loop {
+        let mut digit = (value & 0b11111) as usize;
+        value >>= 5;
+        if value > 0 {
+            digit |= 0b100000;
+        }
+        encoded.push(BASE64.get(digit).copied().unwrap_or(b'A') as char);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// The path of the source file as given in the source map of a generated file
+/// in the directory, being relative to that directory when possible. Relative
+/// paths are relative to the current directory, so the source can be found
+/// from the directory when only one of them is absolute.
+pub fn source_path(current_directory: &Path, directory: &Path, source: &Path) -> String {
+    let source = current_directory.join(source);
+    relative_path(&current_directory.join(directory), &source)
+        .unwrap_or(source)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// A path to `to` relative to the directory `from`, worked out from the
+/// components of the paths alone. Returns `None` when this is not possible
+/// without looking at the file system.
+fn relative_path(from: &Path, to: &Path) -> Option<PathBuf> {
+    fn components(path: &Path) -> Vec<Component<'_>> {
+        let mut components = vec![];
+        for component in path.components() {
+            match component {
+                Component::CurDir => (),
+                Component::ParentDir if matches!(components.last(), Some(Component::Normal(_))) => {
+                    let _ = components.pop();
+                }
+                _ => components.push(component),
+            }
+        }
+        components
+    }
+    let from = components(from);
+    let to = components(to);
+    let is_absolute = |components: &[Component<'_>]| {
+        matches!(
+            components.first(),
+            Some(Component::RootDir | Component::Prefix(_))
+        )
+    };
+    if from.contains(&Component::ParentDir) || is_absolute(&from) != is_absolute(&to) {
+        return None;
+    }
+
+    let common = from
+        .iter()
+        .zip(to.iter())
+        .take_while(|(from, to)| from == to)
+        .count();
+    let mut path = PathBuf::new();
+    for _ in common..from.len() {
+        path.push("..");
+    }
+    for component in to.iter().skip(common) {
+        path.push(component);
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vlq() {
+        let encode = |value| {
+            let mut encoded = String::new();
+            encode_vlq(&mut encoded, value);
+            encoded
+        };
+        assert_eq!(encode(0), "A");
+        assert_eq!(encode(1), "C");
+        assert_eq!(encode(-1), "D");
+        assert_eq!(encode(15), "e");
+        assert_eq!(encode(16), "gB");
+        assert_eq!(encode(-16), "hB");
+        assert_eq!(encode(1000), "w+B");
+        assert_eq!(encode(123456), "gkxH");
+    }
+
+    #[test]
+    fn mappings() {
+        let src = "pub fn main() {\n  \"é\" <> x\n}\n";
+        let line_numbers = LineNumbers::new(src);
+        let mut writer = SourceMapWriter::new(src, &line_numbers);
+        writer.source_position(0);
+        writer
+            .str_write("export function main() {\n  ")
+            .expect("Writing to source map writer failed");
+        writer.source_position(18);
+        writer.source_position(26);
+        writer
+            .str_write("\"é\" + x;\n")
+            .expect("Writing to source map writer failed");
+        writer.source_position(18);
+        writer
+            .str_write("}\n")
+            .expect("Writing to source map writer failed");
+
+        assert_eq!(
+            writer.mappings,
+            vec![
+                Mapping {
+                    generated_line: 0,
+                    generated_column: 0,
+                    source_line: 0,
+                    source_column: 0,
+                },
+                Mapping {
+                    generated_line: 1,
+                    generated_column: 2,
+                    source_line: 1,
+                    // The `é` is 2 bytes but 1 UTF-16 code unit
+                    source_column: 9,
+                },
+                Mapping {
+                    generated_line: 2,
+                    generated_column: 0,
+                    source_line: 1,
+                    source_column: 2,
+                },
+            ]
+        );
+
+        let (code, source_map) = writer.finish("main.mjs", "../src/main.gleam");
+        assert_eq!(code, "export function main() {\n  \"é\" + x;\n}\n");
+        assert_eq!(
+            source_map,
+            r#"{"file":"main.mjs","mappings":"AAAA;EACS;AAAP","names":[],"sources":["../src/main.gleam"],"sourcesContent":["pub fn main() {\n  \"é\" <> x\n}\n"],"version":3}"#
+        );
+    }
+
+    #[test]
+    fn source_paths() {
+        assert_eq!(
+            source_path(
+                Path::new("/app"),
+                Path::new("build/dev/javascript/app/one"),
+                Path::new("./src/one/two.gleam")
+            ),
+            "../../../../../src/one/two.gleam"
+        );
+        assert_eq!(
+            source_path(
+                Path::new("/app"),
+                Path::new("build/dev/javascript/dep"),
+                Path::new("build/packages/dep/src/dep.gleam")
+            ),
+            "../../../packages/dep/src/dep.gleam"
+        );
+        assert_eq!(
+            source_path(
+                Path::new("/app"),
+                Path::new("/out/app"),
+                Path::new("/app/src/app.gleam")
+            ),
+            "../../app/src/app.gleam"
+        );
+        assert_eq!(
+            source_path(
+                Path::new("/app"),
+                Path::new("/out/lib/app/one"),
+                Path::new("src/one/two.gleam")
+            ),
+            "../../../../app/src/one/two.gleam"
+        );
+        assert_eq!(
+            source_path(
+                Path::new("/app"),
+                Path::new("../out"),
+                Path::new("src/app.gleam")
+            ),
+            "../app/src/app.gleam"
+        );
+        assert_eq!(
+            source_path(
+                Path::new(""),
+                Path::new("/out/app"),
+                Path::new("src/app.gleam")
+            ),
+            "src/app.gleam"
+        );
+    }
+}
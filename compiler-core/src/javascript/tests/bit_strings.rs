@@ -181,7 +181,7 @@ fn match_sized() {
     assert_js!(
         r#"
 fn go(x) {
-  let <<a:16, b:8>> = x
+  let assert <<a:16, b:8>> = x
 }
 "#,
     );
@@ -192,7 +192,7 @@ fn discard_sized() {
     assert_js!(
         r#"
 fn go(x) {
-  let <<_:16, _:8>> = x
+  let assert <<_:16, _:8>> = x
 }
 "#,
     );
@@ -203,7 +203,7 @@ fn match_sized_value() {
     assert_js!(
         r#"
 fn go(x) {
-  let <<258:16>> = x
+  let assert <<258:16>> = x
 }
 "#,
     );
@@ -214,7 +214,7 @@ fn match_float() {
     assert_js!(
         r#"
 fn go(x) {
-  let <<a:float, b:int>> = x
+  let assert <<a:float, b:int>> = x
 }
 "#,
     );
@@ -225,11 +225,128 @@ fn match_rest() {
     assert_js!(
         r#"
 fn go(x) {
-  let <<_, b:binary>> = <<1,2,3>>
+  let assert <<_, b:binary>> = <<1,2,3>>
 }
 "#,
     );
 }
 
-// binary rest
-// utf8 matched
+#[test]
+fn unaligned_sizes() {
+    assert_js!(
+        r#"
+fn go(x) {
+  <<x:3, 1:5, x:size(12)>>
+}
+"#,
+    );
+}
+
+#[test]
+fn unit() {
+    assert_js!(
+        r#"
+fn go(x, y) {
+  <<x:size(4)-unit(8), y:bit_string-size(2)-unit(4)>>
+}
+"#,
+    );
+}
+
+#[test]
+fn endianness() {
+    assert_js!(
+        r#"
+fn go(x) {
+  <<x:16-little, x:16-big, x:16-native, 1.0:float-32-little>>
+}
+"#,
+    );
+}
+
+#[test]
+fn utf16_and_utf32() {
+    assert_js!(
+        r#"
+fn go(x, c) {
+  <<x:utf16, x:utf32-little, c:utf16_codepoint, c:utf32_codepoint>>
+}
+"#,
+    );
+}
+
+#[test]
+fn constant() {
+    assert_js!(
+        r#"
+const data = <<1, 2:4, "Gleam":utf8, 1.0:float-16>>
+
+fn go() {
+  data
+}
+"#,
+    );
+}
+
+#[test]
+fn match_variable_size() {
+    assert_js!(
+        r#"
+fn go(x) {
+  let assert <<length, data:binary-size(length), rest:bit_string>> = x
+  #(data, rest)
+}
+"#,
+    );
+}
+
+#[test]
+fn match_signed_little_endian() {
+    assert_js!(
+        r#"
+fn go(x) {
+  let assert <<a:16-signed-little, b:4-signed, c:float-32-little, _:4>> = x
+  #(a, b, c)
+}
+"#,
+    );
+}
+
+#[test]
+fn match_unaligned_rest() {
+    assert_js!(
+        r#"
+fn go(x) {
+  let assert <<a:3, rest:binary>> = x
+  #(a, rest)
+}
+"#,
+    );
+}
+
+#[test]
+fn match_utf8_string() {
+    assert_js!(
+        r#"
+fn go(x) {
+  case x {
+    <<"Hello, \"Joe\"":utf8, rest:binary>> -> rest
+    <<"hi":utf16-little>> -> x
+    _ -> x
+  }
+}
+"#,
+    );
+}
+
+#[test]
+fn match_codepoints() {
+    assert_js!(
+        r#"
+fn go(x) {
+  let assert <<a:utf8_codepoint, b:utf16_codepoint, _:utf8, rest:bit_string>> = x
+  #(a, b, rest)
+}
+"#,
+    );
+}
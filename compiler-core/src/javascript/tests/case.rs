@@ -124,6 +124,7 @@ fn pipe() {
 fn go(x, f) {
   case x |> f {
     0 -> Nil
+    _ -> Nil
   }
 }
 "#,
@@ -5,8 +5,9 @@ fn guards_cause_badmatch_to_render() {
     assert_js!(
         r#"pub fn main(x, y) {
   case x {
-    1 -> 1
+    True -> 1
     _ if y -> 0
+    False -> 2
   }
 }
 "#,
@@ -196,3 +197,77 @@ fn alternative_patterns_guard() {
 "#,
     );
 }
+
+#[test]
+fn int_arithmetic_in_guards() {
+    assert_js!(
+        r#"
+pub fn main(x, y) {
+  case x {
+    _ if x + y * 2 > 10 - y / 2 % 3 -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn float_arithmetic_in_guards() {
+    assert_js!(
+        r#"
+pub fn main(x, y) {
+  case x {
+    _ if { x +. y } *. 2.0 >. 10.0 -. y /. 2.0 -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn string_concatenation_in_guards() {
+    assert_js!(
+        r#"
+pub fn main(x) {
+  case x {
+    _ if x <> "!" == "Hello!" -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn negation_in_guards() {
+    assert_js!(
+        r#"
+pub fn main(x, y) {
+  case x {
+    _ if !x && !{ y || x } -> 1
+    _ -> 0
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn record_access_in_guards() {
+    assert_js!(
+        r#"
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main(people: #(Person, Int)) {
+  case people {
+    #(person, limit) if person.age > limit -> person.name
+    _ -> "Unknown"
+  }
+}
+"#
+    );
+}
@@ -1,3 +1,21 @@
+pub mod call_hierarchy;
+pub mod code_action;
+pub mod code_lens;
+pub mod completion;
+pub mod definition;
 mod files;
+pub mod folding_range;
+pub mod formatting;
+pub mod hover;
+pub mod inlay_hints;
+pub mod reference;
+pub mod rename;
+pub mod selection_range;
+pub mod semantic_tokens;
+pub mod signature_help;
+pub mod symbols;
+
+#[cfg(test)]
+mod tests;
 
 pub use files::FileSystemProxy;
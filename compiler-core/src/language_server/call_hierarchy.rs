@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use smol_str::SmolStr;
+
+use super::reference::{self, ReferenceKind, Symbol};
+use crate::{
+    ast::{BitStringSegmentOption, ExternalFunction, Function, SrcSpan, Statement, TypedExpr},
+    build::Module,
+    type_::{self, ValueConstructorVariant},
+};
+
+/// A function defined at the top level of a module, which may belong to a
+/// dependency package.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallHierarchyFunction {
+    pub module: SmolStr,
+    pub package: SmolStr,
+    pub name: SmolStr,
+    /// The location of the head of the function.
+    pub location: SrcSpan,
+}
+
+/// The calls from one function to another, made at the given locations in
+/// the module of the calling function.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Calls {
+    pub function: CallHierarchyFunction,
+    pub locations: Vec<SrcSpan>,
+}
+
+/// Returns the function defined or referred to at the byte index.
+///
+pub fn function_at(
+    module: &Module,
+    byte_index: u32,
+    modules: &HashMap<SmolStr, &type_::Module>,
+) -> Option<CallHierarchyFunction> {
+    match reference::reference_at(module, byte_index)?.symbol {
+        Symbol::ModuleValue {
+            module: function_module,
+            name,
+        } => find_function(module, &function_module, &name, modules),
+        Symbol::ModuleType { .. } | Symbol::RecordLabel { .. } | Symbol::LocalVariable { .. } => {
+            None
+        }
+    }
+}
+
+/// Returns the calls to the named function of the given module made by each
+/// function of the module. References to the function that do not call it,
+/// such as passing it as an argument, are not calls.
+///
+/// Calls through pipes and `use` expressions are included as they are
+/// ordinary calls once the module has been type checked.
+///
+pub fn incoming_calls(module: &Module, function_module: &str, name: &str) -> Vec<Calls> {
+    let symbol = Symbol::ModuleValue {
+        module: function_module.into(),
+        name: name.into(),
+    };
+    let called = called_functions(module);
+    let references: Vec<_> = reference::find_references(module, &symbol)
+        .into_iter()
+        .filter(|reference| {
+            reference.kind == ReferenceKind::Usage && is_called(&called, reference.location)
+        })
+        .collect();
+
+    module_functions(module)
+        .filter_map(|(caller, span)| {
+            let locations: Vec<_> = references
+                .iter()
+                .map(|reference| reference.location)
+                .filter(|location| span.start <= location.start && location.end <= span.end)
+                .collect();
+            (!locations.is_empty()).then_some(Calls {
+                function: caller,
+                locations,
+            })
+        })
+        .collect()
+}
+
+/// Returns the calls made by the named function of the module, grouped by the
+/// function called, in the order they are first called.
+///
+pub fn outgoing_calls(
+    module: &Module,
+    name: &str,
+    modules: &HashMap<SmolStr, &type_::Module>,
+) -> Vec<Calls> {
+    let span = match module_functions(module).find(|(function, _)| function.name == name) {
+        Some((_, span)) => span,
+        None => return vec![],
+    };
+
+    let called = called_functions(module);
+    let mut calls: Vec<Calls> = vec![];
+    for reference in reference::module_references(module) {
+        let within = span.start <= reference.location.start && reference.location.end <= span.end;
+        let (callee_module, callee) = match reference.symbol {
+            Symbol::ModuleValue { module, name }
+                if within
+                    && reference.kind == ReferenceKind::Usage
+                    && is_called(&called, reference.location) =>
+            {
+                (module, name)
+            }
+            _ => continue,
+        };
+        match calls
+            .iter_mut()
+            .find(|calls| calls.function.module == callee_module && calls.function.name == callee)
+        {
+            Some(calls) => calls.locations.push(reference.location),
+            None => {
+                if let Some(function) = find_function(module, &callee_module, &callee, modules) {
+                    calls.push(Calls {
+                        function,
+                        locations: vec![reference.location],
+                    });
+                }
+            }
+        }
+    }
+    calls
+}
+
+/// The locations of the functions called by name in the functions of the
+/// module, being the function of each call expression.
+fn called_functions(module: &Module) -> Vec<SrcSpan> {
+    let mut called = vec![];
+    for statement in &module.ast.statements {
+        if let Statement::Function(Function { body, .. }) = statement {
+            collect_called_functions(body, &mut called);
+        }
+    }
+    called
+}
+
+fn collect_called_functions(expression: &TypedExpr, called: &mut Vec<SrcSpan>) {
+    match expression {
+        TypedExpr::Int { .. }
+        | TypedExpr::Float { .. }
+        | TypedExpr::String { .. }
+        | TypedExpr::Var { .. }
+        | TypedExpr::Todo { .. }
+        | TypedExpr::Panic { .. }
+        | TypedExpr::ModuleSelect { .. } => (),
+
+        TypedExpr::Call { fun, args, .. } => {
+            match fun.as_ref() {
+                TypedExpr::Var { location, .. } | TypedExpr::ModuleSelect { location, .. } => {
+                    called.push(*location)
+                }
+                _ => collect_called_functions(fun, called),
+            }
+            for arg in args {
+                collect_called_functions(&arg.value, called);
+            }
+        }
+
+        TypedExpr::Block { expressions, .. }
+        | TypedExpr::Pipeline { expressions, .. }
+        | TypedExpr::Tuple {
+            elems: expressions, ..
+        } => {
+            for expression in expressions {
+                collect_called_functions(expression, called);
+            }
+        }
+
+        TypedExpr::List { elements, tail, .. } => {
+            for element in elements {
+                collect_called_functions(element, called);
+            }
+            if let Some(tail) = tail {
+                collect_called_functions(tail, called);
+            }
+        }
+
+        TypedExpr::Fn { body, .. } => collect_called_functions(body, called),
+
+        TypedExpr::BinOp { left, right, .. } => {
+            collect_called_functions(left, called);
+            collect_called_functions(right, called);
+        }
+
+        TypedExpr::Assignment { value, .. } => collect_called_functions(value, called),
+
+        TypedExpr::Case {
+            subjects, clauses, ..
+        } => {
+            for subject in subjects {
+                collect_called_functions(subject, called);
+            }
+            for clause in clauses {
+                collect_called_functions(&clause.then, called);
+            }
+        }
+
+        TypedExpr::RecordAccess {
+            record: expression, ..
+        }
+        | TypedExpr::TupleIndex {
+            tuple: expression, ..
+        }
+        | TypedExpr::NegateBool {
+            value: expression, ..
+        }
+        | TypedExpr::NegateInt {
+            value: expression, ..
+        } => collect_called_functions(expression, called),
+
+        TypedExpr::BitString { segments, .. } => {
+            for segment in segments {
+                collect_called_functions(&segment.value, called);
+                for option in &segment.options {
+                    if let BitStringSegmentOption::Size { value, .. } = option {
+                        collect_called_functions(value, called);
+                    }
+                }
+            }
+        }
+
+        TypedExpr::RecordUpdate { spread, args, .. } => {
+            collect_called_functions(spread, called);
+            for arg in args {
+                collect_called_functions(&arg.value, called);
+            }
+        }
+    }
+}
+
+fn is_called(called: &[SrcSpan], location: SrcSpan) -> bool {
+    called
+        .iter()
+        .any(|span| span.start <= location.start && location.end <= span.end)
+}
+
+/// The functions of the module, each with the span of its whole definition.
+fn module_functions(
+    module: &Module,
+) -> impl Iterator<Item = (CallHierarchyFunction, SrcSpan)> + '_ {
+    module.ast.statements.iter().filter_map(|statement| {
+        let (name, location, span) = match statement {
+            Statement::Function(Function {
+                name,
+                location,
+                end_position,
+                ..
+            }) => (
+                name,
+                *location,
+                SrcSpan::new(location.start, end_position + 1),
+            ),
+            _ => return None,
+        };
+        let function = CallHierarchyFunction {
+            module: module.name.clone(),
+            package: module.ast.type_info.package.clone(),
+            name: name.clone(),
+            location,
+        };
+        Some((function, span))
+    })
+}
+
+/// Finds the definition of a function, looking in the statements of the
+/// module itself as the interfaces of modules hold only their public values.
+fn find_function(
+    module: &Module,
+    function_module: &SmolStr,
+    name: &SmolStr,
+    modules: &HashMap<SmolStr, &type_::Module>,
+) -> Option<CallHierarchyFunction> {
+    let (location, package) = if function_module == &module.name {
+        let location = module
+            .ast
+            .statements
+            .iter()
+            .find_map(|statement| match statement {
+                Statement::Function(Function {
+                    name: function,
+                    location,
+                    ..
+                })
+                | Statement::ExternalFunction(ExternalFunction {
+                    name: function,
+                    location,
+                    ..
+                }) if function == name => Some(*location),
+                _ => None,
+            })?;
+        (location, module.ast.type_info.package.clone())
+    } else {
+        let interface = modules.get(function_module)?;
+        match &interface.values.get(name)?.variant {
+            ValueConstructorVariant::ModuleFn { location, .. } => {
+                (*location, interface.package.clone())
+            }
+            ValueConstructorVariant::LocalVariable { .. }
+            | ValueConstructorVariant::ModuleConstant { .. }
+            | ValueConstructorVariant::Record { .. } => return None,
+        }
+    };
+    Some(CallHierarchyFunction {
+        module: function_module.clone(),
+        package,
+        name: name.clone(),
+        location,
+    })
+}
@@ -0,0 +1,416 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use smol_str::SmolStr;
+
+use crate::{
+    ast::{Function, Import, SrcSpan, Statement, TargetGroup, UntypedStatement},
+    parse::parse_module,
+    type_::{self, error::PatternMatchKind},
+};
+
+use super::completion::is_name_char;
+
+/// A fix for a problem in a module, made up of edits to its source code.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeAction {
+    pub title: String,
+    pub kind: CodeActionKind,
+    /// The location of the problem the action fixes.
+    pub location: SrcSpan,
+    pub edits: Vec<Edit>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeActionKind {
+    /// Fixes a single problem.
+    QuickFix,
+    /// Fixes all the problems of some sort in a module at once.
+    FixAll,
+}
+
+/// Replaces the source code at the location with the new text.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub location: SrcSpan,
+    pub new_text: String,
+}
+
+impl Edit {
+    fn insert(at: u32, new_text: String) -> Self {
+        Self {
+            location: SrcSpan::new(at, at),
+            new_text,
+        }
+    }
+}
+
+/// Returns the actions that fix a type error in a module, given the source
+/// code the error was found in.
+///
+pub fn error_actions(error: &type_::Error, src: &str) -> Vec<CodeAction> {
+    match error {
+        type_::Error::NotExhaustivePatternMatch {
+            location,
+            unmatched,
+            kind: PatternMatchKind::Case,
+        } => add_missing_clauses(*location, unmatched, src)
+            .into_iter()
+            .collect(),
+
+        type_::Error::NotExhaustivePatternMatch {
+            location,
+            kind: PatternMatchKind::Assignment,
+            ..
+        } => use_let_assert(*location, src).into_iter().collect(),
+
+        _ => vec![],
+    }
+}
+
+/// Inserts a clause with a `todo` body for each of the unmatched patterns at
+/// the end of the case expression.
+fn add_missing_clauses(location: SrcSpan, unmatched: &[SmolStr], src: &str) -> Option<CodeAction> {
+    let closing_brace = location.end.checked_sub(1)?;
+    let before_brace = src.get(..closing_brace as usize)?;
+    if !src.get(location.start as usize..)?.starts_with("case") {
+        return None;
+    }
+    let insert_at = before_brace.trim_end().len() as u32;
+
+    // Clauses are put on their own lines indented within the closing brace
+    // of the case expression, unless it is written on a single line.
+    let line_start = before_brace.rfind('\n').map(|index| index + 1)?;
+    let indentation = before_brace.get(line_start..)?;
+    let separator = if indentation.trim().is_empty() {
+        format!("\n{indentation}  ")
+    } else {
+        " ".into()
+    };
+
+    let new_text = unmatched
+        .iter()
+        .map(|pattern| format!("{separator}{pattern} -> todo"))
+        .collect();
+    Some(CodeAction {
+        title: "Add missing case clauses".into(),
+        kind: CodeActionKind::QuickFix,
+        location,
+        edits: vec![Edit::insert(insert_at, new_text)],
+    })
+}
+
+/// Turns a `let` that does not match all possible values into a `let assert`.
+fn use_let_assert(location: SrcSpan, src: &str) -> Option<CodeAction> {
+    if !src.get(location.start as usize..)?.starts_with("let ") {
+        return None;
+    }
+    Some(CodeAction {
+        title: "Convert to `let assert`".into(),
+        kind: CodeActionKind::QuickFix,
+        location,
+        edits: vec![Edit::insert(location.start + 3, " assert".into())],
+    })
+}
+
+/// Returns the actions that remove the unused code a module has been warned
+/// about, given the source code the warnings were found in. Along with an
+/// action for each warning there is one that fixes all of them.
+///
+pub fn warning_actions(warnings: &[type_::Warning], src: &str) -> Vec<CodeAction> {
+    let module = match parse_module(src) {
+        Ok((module, _)) => module,
+        Err(_) => return vec![],
+    };
+    let statements = statements(&module.statements);
+    let unused: Vec<_> = warnings
+        .iter()
+        .filter_map(|warning| Unused::from_warning(warning, &statements, src))
+        .collect();
+
+    let mut actions: Vec<_> = unused
+        .iter()
+        .map(|unused| CodeAction {
+            title: unused.title(),
+            kind: CodeActionKind::QuickFix,
+            location: unused.location(),
+            edits: remove_unused(std::slice::from_ref(unused), src),
+        })
+        .collect();
+    if unused.len() > 1 {
+        actions.push(CodeAction {
+            title: "Fix all unused code in module".into(),
+            kind: CodeActionKind::FixAll,
+            location: SrcSpan::new(0, src.len() as u32),
+            edits: remove_unused(&unused, src),
+        });
+    }
+    actions
+}
+
+fn statements(groups: &[TargetGroup]) -> Vec<&UntypedStatement> {
+    groups
+        .iter()
+        .flat_map(TargetGroup::statements_ref)
+        .collect()
+}
+
+/// Unused code that can be removed, or in the case of variables, discarded.
+#[derive(Debug)]
+enum Unused<'a> {
+    UnqualifiedImport {
+        import: &'a Import<()>,
+        index: usize,
+    },
+    Import {
+        import: &'a Import<()>,
+    },
+    Variable {
+        name: SmolStr,
+        location: SrcSpan,
+        start: u32,
+    },
+    Function {
+        name: SmolStr,
+        location: SrcSpan,
+        span: SrcSpan,
+    },
+}
+
+impl<'a> Unused<'a> {
+    fn from_warning(
+        warning: &type_::Warning,
+        statements: &[&'a UntypedStatement],
+        src: &str,
+    ) -> Option<Self> {
+        let imports = || {
+            statements.iter().filter_map(|statement| match statement {
+                Statement::Import(import) => Some(import),
+                _ => None,
+            })
+        };
+
+        match warning {
+            type_::Warning::UnusedImportedValue { location, .. }
+            | type_::Warning::UnusedType {
+                location,
+                imported: true,
+                ..
+            }
+            | type_::Warning::UnusedConstructor {
+                location,
+                imported: true,
+                ..
+            } => imports().find_map(|import| {
+                let index = import
+                    .unqualified
+                    .iter()
+                    .position(|unqualified| unqualified.location == *location)?;
+                Some(Self::UnqualifiedImport { import, index })
+            }),
+
+            type_::Warning::UnusedImportedModule { location, .. } => imports()
+                .find(|import| import.location == *location)
+                .map(|import| Self::Import { import }),
+
+            type_::Warning::UnusedVariable { location, name } => {
+                // The location of a function argument includes its label and
+                // annotation, so the name is the last one before any annotation.
+                let text = src.get(location.start as usize..location.end as usize)?;
+                let text = text.split(':').next().unwrap_or_default();
+                let offset = text
+                    .match_indices(name.as_str())
+                    .filter(|(index, _)| is_word(text, *index, name.len()))
+                    .last()?
+                    .0;
+                Some(Self::Variable {
+                    name: name.clone(),
+                    location: *location,
+                    start: location.start + offset as u32,
+                })
+            }
+
+            type_::Warning::UnusedPrivateFunction { location, name } => {
+                let end_position = statements.iter().find_map(|statement| match statement {
+                    Statement::Function(Function {
+                        location: function_location,
+                        end_position,
+                        ..
+                    }) if function_location == location => Some(*end_position),
+                    _ => None,
+                })?;
+                Some(Self::Function {
+                    name: name.clone(),
+                    location: *location,
+                    span: function_lines(src, location.start, end_position + 1),
+                })
+            }
+
+            _ => None,
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Self::UnqualifiedImport { import, index } => {
+                let name = import
+                    .unqualified
+                    .get(*index)
+                    .map(|unqualified| unqualified.variable_name())
+                    .unwrap_or_default();
+                format!("Remove unused import `{name}`")
+            }
+            Self::Import { import } => format!("Remove unused import `{}`", import.module),
+            Self::Variable { name, .. } => format!("Prefix unused variable `{name}` with `_`"),
+            Self::Function { name, .. } => format!("Remove unused function `{name}`"),
+        }
+    }
+
+    fn location(&self) -> SrcSpan {
+        match self {
+            Self::UnqualifiedImport { import, index } => import
+                .unqualified
+                .get(*index)
+                .map(|unqualified| unqualified.location)
+                .unwrap_or(import.location),
+            Self::Import { import } => import.location,
+            Self::Variable { location, .. } | Self::Function { location, .. } => *location,
+        }
+    }
+}
+
+/// The edits that remove all of the unused code. Unused unqualified imports
+/// from the same import are removed together, and nothing is edited within
+/// the functions being removed.
+fn remove_unused(unused: &[Unused<'_>], src: &str) -> Vec<Edit> {
+    let mut edits = vec![];
+    let mut unqualified_imports: BTreeMap<u32, (&Import<()>, BTreeSet<usize>)> = BTreeMap::new();
+    for unused in unused {
+        match unused {
+            Unused::UnqualifiedImport { import, index } => {
+                let _ = unqualified_imports
+                    .entry(import.location.start)
+                    .or_insert_with(|| (import, BTreeSet::new()))
+                    .1
+                    .insert(*index);
+            }
+            Unused::Import { import } => edits.push(Edit {
+                location: whole_lines(src, import.location.start, import.location.end),
+                new_text: String::new(),
+            }),
+            Unused::Variable { start, .. } => edits.push(Edit::insert(*start, "_".into())),
+            Unused::Function { span, .. } => edits.push(Edit {
+                location: *span,
+                new_text: String::new(),
+            }),
+        }
+    }
+    for (import, unused) in unqualified_imports.into_values() {
+        edits.extend(remove_unqualified_imports(import, &unused, src));
+    }
+
+    let removed: Vec<_> = edits
+        .iter()
+        .filter(|edit| edit.new_text.is_empty())
+        .map(|edit| edit.location)
+        .collect();
+    edits.retain(|edit| {
+        !removed.iter().any(|span| {
+            *span != edit.location
+                && span.start <= edit.location.start
+                && edit.location.end <= span.end
+        })
+    });
+    edits.sort_by_key(|edit| edit.location.start);
+    edits
+}
+
+/// Removes the unqualified imports at the given indexes, rewriting the list of
+/// those that remain, or removing the list entirely if none remain.
+fn remove_unqualified_imports(
+    import: &Import<()>,
+    unused: &BTreeSet<usize>,
+    src: &str,
+) -> Option<Edit> {
+    let start = import.location.start as usize;
+    let open = start + src.get(start..)?.find(".{")?;
+    let close = open + src.get(open..)?.find('}')?;
+
+    if unused.len() == import.unqualified.len() {
+        return Some(Edit {
+            location: SrcSpan::new(open as u32, close as u32 + 1),
+            new_text: String::new(),
+        });
+    }
+
+    let remaining: Option<Vec<_>> = import
+        .unqualified
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !unused.contains(index))
+        .map(|(_, unqualified)| {
+            let start = unqualified.location.start as usize;
+            let mut end = unqualified.location.end as usize;
+            if let Some(as_name) = &unqualified.as_name {
+                end += src.get(end..)?.find(as_name.as_str())? + as_name.len();
+            }
+            src.get(start..end)
+        })
+        .collect();
+    Some(Edit {
+        location: SrcSpan::new(open as u32 + 2, close as u32),
+        new_text: remaining?.join(", "),
+    })
+}
+
+/// The span of the whole lines from the start to the end. Only whole lines are
+/// removed, so the keyword `import` or `fn` before the start is removed too.
+fn whole_lines(src: &str, start: u32, end: u32) -> SrcSpan {
+    let start = line_start(src, start as usize);
+    let end = line_end(src, end as usize);
+    SrcSpan::new(start as u32, end as u32)
+}
+
+/// The span of the whole lines of a function, including its documentation
+/// comment and a blank line after it.
+fn function_lines(src: &str, start: u32, end: u32) -> SrcSpan {
+    let SrcSpan { start, end } = whole_lines(src, start, end);
+    let mut start = start as usize;
+    let mut end = end as usize;
+    while start > 0 {
+        let previous = line_start(src, start - 1);
+        match src.get(previous..start) {
+            Some(line) if line.trim_start().starts_with("///") => start = previous,
+            _ => break,
+        }
+    }
+    if let Some(line) = src.get(end..line_end(src, end)) {
+        if line.trim().is_empty() {
+            end += line.len();
+        }
+    }
+    SrcSpan::new(start as u32, end as u32)
+}
+
+fn line_start(src: &str, index: usize) -> usize {
+    src.get(..index)
+        .and_then(|before| before.rfind('\n'))
+        .map(|newline| newline + 1)
+        .unwrap_or(0)
+}
+
+fn line_end(src: &str, index: usize) -> usize {
+    src.get(index..)
+        .and_then(|after| after.find('\n'))
+        .map(|newline| index + newline + 1)
+        .unwrap_or(src.len())
+}
+
+fn is_word(text: &str, index: usize, len: usize) -> bool {
+    let before = text.get(..index).and_then(|before| before.chars().last());
+    let after = text
+        .get(index + len..)
+        .and_then(|after| after.chars().next());
+    !before.is_some_and(is_name_char) && !after.is_some_and(is_name_char)
+}
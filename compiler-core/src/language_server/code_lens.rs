@@ -0,0 +1,65 @@
+use smol_str::SmolStr;
+
+use crate::{
+    ast::{Function, SrcSpan, Statement},
+    build::{Module, Origin},
+};
+
+/// A command shown above a test module or test function to run its tests.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestLens {
+    pub location: SrcSpan,
+    pub title: &'static str,
+    /// The test functions run by the command.
+    pub functions: Vec<SmolStr>,
+}
+
+/// Returns a lens to run all the tests of a test module at the top of the
+/// module, and one to run each test above the test function.
+///
+pub fn test_lenses(module: &Module) -> Vec<TestLens> {
+    let tests = test_functions(module);
+    if tests.is_empty() {
+        return vec![];
+    }
+
+    let run_module = TestLens {
+        location: SrcSpan::new(0, 0),
+        title: "Run module",
+        functions: tests.iter().map(|(name, _)| name.clone()).collect(),
+    };
+    let run_tests = tests.into_iter().map(|(name, location)| TestLens {
+        location,
+        title: "Run test",
+        functions: vec![name],
+    });
+    std::iter::once(run_module).chain(run_tests).collect()
+}
+
+/// The test functions of a module in the `test` directory, being the public
+/// functions without arguments with names ending in `_test`, along with the
+/// location of the head of each.
+///
+pub fn test_functions(module: &Module) -> Vec<(SmolStr, SrcSpan)> {
+    if module.origin != Origin::Test {
+        return vec![];
+    }
+    module
+        .ast
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Function(Function {
+                name,
+                location,
+                arguments,
+                public: true,
+                ..
+            }) if name.ends_with("_test") && arguments.is_empty() => {
+                Some((name.clone(), *location))
+            }
+            _ => None,
+        })
+        .collect()
+}
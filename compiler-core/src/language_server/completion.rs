@@ -0,0 +1,1021 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use itertools::Itertools;
+use smol_str::SmolStr;
+
+use crate::{
+    analyse::get_compatible_record_fields,
+    ast::{
+        Arg, AssignName, CustomType, ExternalFunction, ExternalType, Function, Import,
+        ModuleConstant, Pattern, SrcSpan, Statement, TypeAlias, TypedClause, TypedExpr,
+        TypedPattern,
+    },
+    build::{attach_doc_comments, Module},
+    parse::{extra::Comment, parse_module_with_recovery, Parsed},
+    type_::{self, prelude::fn_, pretty::Printer, Type, ValueConstructorVariant},
+};
+
+use super::reference::named_type;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Variable,
+    Function,
+    Constant,
+    Constructor,
+    Field,
+    Type,
+    Module,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub label: SmolStr,
+    pub kind: CompletionKind,
+    /// The printed type of the completed value.
+    pub detail: Option<String>,
+    pub documentation: Option<SmolStr>,
+}
+
+/// The module comments of a module and the doc comments of its top level
+/// definitions, which are not stored in the module interface.
+///
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Documentation {
+    module: Option<SmolStr>,
+    values: HashMap<SmolStr, SmolStr>,
+    types: HashMap<SmolStr, SmolStr>,
+}
+
+impl Documentation {
+    /// Collects the documentation from the statements of a module, which must
+    /// already have had their doc comments attached.
+    pub fn from_statements<'a, A: 'a, B: 'a, C: 'a, D: 'a>(
+        statements: impl IntoIterator<Item = &'a Statement<A, B, C, D>>,
+    ) -> Self {
+        let mut documentation = Self::default();
+        for statement in statements {
+            match statement {
+                Statement::Function(Function { name, doc, .. })
+                | Statement::ExternalFunction(ExternalFunction { name, doc, .. })
+                | Statement::ModuleConstant(ModuleConstant { name, doc, .. }) => {
+                    documentation.insert_value(name, doc.as_ref());
+                }
+
+                Statement::TypeAlias(TypeAlias {
+                    alias: name, doc, ..
+                })
+                | Statement::ExternalType(ExternalType { name, doc, .. }) => {
+                    documentation.insert_type(name, doc.as_ref());
+                }
+
+                Statement::CustomType(CustomType {
+                    name,
+                    doc,
+                    constructors,
+                    ..
+                }) => {
+                    documentation.insert_type(name, doc.as_ref());
+                    for constructor in constructors {
+                        documentation
+                            .insert_value(&constructor.name, constructor.documentation.as_ref());
+                    }
+                }
+
+                Statement::Import(_) => (),
+            }
+        }
+        documentation
+    }
+
+    /// Collects the documentation of a compiled module, which must already
+    /// have had its doc and module comments attached.
+    pub fn from_module(module: &Module) -> Self {
+        let mut documentation = Self::from_statements(&module.ast.statements);
+        documentation.module = module_documentation(&module.ast.documentation);
+        documentation
+    }
+
+    /// Parses the source code of a module to find its documentation. This is
+    /// used for modules from dependency packages, for which the language server
+    /// only has the module interface. If the code has syntax errors the
+    /// documentation of the statements that could be parsed is used.
+    pub fn parse(code: &str) -> Self {
+        let Parsed { module, extra, .. } = parse_module_with_recovery(code);
+        let mut statements: Vec<_> = module
+            .statements
+            .into_iter()
+            .flat_map(|group| group.statements())
+            .collect();
+        attach_doc_comments(statements.iter_mut(), &extra.doc_comments, code);
+        let mut documentation = Self::from_statements(&statements);
+        let module_comments = extra
+            .module_comments
+            .iter()
+            .map(|span| Comment::from((span, code)).content.into())
+            .collect::<Vec<SmolStr>>();
+        documentation.module = module_documentation(&module_comments);
+        documentation
+    }
+
+    pub(crate) fn module(&self) -> Option<&SmolStr> {
+        self.module.as_ref()
+    }
+
+    pub(crate) fn value(&self, name: &str) -> Option<&SmolStr> {
+        self.values.get(name)
+    }
+
+    pub(crate) fn type_(&self, name: &str) -> Option<&SmolStr> {
+        self.types.get(name)
+    }
+
+    fn insert_value(&mut self, name: &SmolStr, doc: Option<&SmolStr>) {
+        if let Some(doc) = doc {
+            let _ = self.values.insert(name.clone(), doc.clone());
+        }
+    }
+
+    fn insert_type(&mut self, name: &SmolStr, doc: Option<&SmolStr>) {
+        if let Some(doc) = doc {
+            let _ = self.types.insert(name.clone(), doc.clone());
+        }
+    }
+}
+
+fn module_documentation(module_comments: &[SmolStr]) -> Option<SmolStr> {
+    if module_comments.is_empty() {
+        return None;
+    }
+    Some(module_comments.iter().join("\n").into())
+}
+
+/// What is being written at the position of the cursor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Context {
+    Expression,
+    Pattern,
+    Type,
+    /// A value, constructor or type from an imported module, after `module.`
+    ModuleMember {
+        module: SmolStr,
+        layer: Layer,
+    },
+    /// A field of a record held in a local variable, after `variable.`
+    RecordField {
+        variable: SmolStr,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layer {
+    Values,
+    Constructors,
+    Types,
+}
+
+/// Returns the completions for the position of the cursor in the source code.
+///
+/// The source code may have been edited since the module was last compiled
+/// successfully, for example to type the `.` after a module name, so the
+/// context is worked out from the source code while the names in scope are
+/// taken from the compiled module.
+///
+/// `modules` holds the interfaces of the modules that can be imported and
+/// `documentation` the doc comments for any of them that are known.
+///
+pub fn completions(
+    module: &Module,
+    src: &str,
+    cursor: u32,
+    modules: &HashMap<SmolStr, &type_::Module>,
+    documentation: &HashMap<SmolStr, Documentation>,
+) -> Vec<Completion> {
+    let completer = Completer {
+        module,
+        cursor,
+        modules,
+        documentation,
+        own_documentation: Documentation::from_statements(&module.ast.statements),
+    };
+    let mut completions = match context(module, src, cursor) {
+        Some(context) => completer.complete(context),
+        None => vec![],
+    };
+
+    // Earlier completions shadow later ones with the same name
+    let mut seen = HashSet::new();
+    completions.retain(|completion| seen.insert(completion.label.clone()));
+    completions
+}
+
+struct Completer<'a> {
+    module: &'a Module,
+    cursor: u32,
+    modules: &'a HashMap<SmolStr, &'a type_::Module>,
+    documentation: &'a HashMap<SmolStr, Documentation>,
+    own_documentation: Documentation,
+}
+
+impl<'a> Completer<'a> {
+    fn complete(&self, context: Context) -> Vec<Completion> {
+        match context {
+            Context::Expression => {
+                let mut completions = self.local_variables();
+                completions.extend(self.module_values(Layer::Values));
+                completions.extend(self.imported(Layer::Values));
+                completions.extend(self.prelude(Layer::Values));
+                completions.extend(self.module_names());
+                completions
+            }
+
+            Context::Pattern => {
+                let mut completions = self.module_values(Layer::Constructors);
+                completions.extend(self.imported(Layer::Constructors));
+                completions.extend(self.prelude(Layer::Constructors));
+                completions.extend(self.module_names());
+                completions
+            }
+
+            Context::Type => {
+                let mut completions = self.module_types();
+                completions.extend(self.imported(Layer::Types));
+                completions.extend(self.prelude(Layer::Types));
+                completions.extend(self.module_names());
+                completions
+            }
+
+            Context::ModuleMember { module, layer } => match self.modules.get(&module) {
+                Some(interface) => self.interface_members(interface, layer, None),
+                None => vec![],
+            },
+
+            Context::RecordField { variable } => self.record_fields(&variable),
+        }
+    }
+
+    fn local_variables(&self) -> Vec<Completion> {
+        let mut locals = locals_in_scope(self.module, self.cursor);
+        locals.sort_by(|a, b| a.0.cmp(&b.0));
+        locals
+            .into_iter()
+            .map(|(name, type_)| Completion {
+                label: name,
+                kind: CompletionKind::Variable,
+                detail: type_.map(|type_| print(&type_)),
+                documentation: None,
+            })
+            .collect()
+    }
+
+    /// The values or types defined in the module being edited, including
+    /// private ones.
+    fn module_values(&self, layer: Layer) -> Vec<Completion> {
+        let mut completions = vec![];
+        for statement in &self.module.ast.statements {
+            match statement {
+                Statement::Function(Function {
+                    name,
+                    arguments,
+                    return_type,
+                    ..
+                }) if layer == Layer::Values => {
+                    let arguments = arguments.iter().map(|a| a.type_.clone()).collect();
+                    let type_ = fn_(arguments, return_type.clone());
+                    completions.push(self.own_value(name, CompletionKind::Function, &type_));
+                }
+
+                Statement::ExternalFunction(ExternalFunction {
+                    name,
+                    arguments,
+                    return_type,
+                    ..
+                }) if layer == Layer::Values => {
+                    let arguments = arguments.iter().map(|a| a.type_.clone()).collect();
+                    let type_ = fn_(arguments, return_type.clone());
+                    completions.push(self.own_value(name, CompletionKind::Function, &type_));
+                }
+
+                Statement::ModuleConstant(ModuleConstant { name, type_, .. })
+                    if layer == Layer::Values =>
+                {
+                    completions.push(self.own_value(name, CompletionKind::Constant, type_));
+                }
+
+                Statement::CustomType(CustomType {
+                    name,
+                    public,
+                    constructors,
+                    typed_parameters,
+                    ..
+                }) => {
+                    let custom_type = Arc::new(Type::App {
+                        public: *public,
+                        module: self.module.name.clone(),
+                        name: name.clone(),
+                        args: typed_parameters.clone(),
+                    });
+                    for constructor in constructors {
+                        let type_ = if constructor.arguments.is_empty() {
+                            custom_type.clone()
+                        } else {
+                            let arguments = constructor
+                                .arguments
+                                .iter()
+                                .map(|a| a.type_.clone())
+                                .collect();
+                            fn_(arguments, custom_type.clone())
+                        };
+                        completions.push(self.own_value(
+                            &constructor.name,
+                            CompletionKind::Constructor,
+                            &type_,
+                        ));
+                    }
+                }
+
+                Statement::Function(_)
+                | Statement::ExternalFunction(_)
+                | Statement::ModuleConstant(_)
+                | Statement::TypeAlias(_)
+                | Statement::ExternalType(_)
+                | Statement::Import(_) => (),
+            }
+        }
+        completions.sort_by(|a, b| a.label.cmp(&b.label));
+        completions
+    }
+
+    fn own_value(&self, name: &SmolStr, kind: CompletionKind, type_: &Type) -> Completion {
+        Completion {
+            label: name.clone(),
+            kind,
+            detail: Some(print(type_)),
+            documentation: self.own_documentation.values.get(name).cloned(),
+        }
+    }
+
+    fn module_types(&self) -> Vec<Completion> {
+        let mut completions: Vec<_> = self
+            .module
+            .ast
+            .statements
+            .iter()
+            .filter_map(|statement| {
+                let (name, detail) = match statement {
+                    Statement::CustomType(CustomType { name, .. })
+                    | Statement::ExternalType(ExternalType { name, .. }) => (name, None),
+                    Statement::TypeAlias(TypeAlias { alias, type_, .. }) => {
+                        (alias, Some(print(type_)))
+                    }
+                    Statement::Function(_)
+                    | Statement::ExternalFunction(_)
+                    | Statement::Import(_)
+                    | Statement::ModuleConstant(_) => return None,
+                };
+                Some(Completion {
+                    label: name.clone(),
+                    kind: CompletionKind::Type,
+                    detail,
+                    documentation: self.own_documentation.types.get(name).cloned(),
+                })
+            })
+            .collect();
+        completions.sort_by(|a, b| a.label.cmp(&b.label));
+        completions
+    }
+
+    /// The values, constructors or types imported unqualified into the module.
+    fn imported(&self, layer: Layer) -> Vec<Completion> {
+        let mut completions = vec![];
+        for import in self.imports() {
+            let interface = match self.modules.get(&import.module) {
+                Some(interface) => interface,
+                None => continue,
+            };
+            for unqualified in &import.unqualified {
+                let name = unqualified.variable_name();
+                let found = self.interface_members(interface, layer, Some(&unqualified.name));
+                completions.extend(found.into_iter().map(|completion| Completion {
+                    label: name.into(),
+                    ..completion
+                }));
+            }
+        }
+        completions
+    }
+
+    fn prelude(&self, layer: Layer) -> Vec<Completion> {
+        match self.modules.get("gleam") {
+            Some(prelude) => self.interface_members(prelude, layer, None),
+            None => vec![],
+        }
+    }
+
+    fn module_names(&self) -> Vec<Completion> {
+        self.imports()
+            .map(|import| Completion {
+                label: import.variable_name(),
+                kind: CompletionKind::Module,
+                detail: Some(import.module.to_string()),
+                documentation: None,
+            })
+            .collect()
+    }
+
+    /// The public members of a module interface in the given layer, or only
+    /// the one with the given name if there is one.
+    fn interface_members(
+        &self,
+        interface: &type_::Module,
+        layer: Layer,
+        only: Option<&SmolStr>,
+    ) -> Vec<Completion> {
+        let documentation = self.documentation.get(&interface.name);
+        let included = |name: &SmolStr| only.map(|only| only == name).unwrap_or(true);
+
+        let mut completions: Vec<_> = if layer == Layer::Types {
+            interface
+                .types
+                .iter()
+                .filter(|(name, _)| included(name))
+                .map(|(name, _)| Completion {
+                    label: name.clone(),
+                    kind: CompletionKind::Type,
+                    detail: None,
+                    documentation: documentation.and_then(|d| d.types.get(name).cloned()),
+                })
+                .collect()
+        } else {
+            interface
+                .values
+                .iter()
+                .filter(|(name, _)| included(name))
+                .filter_map(|(name, value)| {
+                    let kind = match &value.variant {
+                        ValueConstructorVariant::Record { .. } => CompletionKind::Constructor,
+                        ValueConstructorVariant::ModuleFn { .. } => CompletionKind::Function,
+                        ValueConstructorVariant::ModuleConstant { .. } => CompletionKind::Constant,
+                        ValueConstructorVariant::LocalVariable { .. } => return None,
+                    };
+                    if layer == Layer::Constructors && kind != CompletionKind::Constructor {
+                        return None;
+                    }
+                    Some(Completion {
+                        label: name.clone(),
+                        kind,
+                        detail: Some(print(&value.type_)),
+                        documentation: documentation.and_then(|d| d.values.get(name).cloned()),
+                    })
+                })
+                .collect()
+        };
+        completions.sort_by(|a, b| a.label.cmp(&b.label));
+        completions
+    }
+
+    fn record_fields(&self, variable: &str) -> Vec<Completion> {
+        let type_ = locals_in_scope(self.module, self.cursor)
+            .into_iter()
+            .find(|(name, _)| name == variable)
+            .and_then(|(_, type_)| type_);
+        let (module, type_name) = match type_.as_deref().and_then(named_type) {
+            Some(found) => found,
+            None => return vec![],
+        };
+
+        let mut fields: Vec<(SmolStr, Arc<Type>)> = if module == self.module.name {
+            self.own_record_fields(&type_name)
+        } else {
+            self.modules
+                .get(&module)
+                .and_then(|interface| interface.accessors.get(&type_name))
+                .map(|accessors| {
+                    accessors
+                        .accessors
+                        .values()
+                        .map(|accessor| (accessor.label.clone(), accessor.type_.clone()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        fields.sort_by(|a, b| a.0.cmp(&b.0));
+        fields
+            .into_iter()
+            .map(|(label, type_)| Completion {
+                label,
+                kind: CompletionKind::Field,
+                detail: Some(print(&type_)),
+                documentation: None,
+            })
+            .collect()
+    }
+
+    /// The fields shared by all the constructors of a custom type defined in
+    /// the module being edited. The module interface only holds accessors for
+    /// public types so these are found from the definition instead.
+    fn own_record_fields(&self, type_name: &str) -> Vec<(SmolStr, Arc<Type>)> {
+        let constructors = self.module.ast.statements.iter().find_map(|s| match s {
+            Statement::CustomType(CustomType {
+                name, constructors, ..
+            }) if name == type_name => Some(constructors),
+            _ => None,
+        });
+        let constructors = match constructors {
+            Some(constructors) => constructors,
+            None => return vec![],
+        };
+        let first = match constructors.first() {
+            Some(first) => first,
+            None => return vec![],
+        };
+        get_compatible_record_fields(constructors)
+            .into_iter()
+            .filter_map(|(index, label, _)| {
+                let argument = first.arguments.get(index)?;
+                Some((label.clone(), argument.type_.clone()))
+            })
+            .collect()
+    }
+
+    fn imports(&self) -> impl Iterator<Item = &Import<SmolStr>> {
+        self.module
+            .ast
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Import(import) => Some(import),
+                _ => None,
+            })
+    }
+}
+
+/// The variables defined in the function containing the cursor that are
+/// in scope at the cursor, along with their types where known.
+pub(crate) fn locals_in_scope(module: &Module, cursor: u32) -> Vec<(SmolStr, Option<Arc<Type>>)> {
+    let collector = LocalsCollector::for_position(module, cursor);
+    let mut visible: HashMap<SmolStr, &Local> = HashMap::new();
+    for local in &collector.locals {
+        if local.definition.start < cursor
+            && in_scope(local.scope, cursor)
+            && visible
+                .get(&local.name)
+                .map(|other| other.definition.start < local.definition.start)
+                .unwrap_or(true)
+        {
+            let _ = visible.insert(local.name.clone(), local);
+        }
+    }
+    visible
+        .into_values()
+        .map(|local| (local.name.clone(), collector.type_of(local)))
+        .collect()
+}
+
+/// The type of the local variable defined at the location, if it is known.
+pub(crate) fn local_variable_type(module: &Module, definition: SrcSpan) -> Option<Arc<Type>> {
+    let collector = LocalsCollector::for_position(module, definition.start);
+    let local = collector
+        .locals
+        .iter()
+        .find(|local| local.definition == definition)?;
+    collector.type_of(local)
+}
+
+fn print(type_: &Type) -> String {
+    Printer::new().pretty_print(type_, 0)
+}
+
+fn in_scope(scope: SrcSpan, cursor: u32) -> bool {
+    scope.start <= cursor && cursor <= scope.end
+}
+
+#[derive(Debug)]
+struct Local {
+    name: SmolStr,
+    definition: SrcSpan,
+    /// The part of the source code in which the variable can be referred to.
+    scope: SrcSpan,
+    type_: Option<Arc<Type>>,
+}
+
+/// Finds the local variables defined within a function. The types of
+/// variables defined in patterns are not recorded in the typed AST, so they
+/// are worked out from the type of the value the pattern matches, or failing
+/// that from where the variables are used.
+#[derive(Debug, Default)]
+struct LocalsCollector {
+    locals: Vec<Local>,
+    usage_types: HashMap<SrcSpan, Arc<Type>>,
+}
+
+impl LocalsCollector {
+    /// Collects the local variables of the functions containing the position.
+    fn for_position(module: &Module, position: u32) -> Self {
+        let mut collector = Self::default();
+        for statement in &module.ast.statements {
+            if let Statement::Function(Function {
+                location,
+                end_position,
+                arguments,
+                body,
+                ..
+            }) = statement
+            {
+                let scope = SrcSpan::new(location.start, end_position + 1);
+                if in_scope(scope, position) {
+                    collector.arguments(arguments, scope);
+                    collector.expression(body, scope.end);
+                }
+            }
+        }
+        collector
+    }
+
+    fn type_of(&self, local: &Local) -> Option<Arc<Type>> {
+        local
+            .type_
+            .clone()
+            .or_else(|| self.usage_types.get(&local.definition).cloned())
+    }
+
+    fn define(
+        &mut self,
+        name: &SmolStr,
+        definition: SrcSpan,
+        scope: SrcSpan,
+        type_: Option<Arc<Type>>,
+    ) {
+        // Variables starting with an underscore are generated by the compiler
+        if !name.starts_with('_') {
+            self.locals.push(Local {
+                name: name.clone(),
+                definition,
+                scope,
+                type_,
+            });
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[Arg<Arc<Type>>], scope: SrcSpan) {
+        for argument in arguments {
+            if let Some(name) = argument.get_variable_name() {
+                self.define(name, argument.location, scope, Some(argument.type_.clone()));
+            }
+        }
+    }
+
+    /// Collects the variables defined in the expression, which are in scope
+    /// until the given position at the latest.
+    fn expression(&mut self, expression: &TypedExpr, scope_end: u32) {
+        match expression {
+            TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Todo { .. }
+            | TypedExpr::Panic { .. }
+            | TypedExpr::ModuleSelect { .. } => (),
+
+            TypedExpr::Var { constructor, .. } => {
+                if let ValueConstructorVariant::LocalVariable { location } = &constructor.variant {
+                    let _ = self
+                        .usage_types
+                        .insert(*location, constructor.type_.clone());
+                }
+            }
+
+            TypedExpr::Block { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                for expression in expressions {
+                    if let TypedExpr::Assignment { value, pattern, .. } = expression {
+                        self.expression(value, scope_end);
+                        let scope = SrcSpan::new(expression.location().end, scope_end);
+                        self.pattern(pattern, scope, Some(value.type_()));
+                    } else {
+                        self.expression(expression, scope_end);
+                    }
+                }
+            }
+
+            // An assignment on its own is the last expression of a block, so
+            // the variables it defines cannot be used.
+            TypedExpr::Assignment { value, .. } => self.expression(value, scope_end),
+
+            TypedExpr::Fn {
+                location,
+                args,
+                body,
+                ..
+            } => {
+                self.arguments(args, *location);
+                self.expression(body, location.end);
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                for element in elements {
+                    self.expression(element, scope_end);
+                }
+                if let Some(tail) = tail {
+                    self.expression(tail, scope_end);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expression(fun, scope_end);
+                for arg in args {
+                    self.expression(&arg.value, scope_end);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expression(left, scope_end);
+                self.expression(right, scope_end);
+            }
+
+            TypedExpr::Case {
+                subjects, clauses, ..
+            } => {
+                for subject in subjects {
+                    self.expression(subject, scope_end);
+                }
+                for clause in clauses {
+                    self.clause(clause, subjects);
+                }
+            }
+
+            TypedExpr::RecordAccess { record, .. } => self.expression(record, scope_end),
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expression(tuple, scope_end),
+
+            TypedExpr::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.expression(elem, scope_end);
+                }
+            }
+
+            TypedExpr::BitString { segments, .. } => {
+                for segment in segments {
+                    self.expression(&segment.value, scope_end);
+                }
+            }
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expression(spread, scope_end);
+                for arg in args {
+                    self.expression(&arg.value, scope_end);
+                }
+            }
+
+            TypedExpr::NegateBool { value, .. } | TypedExpr::NegateInt { value, .. } => {
+                self.expression(value, scope_end)
+            }
+        }
+    }
+
+    fn clause(&mut self, clause: &TypedClause, subjects: &[TypedExpr]) {
+        for (pattern, subject) in clause.pattern.iter().zip(subjects) {
+            self.pattern(pattern, clause.location, Some(subject.type_()));
+        }
+        self.expression(&clause.then, clause.location.end);
+    }
+
+    /// Collects the variables defined by the pattern. The type of the pattern
+    /// is given if it is known, and the types of the patterns within it are
+    /// found from it.
+    fn pattern(&mut self, pattern: &TypedPattern, scope: SrcSpan, type_: Option<Arc<Type>>) {
+        let type_ = type_.map(type_::collapse_links);
+        match pattern {
+            Pattern::Int { .. }
+            | Pattern::Float { .. }
+            | Pattern::String { .. }
+            | Pattern::VarUsage { .. }
+            | Pattern::Discard { .. } => (),
+
+            Pattern::Var { location, name } => self.define(name, *location, scope, type_),
+
+            Pattern::Assign {
+                name,
+                location,
+                pattern,
+            } => {
+                self.define(name, *location, scope, type_.clone());
+                self.pattern(pattern, scope, type_);
+            }
+
+            Pattern::List { elements, tail, .. } => {
+                let element_type = match type_.as_deref() {
+                    Some(Type::App { args, .. }) => args.first().cloned(),
+                    _ => None,
+                };
+                for element in elements {
+                    self.pattern(element, scope, element_type.clone());
+                }
+                if let Some(tail) = tail {
+                    self.pattern(tail, scope, type_);
+                }
+            }
+
+            // The constructor has its type with the arguments it is matched
+            // with, and the arguments are in the order of that type.
+            Pattern::Constructor {
+                arguments,
+                type_: constructor_type,
+                ..
+            } => {
+                let argument_types = constructor_type
+                    .fn_types()
+                    .map(|(argument_types, _)| argument_types)
+                    .unwrap_or_default();
+                for (index, argument) in arguments.iter().enumerate() {
+                    self.pattern(&argument.value, scope, argument_types.get(index).cloned());
+                }
+            }
+
+            Pattern::Tuple { elems, .. } => {
+                let element_types = match type_.as_deref() {
+                    Some(Type::Tuple { elems }) => elems.clone(),
+                    _ => vec![],
+                };
+                for (index, elem) in elems.iter().enumerate() {
+                    self.pattern(elem, scope, element_types.get(index).cloned());
+                }
+            }
+
+            Pattern::BitString { segments, .. } => {
+                for segment in segments {
+                    self.pattern(&segment.value, scope, Some(segment.type_.clone()));
+                }
+            }
+
+            Pattern::Concatenate {
+                right_location,
+                right_side_assignment,
+                ..
+            } => {
+                if let AssignName::Variable(name) = right_side_assignment {
+                    self.define(name, *right_location, scope, Some(type_::string()));
+                }
+            }
+        }
+    }
+}
+
+/// Works out what is being written at the cursor from the source code before
+/// it.
+fn context(module: &Module, src: &str, cursor: u32) -> Option<Context> {
+    let before = src.get(..cursor as usize)?;
+    let before = before.trim_end_matches(is_name_char);
+
+    if let Some(before_dot) = before.strip_suffix('.') {
+        let qualifier_start = before_dot.trim_end_matches(is_name_char).len();
+        let qualifier = before_dot.get(qualifier_start..)?;
+        let before_qualifier = before_dot.get(..qualifier_start)?;
+        if !qualifier.starts_with(|c: char| c.is_ascii_lowercase()) {
+            return None;
+        }
+
+        let imported = module.ast.statements.iter().find_map(|s| match s {
+            Statement::Import(import) if import.variable_name() == qualifier => {
+                Some(import.module.clone())
+            }
+            _ => None,
+        });
+        return Some(match imported {
+            Some(module) => {
+                let layer = if is_type_context(before_qualifier) {
+                    Layer::Types
+                } else if is_pattern_context(before_qualifier) {
+                    Layer::Constructors
+                } else {
+                    Layer::Values
+                };
+                Context::ModuleMember { module, layer }
+            }
+            None => Context::RecordField {
+                variable: qualifier.into(),
+            },
+        });
+    }
+
+    Some(if is_type_context(before) {
+        Context::Type
+    } else if is_pattern_context(before) {
+        Context::Pattern
+    } else {
+        Context::Expression
+    })
+}
+
+pub(crate) fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// The text of the line that the end of the source code is on, without
+/// leading whitespace.
+fn current_line(src: &str) -> &str {
+    let start = src.rfind('\n').map(|index| index + 1).unwrap_or(0);
+    src.get(start..).unwrap_or_default().trim_start()
+}
+
+fn starts_with_any(line: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|prefix| line.starts_with(prefix))
+}
+
+/// Whether a type annotation is being written at the end of the source code.
+fn is_type_context(before: &str) -> bool {
+    let trimmed = before.trim_end();
+    let line = current_line(trimmed);
+
+    // A return annotation of a named or anonymous function
+    if trimmed.ends_with("->") {
+        return starts_with_any(line, &["fn ", "pub fn ", "fn("]) || line.contains(" fn(");
+    }
+
+    // A variable or argument annotation, or a field of a custom type
+    if trimmed.ends_with(':') {
+        return starts_with_any(
+            line,
+            &["fn ", "pub fn ", "let ", "assert ", "const ", "pub const "],
+        ) || line.contains("fn(")
+            || enclosing_brace_line(trimmed)
+                .map(|line| starts_with_any(line, &["type ", "pub type ", "pub opaque type "]))
+                .unwrap_or(false);
+    }
+
+    // A type parameter, e.g. `List(`
+    if trimmed.ends_with('(') || trimmed.ends_with(',') {
+        let open = match enclosing_open(trimmed, '(', ')') {
+            Some(open) => open,
+            None => return false,
+        };
+        let before_paren = trimmed.get(..open).unwrap_or_default();
+        let name_start = before_paren.trim_end_matches(is_name_char).len();
+        let name = before_paren.get(name_start..).unwrap_or_default();
+        let mut before_name = before_paren.get(..name_start).unwrap_or_default();
+        if name.is_empty() {
+            // A tuple type, e.g. `#(`
+            before_name = match before_name.strip_suffix('#') {
+                Some(before_hash) => before_hash,
+                None => return false,
+            };
+        } else if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+            return false;
+        } else if let Some(before_dot) = before_name.strip_suffix('.') {
+            // A qualified type, e.g. `option.Option(`
+            before_name = before_dot.trim_end_matches(is_name_char);
+        }
+        return is_type_context(before_name);
+    }
+
+    false
+}
+
+/// Whether a pattern is being written at the end of the source code.
+fn is_pattern_context(before: &str) -> bool {
+    let line = current_line(before);
+
+    // The pattern of a let or assert assignment
+    if starts_with_any(line, &["let ", "assert "]) {
+        return !line.contains('=');
+    }
+
+    // The pattern of a case clause, but not the start of a clause body on the
+    // line after the arrow
+    let previous_line = before
+        .rsplit('\n')
+        .skip(1)
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    !line.contains("->")
+        && !previous_line.ends_with("->")
+        && enclosing_brace_line(before)
+            .map(|line| line.starts_with("case ") || line.contains(" case "))
+            .unwrap_or(false)
+}
+
+/// The line containing the opening brace of the block that the end of the
+/// source code is in.
+fn enclosing_brace_line(src: &str) -> Option<&str> {
+    let open = enclosing_open(src, '{', '}')?;
+    Some(current_line(src.get(..open)?))
+}
+
+/// The index of the opening bracket that has not been closed by the end of the
+/// source code.
+fn enclosing_open(src: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0;
+    for (index, char) in src.char_indices().rev() {
+        if char == close {
+            depth += 1;
+        } else if char == open {
+            if depth == 0 {
+                return Some(index);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
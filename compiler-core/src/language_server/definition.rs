@@ -0,0 +1,100 @@
+use std::{collections::HashMap, sync::Arc};
+
+use smol_str::SmolStr;
+
+use crate::{
+    ast::{Function, ModuleConstant, SrcSpan, Statement},
+    build::{Located, Module, Target},
+    type_::{self, Type, TypeVar},
+};
+
+/// Where something is defined, possibly in a module of a dependency package.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    pub module: SmolStr,
+    pub package: SmolStr,
+    pub location: SrcSpan,
+}
+
+/// The definition of the type of the expression at the byte index, or of the
+/// return type of the function or the type of the constant defined there.
+///
+/// Only named types have a definition, so there is none for functions, tuples
+/// and type variables, nor for the types of the prelude.
+///
+pub fn type_definition(
+    module: &Module,
+    byte_index: u32,
+    modules: &HashMap<SmolStr, &type_::Module>,
+) -> Option<Definition> {
+    let type_ = match module.find_node(byte_index)? {
+        Located::Expression(expression) => expression.type_(),
+        Located::Statement(Statement::Function(Function { return_type, .. })) => {
+            return_type.clone()
+        }
+        Located::Statement(Statement::ModuleConstant(ModuleConstant { type_, .. })) => {
+            type_.clone()
+        }
+        Located::Statement(_) => return None,
+    };
+
+    match resolve_links(type_).as_ref() {
+        Type::App { module, name, .. } => {
+            let interface = modules.get(module)?;
+            let constructor = interface.types.get(name)?;
+            Some(Definition {
+                module: module.clone(),
+                package: interface.package.clone(),
+                location: constructor.origin,
+            })
+        }
+        Type::Fn { .. } | Type::Var { .. } | Type::Tuple { .. } => None,
+    }
+}
+
+fn resolve_links(type_: Arc<Type>) -> Arc<Type> {
+    if let Type::Var { type_: var } = type_.as_ref() {
+        if let TypeVar::Link { type_ } = &*var.borrow() {
+            return resolve_links(type_.clone());
+        }
+    }
+    type_
+}
+
+/// The location of the name of a function defined in the source code of an
+/// Erlang or JavaScript module, for finding the implementation of an
+/// external function.
+///
+pub fn native_function_location(code: &str, function: &str, target: Target) -> Option<SrcSpan> {
+    let mut line_start = 0;
+    for line in code.split('\n') {
+        let definition = match target {
+            // Erlang functions are defined by clauses at the start of a line
+            Target::Erlang => line
+                .strip_prefix(function)
+                .or_else(|| line.strip_prefix(&format!("'{function}'")))
+                .filter(|rest| rest.trim_start().starts_with('(')),
+            Target::JavaScript => javascript_definition(line.trim_start(), function),
+        };
+        if definition.is_some() {
+            let start = line_start + line.find(function).unwrap_or_default();
+            return Some(SrcSpan::new(start as u32, (start + function.len()) as u32));
+        }
+        line_start += line.len() + 1;
+    }
+    None
+}
+
+/// The rest of the line after the name if it is a JavaScript function, or a
+/// variable that could be holding a function, of the given name.
+fn javascript_definition<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let line = line.strip_prefix("async ").unwrap_or(line);
+    let line = ["function ", "function* ", "const ", "let ", "var "]
+        .iter()
+        .find_map(|keyword| line.strip_prefix(keyword))?;
+    line.trim_start()
+        .strip_prefix(name)
+        .filter(|rest| rest.trim_start().starts_with(['(', '=']))
+}
@@ -0,0 +1,232 @@
+use crate::{
+    ast::{Function, SrcSpan, Statement, TypedExpr},
+    build::Module,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldingRangeKind {
+    Region,
+    Imports,
+    Comment,
+}
+
+/// A region of the source code that an editor can collapse.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoldingRange {
+    pub location: SrcSpan,
+    pub kind: FoldingRangeKind,
+}
+
+/// Returns the regions of a module that span multiple lines and so can be
+/// folded: function bodies, case expressions and their clauses, blocks,
+/// lists and tuples, groups of imports and runs of documentation comments.
+///
+pub fn folding_ranges(module: &Module) -> Vec<FoldingRange> {
+    let mut folder = Folder {
+        code: &module.code,
+        ranges: vec![],
+    };
+
+    let mut imports: Option<SrcSpan> = None;
+    for statement in &module.ast.statements {
+        match statement {
+            Statement::Import(import) => {
+                let start = imports.map_or(import.location.start, |imports| imports.start);
+                imports = Some(SrcSpan::new(start, import.location.end));
+                continue;
+            }
+            Statement::Function(Function {
+                location,
+                end_position,
+                body,
+                ..
+            }) => {
+                folder.fold(SrcSpan::new(location.start, end_position + 1));
+                folder.body(body);
+            }
+            _ => (),
+        }
+        if let Some(imports) = imports.take() {
+            folder.fold_kind(imports, FoldingRangeKind::Imports);
+        }
+    }
+    if let Some(imports) = imports {
+        folder.fold_kind(imports, FoldingRangeKind::Imports);
+    }
+
+    for comments in [&module.extra.module_comments, &module.extra.doc_comments] {
+        for run in comment_runs(&module.code, comments) {
+            folder.fold_kind(run, FoldingRangeKind::Comment);
+        }
+    }
+
+    folder.ranges.sort_by_key(|range| range.location.start);
+    folder.ranges
+}
+
+/// Groups comments that are on consecutive lines.
+fn comment_runs(code: &str, comments: &[SrcSpan]) -> Vec<SrcSpan> {
+    let mut runs: Vec<SrcSpan> = vec![];
+    for comment in comments {
+        let consecutive = runs.last().is_some_and(|run| {
+            code.get(run.end as usize..comment.start as usize)
+                .is_some_and(|between| between.matches('\n').count() == 1)
+        });
+        match runs.last_mut() {
+            Some(run) if consecutive => run.end = comment.end,
+            _ => runs.push(*comment),
+        }
+    }
+    runs
+}
+
+struct Folder<'a> {
+    code: &'a str,
+    ranges: Vec<FoldingRange>,
+}
+
+impl Folder<'_> {
+    fn fold(&mut self, location: SrcSpan) {
+        self.fold_kind(location, FoldingRangeKind::Region)
+    }
+
+    /// Records the location if it spans multiple lines.
+    fn fold_kind(&mut self, location: SrcSpan, kind: FoldingRangeKind) {
+        let multiline = self
+            .code
+            .get(location.start as usize..location.end as usize)
+            .is_some_and(|code| code.contains('\n'));
+        if multiline {
+            self.ranges.push(FoldingRange { location, kind });
+        }
+    }
+
+    /// The body of a function or a case clause is folded with it, so only the
+    /// expressions within it are folded.
+    fn body(&mut self, body: &TypedExpr) {
+        match body {
+            TypedExpr::Block { expressions, .. } => {
+                for expression in expressions {
+                    self.expression(expression);
+                }
+            }
+            _ => self.expression(body),
+        }
+    }
+
+    fn expression(&mut self, expression: &TypedExpr) {
+        match expression {
+            TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Var { .. }
+            | TypedExpr::ModuleSelect { .. }
+            | TypedExpr::Todo { .. }
+            | TypedExpr::Panic { .. } => (),
+
+            TypedExpr::Block {
+                location,
+                expressions,
+            } => {
+                self.fold(*location);
+                for expression in expressions {
+                    self.expression(expression);
+                }
+            }
+
+            TypedExpr::Pipeline { expressions, .. } => {
+                for expression in expressions {
+                    self.expression(expression);
+                }
+            }
+
+            TypedExpr::Tuple {
+                location, elems, ..
+            } => {
+                self.fold(*location);
+                for elem in elems {
+                    self.expression(elem);
+                }
+            }
+
+            TypedExpr::Fn {
+                location,
+                is_capture,
+                body,
+                ..
+            } => {
+                if !is_capture {
+                    self.fold(*location);
+                }
+                self.body(body);
+            }
+
+            TypedExpr::List {
+                location,
+                elements,
+                tail,
+                ..
+            } => {
+                self.fold(*location);
+                for element in elements {
+                    self.expression(element);
+                }
+                if let Some(tail) = tail {
+                    self.expression(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expression(fun);
+                for arg in args {
+                    self.expression(&arg.value);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+
+            TypedExpr::Assignment { value, .. } => self.expression(value),
+
+            TypedExpr::Case {
+                location,
+                subjects,
+                clauses,
+                ..
+            } => {
+                self.fold(*location);
+                for subject in subjects {
+                    self.expression(subject);
+                }
+                for clause in clauses {
+                    self.fold(clause.location());
+                    self.body(&clause.then);
+                }
+            }
+
+            TypedExpr::RecordAccess { record, .. } => self.expression(record),
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expression(tuple),
+
+            TypedExpr::BitString { segments, .. } => {
+                for segment in segments {
+                    self.expression(&segment.value);
+                }
+            }
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expression(spread);
+                for arg in args {
+                    self.expression(&arg.value);
+                }
+            }
+
+            TypedExpr::NegateBool { value, .. } | TypedExpr::NegateInt { value, .. } => {
+                self.expression(value)
+            }
+        }
+    }
+}
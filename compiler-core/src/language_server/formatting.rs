@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use itertools::Itertools;
+use smol_str::SmolStr;
+
+use super::code_action::Edit;
+use crate::{ast::SrcSpan, format, parse::parse_module, Result};
+
+/// Formats only the top level statements of a module that overlap the
+/// location, returning the edit that replaces them with their formatted code.
+///
+/// The module is split into regions, each running from the line of one
+/// statement to the line of the next, and each region of the module is
+/// replaced by the same region of the formatted module. Comments belonging to
+/// a statement come before it, so they are in the region of the statement
+/// before.
+///
+/// The formatter moves some comments, such as module comments after the
+/// first statement, to a different region. When that happens the range cannot
+/// be formatted on its own, so no edit is returned.
+///
+pub fn format_range(src: &SmolStr, path: &Path, location: SrcSpan) -> Result<Vec<Edit>> {
+    let formatted = pretty(src, path)?;
+    let original_regions = regions(src);
+    let formatted_regions = regions(&formatted);
+
+    if original_regions.len() != formatted_regions.len() {
+        return Ok(vec![]);
+    }
+
+    // A region overlaps the location if they share a character, or if the
+    // location is empty and within the region.
+    let location_end = location.end.max(location.start + 1);
+    let overlapping = original_regions
+        .iter()
+        .zip(formatted_regions.iter())
+        .filter(|(region, _)| region.start < location_end && location.start < region.end)
+        .collect_vec();
+    let (first, last) = match (overlapping.first(), overlapping.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Ok(vec![]),
+    };
+
+    let replaced = SrcSpan::new(first.0.start, last.0.end);
+    let new_text = formatted
+        .get(first.1.start as usize..last.1.end as usize)
+        .unwrap_or_default()
+        .to_string();
+
+    // Formatting the module with the edit made must give the same code as
+    // formatting the module did, otherwise a comment was moved.
+    let edited = format!(
+        "{}{}{}",
+        src.get(..replaced.start as usize).unwrap_or_default(),
+        new_text,
+        src.get(replaced.end as usize..).unwrap_or_default(),
+    );
+    if pretty(&edited.into(), path)? != formatted {
+        return Ok(vec![]);
+    }
+
+    if src.get(replaced.start as usize..replaced.end as usize) == Some(new_text.as_str()) {
+        return Ok(vec![]);
+    }
+    Ok(vec![Edit {
+        location: replaced,
+        new_text,
+    }])
+}
+
+fn pretty(src: &SmolStr, path: &Path) -> Result<String> {
+    let mut formatted = String::new();
+    format::pretty(&mut formatted, src, path)?;
+    Ok(formatted)
+}
+
+/// The regions of a module, running from the start of the line of each
+/// statement to that of the next, along with the region before the first
+/// statement. Lines are used as the locations of some statements, such as
+/// imports, do not include their keyword.
+fn regions(src: &str) -> Vec<SrcSpan> {
+    let starts = match parse_module(src) {
+        Ok((module, _)) => module
+            .statements
+            .iter()
+            .flat_map(|group| group.statements_ref())
+            .map(|statement| line_start(src, statement.location().start))
+            .collect_vec(),
+        Err(_) => vec![],
+    };
+    std::iter::once(0)
+        .chain(starts)
+        .chain(std::iter::once(src.len() as u32))
+        .tuple_windows()
+        .map(|(start, end)| SrcSpan::new(start, end))
+        .collect()
+}
+
+fn line_start(src: &str, byte_index: u32) -> u32 {
+    src.get(..byte_index as usize)
+        .and_then(|before| before.rfind('\n'))
+        .map_or(0, |newline| newline as u32 + 1)
+}
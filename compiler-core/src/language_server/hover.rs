@@ -0,0 +1,334 @@
+use std::{collections::HashMap, sync::Arc};
+
+use itertools::Itertools;
+use smol_str::SmolStr;
+
+use super::{
+    completion::{self, Documentation},
+    reference::{self, ReferenceKind, Symbol},
+};
+use crate::{
+    ast::{
+        CustomType, ExternalFunction, ExternalType, Function, Import, ModuleConstant, SrcSpan,
+        Statement, TypeAlias, TypedStatement,
+    },
+    build::{Located, Module},
+    format::Formatter,
+    pretty,
+    type_::{self, pretty::Printer, Type, ValueConstructorVariant},
+};
+
+/// Information about the code at a position in a module.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hover {
+    pub location: SrcSpan,
+    /// Gleam code describing the thing at the position, such as its type or
+    /// its definition.
+    pub code: String,
+    pub documentation: Option<SmolStr>,
+}
+
+/// Returns the information to show for the thing at the byte index of a
+/// module, being:
+///
+/// - The definition and documentation of functions, constants, record
+///   constructors and types, wherever they are referred to.
+/// - The type of local variables, both where they are used and where they are
+///   defined by a pattern or as a function argument.
+/// - The documentation of the module imported by an import statement.
+/// - The type of any other expression.
+///
+/// `modules` holds the interfaces of the modules that can be imported and
+/// `documentation` returns the doc comments of one of them, if they are known.
+/// It is only called for the module that the thing being hovered over belongs
+/// to.
+///
+pub fn hover(
+    module: &Module,
+    byte_index: u32,
+    modules: &HashMap<SmolStr, &type_::Module>,
+    documentation: &impl Fn(&str) -> Option<Documentation>,
+) -> Option<Hover> {
+    if let Some(reference) = reference::reference_at(module, byte_index) {
+        let location = reference.location;
+        let symbol_hover = match &reference.symbol {
+            Symbol::ModuleValue {
+                module: value_module,
+                name,
+            } if value_module == &module.name => module_value(module, name),
+            Symbol::ModuleValue {
+                module: value_module,
+                name,
+            } => imported_value(value_module, name, modules, documentation),
+            Symbol::ModuleType {
+                module: type_module,
+                name,
+            } if type_module == &module.name => module_type(module, name),
+            Symbol::ModuleType {
+                module: type_module,
+                name,
+            } => imported_type(type_module, name, modules, documentation),
+            Symbol::LocalVariable { definition, .. }
+                if reference.kind == ReferenceKind::Definition =>
+            {
+                local_variable(module, *definition)
+            }
+            Symbol::LocalVariable { .. } | Symbol::RecordLabel { .. } => None,
+        };
+        if let Some((code, documentation)) = symbol_hover {
+            return Some(Hover {
+                location,
+                code,
+                documentation,
+            });
+        }
+    }
+
+    import(module, byte_index, documentation).or_else(|| expression(module, byte_index))
+}
+
+fn expression(module: &Module, byte_index: u32) -> Option<Hover> {
+    match module.find_node(byte_index)? {
+        Located::Expression(expression) => Some(Hover {
+            location: expression.location(),
+            code: Printer::new().pretty_print(&expression.type_(), 0),
+            documentation: None,
+        }),
+        Located::Statement(_) => None,
+    }
+}
+
+/// The name of the module of an import statement, showing the documentation
+/// of the module.
+fn import(
+    module: &Module,
+    byte_index: u32,
+    documentation: &impl Fn(&str) -> Option<Documentation>,
+) -> Option<Hover> {
+    module
+        .ast
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            // The location of an import starts at the name of the module
+            Statement::Import(Import {
+                module: imported,
+                location,
+                ..
+            }) => {
+                let name = SrcSpan::new(location.start, location.start + imported.len() as u32);
+                (name.start <= byte_index && byte_index <= name.end).then(|| Hover {
+                    location: name,
+                    code: format!("import {imported}"),
+                    documentation: documentation(imported)
+                        .and_then(|documentation| documentation.module().cloned()),
+                })
+            }
+            _ => None,
+        })
+}
+
+/// The type of a local variable at its definition.
+fn local_variable(module: &Module, definition: SrcSpan) -> Option<(String, Option<SmolStr>)> {
+    let type_ = completion::local_variable_type(module, definition)?;
+    Some((Printer::new().pretty_print(&type_, 0), None))
+}
+
+fn module_value(module: &Module, name: &str) -> Option<(String, Option<SmolStr>)> {
+    module
+        .ast
+        .statements
+        .iter()
+        .find_map(|statement| value_definition(statement, name))
+}
+
+fn value_definition(statement: &TypedStatement, name: &str) -> Option<(String, Option<SmolStr>)> {
+    let mut formatter = Formatter::new();
+    let (definition, doc) = match statement {
+        Statement::Function(Function {
+            name: function,
+            public,
+            arguments,
+            return_type,
+            doc,
+            ..
+        }) if function == name => (
+            formatter.docs_fn_signature(*public, function, arguments, return_type.clone()),
+            doc,
+        ),
+
+        Statement::ExternalFunction(ExternalFunction {
+            name: function,
+            public,
+            arguments,
+            return_,
+            doc,
+            ..
+        }) if function == name => (
+            formatter.external_fn_signature(*public, function, arguments, return_),
+            doc,
+        ),
+
+        Statement::ModuleConstant(ModuleConstant {
+            name: constant,
+            public,
+            value,
+            doc,
+            ..
+        }) if constant == name => (formatter.docs_const_expr(*public, constant, value), doc),
+
+        Statement::CustomType(CustomType { constructors, .. }) => {
+            let constructor = constructors
+                .iter()
+                .find(|constructor| constructor.name == name)?;
+            (
+                formatter.record_constructor(constructor),
+                &constructor.documentation,
+            )
+        }
+
+        _ => return None,
+    };
+    Some((print(definition), doc.clone()))
+}
+
+fn module_type(module: &Module, name: &str) -> Option<(String, Option<SmolStr>)> {
+    let mut formatter = Formatter::new();
+    let (definition, doc) = module
+        .ast
+        .statements
+        .iter()
+        .find_map(|statement| match statement {
+            Statement::CustomType(CustomType {
+                name: type_,
+                public,
+                opaque,
+                parameters,
+                constructors,
+                location,
+                doc,
+                ..
+            }) if type_ == name => Some((
+                formatter.custom_type(*public, *opaque, type_, parameters, constructors, location),
+                doc,
+            )),
+
+            Statement::TypeAlias(TypeAlias {
+                alias,
+                public,
+                parameters,
+                type_ast,
+                doc,
+                ..
+            }) if alias == name => Some((
+                formatter.type_alias(*public, alias, parameters, type_ast),
+                doc,
+            )),
+
+            Statement::ExternalType(ExternalType {
+                name: type_,
+                public,
+                arguments,
+                doc,
+                ..
+            }) if type_ == name => Some((formatter.external_type(*public, type_, arguments), doc)),
+
+            _ => None,
+        })?;
+    Some((print(definition), doc.clone()))
+}
+
+fn imported_value(
+    module: &str,
+    name: &str,
+    modules: &HashMap<SmolStr, &type_::Module>,
+    documentation: &impl Fn(&str) -> Option<Documentation>,
+) -> Option<(String, Option<SmolStr>)> {
+    let value = modules.get(module)?.values.get(name)?;
+    let definition = match &value.variant {
+        ValueConstructorVariant::ModuleConstant { literal, .. } => {
+            print(Formatter::new().docs_const_expr(true, name, literal))
+        }
+        ValueConstructorVariant::ModuleFn { .. } => {
+            format!("pub fn {}", signature(name, &value.type_))
+        }
+        ValueConstructorVariant::Record { .. } => signature(name, &value.type_),
+        ValueConstructorVariant::LocalVariable { .. } => return None,
+    };
+    let doc = documentation(module).and_then(|documentation| documentation.value(name).cloned());
+    Some((definition, doc))
+}
+
+/// The definition of a type from another module. Only the types of the
+/// fields of its record constructors are known, so they are shown without
+/// labels.
+fn imported_type(
+    module: &str,
+    name: &str,
+    modules: &HashMap<SmolStr, &type_::Module>,
+    documentation: &impl Fn(&str) -> Option<Documentation>,
+) -> Option<(String, Option<SmolStr>)> {
+    let interface = modules.get(module)?;
+    let type_ = interface.types.get(name)?;
+
+    let mut printer = Printer::new();
+    let mut definition = format!("pub type {name}");
+    if !type_.parameters.is_empty() {
+        let parameters = type_
+            .parameters
+            .iter()
+            .map(|parameter| printer.pretty_print(parameter, 0))
+            .join(", ");
+        definition.push_str(&format!("({parameters})"));
+    }
+
+    let constructors = interface
+        .types_constructors
+        .get(name)
+        .into_iter()
+        .flatten()
+        .filter_map(|constructor| {
+            let value = interface.values.get(constructor)?;
+            Some(signature_with(&mut printer, constructor, &value.type_))
+        })
+        .collect_vec();
+    if !constructors.is_empty() {
+        definition.push_str(" {\n");
+        for constructor in constructors {
+            definition.push_str(&format!("  {constructor}\n"));
+        }
+        definition.push('}');
+    }
+
+    let doc = documentation(module).and_then(|documentation| documentation.type_(name).cloned());
+    Some((definition, doc))
+}
+
+/// The name followed by the arguments of a function type, and its return
+/// type unless it is a record constructor.
+fn signature(name: &str, type_: &Arc<Type>) -> String {
+    signature_with(&mut Printer::new(), name, type_)
+}
+
+fn signature_with(printer: &mut Printer, name: &str, type_: &Arc<Type>) -> String {
+    match type_::collapse_links(type_.clone()).as_ref() {
+        Type::Fn { args, retrn } => {
+            let arguments = args
+                .iter()
+                .map(|argument| printer.pretty_print(argument, 0))
+                .join(", ");
+            if name.starts_with(char::is_uppercase) {
+                format!("{name}({arguments})")
+            } else {
+                let return_type = printer.pretty_print(retrn, 0);
+                format!("{name}({arguments}) -> {return_type}")
+            }
+        }
+        _ => name.into(),
+    }
+}
+
+fn print(document: pretty::Document<'_>) -> String {
+    document.to_pretty_string(80)
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+
+use crate::{
+    ast::{Arg, Function, Pattern, Statement, TypedExpr, PIPE_VARIABLE},
+    build::Module,
+    type_::{pretty::Printer, Type},
+};
+
+/// A type inferred by the compiler, to be shown in the source code where it
+/// has not been written by the programmer.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHint {
+    /// The byte index the hint is shown at.
+    pub position: u32,
+    pub label: String,
+    /// Whether the hint is to be separated from the code before it.
+    pub padding_left: bool,
+}
+
+/// Returns hints for the types of `let` bindings, function parameters and
+/// function return types which have no annotation, and for the type of each
+/// step of a pipeline written over multiple lines.
+///
+pub fn inlay_hints(module: &Module) -> Vec<InlayHint> {
+    let mut hints = vec![];
+    for statement in &module.ast.statements {
+        if let Statement::Function(function) = statement {
+            // Each function has its own printer so that the type variables
+            // in its hints are named consistently.
+            let mut collector = HintCollector {
+                code: &module.code,
+                printer: Printer::new(),
+                hints: vec![],
+            };
+            collector.function(function);
+            hints.append(&mut collector.hints);
+        }
+    }
+    hints.sort_by_key(|hint| hint.position);
+    hints
+}
+
+struct HintCollector<'a> {
+    code: &'a str,
+    printer: Printer,
+    hints: Vec<InlayHint>,
+}
+
+impl HintCollector<'_> {
+    fn hint(&mut self, position: u32, prefix: &str, type_: &Arc<Type>, padding_left: bool) {
+        let label = format!("{prefix}{}", self.printer.pretty_print(type_, 0));
+        self.hints.push(InlayHint {
+            position,
+            label,
+            padding_left,
+        });
+    }
+
+    fn function(&mut self, function: &Function<Arc<Type>, TypedExpr>) {
+        self.arguments(&function.arguments);
+        if function.return_annotation.is_none() {
+            self.hint(function.location.end, "-> ", &function.return_type, true);
+        }
+        self.expression(&function.body);
+    }
+
+    fn arguments(&mut self, arguments: &[Arg<Arc<Type>>]) {
+        for argument in arguments {
+            // Names starting with an underscore are discarded or generated
+            let is_used = argument
+                .get_variable_name()
+                .is_some_and(|name| !name.starts_with('_'));
+            if argument.annotation.is_none() && is_used {
+                self.hint(argument.location.end, ": ", &argument.type_, false);
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &TypedExpr) {
+        match expression {
+            TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Todo { .. }
+            | TypedExpr::Panic { .. }
+            | TypedExpr::Var { .. }
+            | TypedExpr::ModuleSelect { .. } => (),
+
+            TypedExpr::Block { expressions, .. } => {
+                for expression in expressions {
+                    self.expression(expression);
+                }
+            }
+
+            TypedExpr::Pipeline {
+                location,
+                expressions,
+            } => {
+                let multiline = self
+                    .code
+                    .get(location.start as usize..location.end as usize)
+                    .is_some_and(|code| code.contains('\n'));
+                for expression in expressions {
+                    match expression {
+                        TypedExpr::Assignment { value, typ, .. } => {
+                            self.expression(value);
+                            if multiline {
+                                self.hint(value.location().end, "", typ, true);
+                            }
+                        }
+                        _ => self.expression(expression),
+                    }
+                }
+            }
+
+            TypedExpr::Assignment {
+                value,
+                pattern,
+                typ,
+                ..
+            } => {
+                self.expression(value);
+                if let Pattern::Var { location, name } = pattern {
+                    let unannotated = self
+                        .code
+                        .get(location.end as usize..value.location().start as usize)
+                        .is_some_and(|between| !between.contains(':'));
+                    if unannotated && name != PIPE_VARIABLE && !name.starts_with('_') {
+                        self.hint(location.end, ": ", typ, false);
+                    }
+                }
+            }
+
+            TypedExpr::Fn {
+                location,
+                is_capture: false,
+                args,
+                body,
+                return_annotation,
+                typ,
+            } => {
+                self.arguments(args);
+                // The callback of a `use` expression is not written as a
+                // function, so there is nowhere to show its return type.
+                let is_written = self
+                    .code
+                    .get(location.start as usize..)
+                    .is_some_and(|code| code.starts_with("fn"));
+                let arguments_end = closing_paren(self.code, location.start);
+                if let (None, true, Some(end), Some((_, return_type))) =
+                    (return_annotation, is_written, arguments_end, typ.fn_types())
+                {
+                    self.hint(end, "-> ", &return_type, true);
+                }
+                self.expression(body);
+            }
+
+            TypedExpr::Fn { body, .. } => self.expression(body),
+
+            TypedExpr::List { elements, tail, .. } => {
+                for element in elements {
+                    self.expression(element);
+                }
+                if let Some(tail) = tail {
+                    self.expression(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expression(fun);
+                for arg in args {
+                    self.expression(&arg.value);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+
+            TypedExpr::Case {
+                subjects, clauses, ..
+            } => {
+                for subject in subjects {
+                    self.expression(subject);
+                }
+                for clause in clauses {
+                    self.expression(&clause.then);
+                }
+            }
+
+            TypedExpr::RecordAccess { record, .. } => self.expression(record),
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expression(tuple),
+
+            TypedExpr::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.expression(elem);
+                }
+            }
+
+            TypedExpr::BitString { segments, .. } => {
+                for segment in segments {
+                    self.expression(&segment.value);
+                }
+            }
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expression(spread);
+                for arg in args {
+                    self.expression(&arg.value);
+                }
+            }
+
+            TypedExpr::NegateBool { value, .. } | TypedExpr::NegateInt { value, .. } => {
+                self.expression(value)
+            }
+        }
+    }
+}
+
+/// The index just after the parenthesis closing the first one opened at or
+/// after the start.
+fn closing_paren(code: &str, start: u32) -> Option<u32> {
+    let code = code.get(start as usize..)?;
+    let open = code.find('(')?;
+    let mut depth = 0;
+    for (index, char) in code.char_indices().skip_while(|(index, _)| *index < open) {
+        match char {
+            '(' => depth += 1,
+            ')' if depth == 1 => return Some(start + index as u32 + 1),
+            ')' => depth -= 1,
+            _ => (),
+        }
+    }
+    None
+}
@@ -0,0 +1,991 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use smol_str::SmolStr;
+
+use crate::{
+    ast::{
+        Arg, ArgNames, BitStringSegment, BitStringSegmentOption, ClauseGuard, Constant, CustomType,
+        ExternalFunction, ExternalType, Function, Import, ModuleConstant, Pattern, SrcSpan,
+        Statement, TypeAlias, TypeAst, TypedClause, TypedClauseGuard, TypedConstant, TypedExpr,
+        TypedPattern, TypedStatement,
+    },
+    build::Module,
+    type_::{ModuleValueConstructor, PatternConstructor, Type, TypeVar, ValueConstructorVariant},
+};
+
+/// A named thing that can be defined in Gleam code and then referred to
+/// elsewhere, possibly in other modules.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Symbol {
+    /// A function, external function, constant or record constructor defined
+    /// at the top level of a module.
+    ModuleValue { module: SmolStr, name: SmolStr },
+
+    /// A custom type, external type or type alias.
+    ModuleType { module: SmolStr, name: SmolStr },
+
+    /// A labelled field of the record constructors of a custom type.
+    RecordLabel {
+        module: SmolStr,
+        type_name: SmolStr,
+        label: SmolStr,
+    },
+
+    /// A variable or function argument defined within a function. As local
+    /// variables can be shadowed they are identified by where they are
+    /// defined rather than by name.
+    LocalVariable {
+        module: SmolStr,
+        definition: SrcSpan,
+    },
+}
+
+impl Symbol {
+    /// The name of the module that defines this symbol.
+    pub fn module(&self) -> &str {
+        match self {
+            Symbol::ModuleValue { module, .. }
+            | Symbol::ModuleType { module, .. }
+            | Symbol::RecordLabel { module, .. }
+            | Symbol::LocalVariable { module, .. } => module,
+        }
+    }
+
+    /// A description of the kind of symbol, for use in error messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Symbol::ModuleValue { name, .. } if name.starts_with(char::is_uppercase) => {
+                "record constructor"
+            }
+            Symbol::ModuleValue { .. } => "value",
+            Symbol::ModuleType { .. } => "type",
+            Symbol::RecordLabel { .. } => "record label",
+            Symbol::LocalVariable { .. } => "variable",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// The place where the symbol is defined.
+    Definition,
+    /// An unqualified import of the symbol, e.g. `import wibble.{wobble}`.
+    Import,
+    /// Any other use of the symbol.
+    Usage,
+}
+
+/// An occurrence of a symbol in the source code of a module. The location
+/// always covers the name used to refer to the symbol, which may be an alias
+/// given to it in an unqualified import rather than its own name.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub symbol: Symbol,
+    pub location: SrcSpan,
+    pub kind: ReferenceKind,
+}
+
+/// Returns every occurrence of a symbol in the module, in the order they
+/// appear in the source code.
+///
+pub fn module_references(module: &Module) -> Vec<Reference> {
+    let mut collector = ReferenceCollector::new(module);
+    for statement in &module.ast.statements {
+        collector.statement(statement);
+    }
+    collector.finish()
+}
+
+/// Returns the symbol occurrence found at the given byte index, if any.
+///
+pub fn reference_at(module: &Module, byte_index: u32) -> Option<Reference> {
+    // Only the statement containing the byte index needs to be searched.
+    let statement = statement_at(module, byte_index)?;
+    statement_references(module, statement)
+        .into_iter()
+        .find(|reference| reference_contains(reference, byte_index))
+}
+
+/// Returns the locations of all the occurrences of the symbol in the module.
+///
+pub fn find_references(module: &Module, symbol: &Symbol) -> Vec<Reference> {
+    let references = match symbol {
+        // Local variables can only be used within the statement that defines
+        // them.
+        Symbol::LocalVariable {
+            module: defined_in,
+            definition,
+        } => {
+            if defined_in != &module.name {
+                return vec![];
+            }
+            statement_at(module, definition.start)
+                .map(|statement| statement_references(module, statement))
+                .unwrap_or_default()
+        }
+        Symbol::ModuleValue { .. } | Symbol::ModuleType { .. } | Symbol::RecordLabel { .. } => {
+            module_references(module)
+        }
+    };
+    references
+        .into_iter()
+        .filter(|reference| &reference.symbol == symbol)
+        .collect()
+}
+
+fn statement_references(module: &Module, statement: &TypedStatement) -> Vec<Reference> {
+    let mut collector = ReferenceCollector::new(module);
+    collector.statement(statement);
+    collector.finish()
+}
+
+/// Returns the top level statement that the byte index falls within, being
+/// the last one to start before it.
+fn statement_at(module: &Module, byte_index: u32) -> Option<&TypedStatement> {
+    module
+        .ast
+        .statements
+        .iter()
+        .filter(|statement| statement.location().start <= byte_index)
+        .max_by_key(|statement| statement.location().start)
+}
+
+// The cursor is considered to be on a name when it is immediately after the
+// last character of it too, as editors place the cursor there after typing.
+fn reference_contains(reference: &Reference, byte_index: u32) -> bool {
+    let SrcSpan { start, end } = reference.location;
+    start <= byte_index && byte_index <= end
+}
+
+/// The span of a name that starts at the given position.
+fn name_from(start: u32, name: &str) -> SrcSpan {
+    SrcSpan::new(start, start + name.len() as u32)
+}
+
+/// The span of a name that ends at the given position, as is the case for
+/// the label of a field access or of a qualified value.
+fn name_until(end: u32, name: &str) -> SrcSpan {
+    SrcSpan::new(end.saturating_sub(name.len() as u32), end)
+}
+
+/// The span of the name of a possibly qualified constructor, e.g. `Wobble`
+/// in `wibble.Wobble(1)`, given where the constructor starts.
+fn qualified_name(start: u32, module: &Option<SmolStr>, name: &str) -> SrcSpan {
+    match module {
+        Some(module) => name_from(start + module.len() as u32 + 1, name),
+        None => name_from(start, name),
+    }
+}
+
+type Scope = HashMap<SmolStr, SrcSpan>;
+
+struct ReferenceCollector<'a> {
+    module: &'a Module,
+    /// Imported module aliases to the full names of the modules.
+    module_aliases: HashMap<SmolStr, SmolStr>,
+    /// Unqualified imported names to the module and name of the imported item.
+    unqualified: HashMap<SmolStr, (SmolStr, SmolStr)>,
+    module_types: HashSet<SmolStr>,
+    /// When collecting the references of an alternative pattern of a case
+    /// clause these are the variables defined by the first pattern, which the
+    /// variables of the alternative pattern refer to.
+    alternative_of: Option<Scope>,
+    references: Vec<Reference>,
+}
+
+impl<'a> ReferenceCollector<'a> {
+    fn new(module: &'a Module) -> Self {
+        let mut module_aliases = HashMap::new();
+        let mut unqualified = HashMap::new();
+        let mut module_types = HashSet::new();
+
+        for statement in &module.ast.statements {
+            match statement {
+                Statement::Import(import) => {
+                    let _ = module_aliases.insert(import.variable_name(), import.module.clone());
+                    for item in &import.unqualified {
+                        let _ = unqualified.insert(
+                            SmolStr::from(item.variable_name()),
+                            (import.module.clone(), item.name.clone()),
+                        );
+                    }
+                }
+                Statement::CustomType(CustomType { name, .. })
+                | Statement::TypeAlias(TypeAlias { alias: name, .. })
+                | Statement::ExternalType(ExternalType { name, .. }) => {
+                    let _ = module_types.insert(name.clone());
+                }
+                Statement::Function(_)
+                | Statement::ExternalFunction(_)
+                | Statement::ModuleConstant(_) => (),
+            }
+        }
+
+        Self {
+            module,
+            module_aliases,
+            unqualified,
+            module_types,
+            alternative_of: None,
+            references: vec![],
+        }
+    }
+
+    fn finish(self) -> Vec<Reference> {
+        let mut references = self.references;
+        references.sort_by_key(|reference| reference.location.start);
+        references
+    }
+
+    fn module_name(&self) -> SmolStr {
+        self.module.name.clone()
+    }
+
+    fn push(&mut self, symbol: Symbol, location: SrcSpan, kind: ReferenceKind) {
+        self.references.push(Reference {
+            symbol,
+            location,
+            kind,
+        });
+    }
+
+    fn module_value(&self, name: &SmolStr) -> Symbol {
+        Symbol::ModuleValue {
+            module: self.module_name(),
+            name: name.clone(),
+        }
+    }
+
+    fn module_type(&self, name: &SmolStr) -> Symbol {
+        Symbol::ModuleType {
+            module: self.module_name(),
+            name: name.clone(),
+        }
+    }
+
+    /// The symbol a value refers to, as determined by the type checker. The
+    /// name is the one used to refer to it, which may be an alias given in an
+    /// unqualified import.
+    fn resolve_value(&self, variant: &ValueConstructorVariant, name: &SmolStr) -> Option<Symbol> {
+        match variant {
+            ValueConstructorVariant::LocalVariable { location } => {
+                // Variables starting with an underscore are generated by the
+                // compiler
+                if name.starts_with('_') {
+                    return None;
+                }
+                Some(Symbol::LocalVariable {
+                    module: self.module_name(),
+                    definition: *location,
+                })
+            }
+
+            ValueConstructorVariant::ModuleFn { module, name, .. }
+            | ValueConstructorVariant::Record { module, name, .. } => Some(Symbol::ModuleValue {
+                module: module.clone(),
+                name: name.clone(),
+            }),
+
+            // Constants don't record their own name so an imported one is
+            // found by the name it was imported with.
+            ValueConstructorVariant::ModuleConstant { module, .. } => {
+                let name = if module == &self.module.name {
+                    name.clone()
+                } else {
+                    self.unqualified.get(name)?.1.clone()
+                };
+                Some(Symbol::ModuleValue {
+                    module: module.clone(),
+                    name,
+                })
+            }
+        }
+    }
+
+    fn resolve_type(&self, module_alias: Option<&SmolStr>, name: &SmolStr) -> Option<Symbol> {
+        let (module, name) = match module_alias {
+            Some(alias) => (self.module_aliases.get(alias)?.clone(), name.clone()),
+            None if self.module_types.contains(name) => (self.module_name(), name.clone()),
+            None => self.unqualified.get(name)?.clone(),
+        };
+        Some(Symbol::ModuleType { module, name })
+    }
+
+    fn statement(&mut self, statement: &TypedStatement) {
+        match statement {
+            Statement::Function(Function {
+                name,
+                name_location,
+                arguments,
+                body,
+                return_annotation,
+                ..
+            }) => {
+                self.push(
+                    self.module_value(name),
+                    *name_location,
+                    ReferenceKind::Definition,
+                );
+                let mut scope = Scope::new();
+                for argument in arguments {
+                    self.argument(argument, &mut scope);
+                }
+                if let Some(annotation) = return_annotation {
+                    self.type_ast(annotation);
+                }
+                self.expression(body, &mut scope);
+            }
+
+            Statement::ExternalFunction(ExternalFunction {
+                name,
+                name_location,
+                arguments,
+                return_,
+                ..
+            }) => {
+                self.push(
+                    self.module_value(name),
+                    *name_location,
+                    ReferenceKind::Definition,
+                );
+                for argument in arguments {
+                    self.type_ast(&argument.annotation);
+                }
+                self.type_ast(return_);
+            }
+
+            Statement::TypeAlias(TypeAlias {
+                alias,
+                name_location,
+                type_ast,
+                ..
+            }) => {
+                self.push(
+                    self.module_type(alias),
+                    *name_location,
+                    ReferenceKind::Definition,
+                );
+                self.type_ast(type_ast);
+            }
+
+            Statement::CustomType(CustomType {
+                name,
+                name_location,
+                constructors,
+                ..
+            }) => {
+                self.push(
+                    self.module_type(name),
+                    *name_location,
+                    ReferenceKind::Definition,
+                );
+                for constructor in constructors {
+                    self.push(
+                        self.module_value(&constructor.name),
+                        name_from(constructor.location.start, &constructor.name),
+                        ReferenceKind::Definition,
+                    );
+                    for argument in &constructor.arguments {
+                        if let Some(label) = &argument.label {
+                            let symbol = Symbol::RecordLabel {
+                                module: self.module_name(),
+                                type_name: name.clone(),
+                                label: label.clone(),
+                            };
+                            let location = name_from(argument.location.start, label);
+                            self.push(symbol, location, ReferenceKind::Definition);
+                        }
+                        self.type_ast(&argument.ast);
+                    }
+                }
+            }
+
+            Statement::ExternalType(ExternalType {
+                name,
+                name_location,
+                ..
+            }) => {
+                self.push(
+                    self.module_type(name),
+                    *name_location,
+                    ReferenceKind::Definition,
+                );
+            }
+
+            Statement::Import(Import {
+                module,
+                unqualified,
+                ..
+            }) => {
+                for item in unqualified {
+                    let location = name_from(item.location.start, &item.name);
+                    let is_upname = item.name.starts_with(|c: char| c.is_uppercase());
+                    if item.is_value() {
+                        let symbol = Symbol::ModuleValue {
+                            module: module.clone(),
+                            name: item.name.clone(),
+                        };
+                        self.push(symbol, location, ReferenceKind::Import);
+                    }
+                    // An unqualified import of a capitalised name can refer to
+                    // both a type and a record constructor.
+                    if !item.is_value() || is_upname {
+                        let symbol = Symbol::ModuleType {
+                            module: module.clone(),
+                            name: item.name.clone(),
+                        };
+                        self.push(symbol, location, ReferenceKind::Import);
+                    }
+                }
+            }
+
+            Statement::ModuleConstant(ModuleConstant {
+                name,
+                name_location,
+                annotation,
+                value,
+                ..
+            }) => {
+                self.push(
+                    self.module_value(name),
+                    *name_location,
+                    ReferenceKind::Definition,
+                );
+                if let Some(annotation) = annotation {
+                    self.type_ast(annotation);
+                }
+                self.constant(value, &Scope::new());
+            }
+        }
+    }
+
+    fn argument(&mut self, argument: &Arg<Arc<Type>>, scope: &mut Scope) {
+        match &argument.names {
+            ArgNames::Named { name } => {
+                let location = name_from(argument.location.start, name);
+                self.local_definition(name, argument.location, location, scope);
+            }
+            ArgNames::NamedLabelled {
+                name,
+                name_location,
+                ..
+            } => self.local_definition(name, argument.location, *name_location, scope),
+            ArgNames::Discard { .. } | ArgNames::LabelledDiscard { .. } => (),
+        }
+        if let Some(annotation) = &argument.annotation {
+            self.type_ast(annotation);
+        }
+    }
+
+    fn local_definition(
+        &mut self,
+        name: &SmolStr,
+        definition: SrcSpan,
+        location: SrcSpan,
+        scope: &mut Scope,
+    ) {
+        // Variables starting with an underscore are generated by the compiler
+        if name.starts_with('_') {
+            return;
+        }
+        // Variables in alternative patterns are the ones defined by the first
+        // pattern of the clause.
+        let first_definition = self
+            .alternative_of
+            .as_ref()
+            .and_then(|initial| initial.get(name))
+            .copied();
+        if let Some(definition) = first_definition {
+            let symbol = Symbol::LocalVariable {
+                module: self.module_name(),
+                definition,
+            };
+            return self.push(symbol, location, ReferenceKind::Usage);
+        }
+        let _ = scope.insert(name.clone(), definition);
+        let symbol = Symbol::LocalVariable {
+            module: self.module_name(),
+            definition,
+        };
+        self.push(symbol, location, ReferenceKind::Definition);
+    }
+
+    fn local_usage(&mut self, name: &SmolStr, location: SrcSpan, scope: &Scope) {
+        if let Some(definition) = scope.get(name) {
+            let symbol = Symbol::LocalVariable {
+                module: self.module_name(),
+                definition: *definition,
+            };
+            self.push(symbol, location, ReferenceKind::Usage);
+        }
+    }
+
+    fn type_ast(&mut self, ast: &TypeAst) {
+        match ast {
+            TypeAst::Constructor {
+                location,
+                module,
+                name,
+                arguments,
+            } => {
+                if let Some(symbol) = self.resolve_type(module.as_ref(), name) {
+                    let location = qualified_name(location.start, module, name);
+                    self.push(symbol, location, ReferenceKind::Usage);
+                }
+                for argument in arguments {
+                    self.type_ast(argument);
+                }
+            }
+
+            TypeAst::Fn {
+                arguments, return_, ..
+            } => {
+                for argument in arguments {
+                    self.type_ast(argument);
+                }
+                self.type_ast(return_);
+            }
+
+            TypeAst::Tuple { elems, .. } => {
+                for element in elems {
+                    self.type_ast(element);
+                }
+            }
+
+            TypeAst::Var { .. } | TypeAst::Hole { .. } => (),
+        }
+    }
+
+    fn expression(&mut self, expression: &TypedExpr, scope: &mut Scope) {
+        match expression {
+            TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Todo { .. }
+            | TypedExpr::Panic { .. } => (),
+
+            TypedExpr::Block { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                let mut scope = scope.clone();
+                for expression in expressions {
+                    self.expression(expression, &mut scope);
+                }
+            }
+
+            TypedExpr::Var {
+                location,
+                constructor,
+                name,
+            } => {
+                if let Some(symbol) = self.resolve_value(&constructor.variant, name) {
+                    self.push(symbol, *location, ReferenceKind::Usage);
+                }
+            }
+
+            TypedExpr::Fn {
+                args,
+                body,
+                return_annotation,
+                ..
+            } => {
+                let mut scope = scope.clone();
+                for argument in args {
+                    self.argument(argument, &mut scope);
+                }
+                if let Some(annotation) = return_annotation {
+                    self.type_ast(annotation);
+                }
+                self.expression(body, &mut scope);
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                for element in elements {
+                    self.expression(element, scope);
+                }
+                if let Some(tail) = tail {
+                    self.expression(tail, scope);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expression(fun, scope);
+                let record_type = record_constructor_return_type(fun);
+                for argument in args {
+                    if let (Some(label), Some(type_)) = (&argument.label, &record_type) {
+                        self.record_label(type_, label, name_from(argument.location.start, label));
+                    }
+                    self.expression(&argument.value, scope);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expression(left, scope);
+                self.expression(right, scope);
+            }
+
+            TypedExpr::Assignment { value, pattern, .. } => {
+                self.expression(value, scope);
+                self.pattern(pattern, scope);
+            }
+
+            TypedExpr::Case {
+                subjects, clauses, ..
+            } => {
+                for subject in subjects {
+                    self.expression(subject, scope);
+                }
+                for clause in clauses {
+                    self.clause(clause, scope);
+                }
+            }
+
+            TypedExpr::RecordAccess {
+                location,
+                label,
+                record,
+                ..
+            } => {
+                self.expression(record, scope);
+                self.record_label(&record.type_(), label, name_until(location.end, label));
+            }
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_name,
+                ..
+            } => {
+                let symbol = Symbol::ModuleValue {
+                    module: module_name.clone(),
+                    name: label.clone(),
+                };
+                self.push(
+                    symbol,
+                    name_until(location.end, label),
+                    ReferenceKind::Usage,
+                );
+            }
+
+            TypedExpr::Tuple { elems, .. } => {
+                for element in elems {
+                    self.expression(element, scope);
+                }
+            }
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expression(tuple, scope),
+
+            TypedExpr::BitString { segments, .. } => {
+                for segment in segments {
+                    self.expression(&segment.value, scope);
+                    for option in &segment.options {
+                        if let BitStringSegmentOption::Size { value, .. } = option {
+                            self.expression(value, scope);
+                        }
+                    }
+                }
+            }
+
+            TypedExpr::RecordUpdate {
+                typ,
+                constructor,
+                spread,
+                args,
+                ..
+            } => {
+                self.expression(constructor, scope);
+                self.expression(spread, scope);
+                for argument in args {
+                    let location = name_from(argument.location.start, &argument.label);
+                    self.record_label(typ, &argument.label, location);
+                    self.expression(&argument.value, scope);
+                }
+            }
+
+            TypedExpr::NegateBool { value, .. } | TypedExpr::NegateInt { value, .. } => {
+                self.expression(value, scope)
+            }
+        }
+    }
+
+    fn record_label(&mut self, record_type: &Type, label: &SmolStr, location: SrcSpan) {
+        if let Some((module, type_name)) = named_type(record_type) {
+            let symbol = Symbol::RecordLabel {
+                module,
+                type_name,
+                label: label.clone(),
+            };
+            self.push(symbol, location, ReferenceKind::Usage);
+        }
+    }
+
+    fn clause(&mut self, clause: &TypedClause, scope: &Scope) {
+        let mut scope = scope.clone();
+        for pattern in &clause.pattern {
+            self.pattern(pattern, &mut scope);
+        }
+        for patterns in &clause.alternative_patterns {
+            let mut alternative_scope = scope.clone();
+            self.alternative_of = Some(scope.clone());
+            for pattern in patterns {
+                self.pattern(pattern, &mut alternative_scope);
+            }
+            self.alternative_of = None;
+        }
+        if let Some(guard) = &clause.guard {
+            self.clause_guard(guard, clause.location(), &scope);
+        }
+        self.expression(&clause.then, &mut scope);
+    }
+
+    fn clause_guard(&mut self, guard: &TypedClauseGuard, clause: SrcSpan, scope: &Scope) {
+        match guard {
+            ClauseGuard::Equals { left, right, .. }
+            | ClauseGuard::NotEquals { left, right, .. }
+            | ClauseGuard::GtInt { left, right, .. }
+            | ClauseGuard::GtEqInt { left, right, .. }
+            | ClauseGuard::LtInt { left, right, .. }
+            | ClauseGuard::LtEqInt { left, right, .. }
+            | ClauseGuard::GtFloat { left, right, .. }
+            | ClauseGuard::GtEqFloat { left, right, .. }
+            | ClauseGuard::LtFloat { left, right, .. }
+            | ClauseGuard::LtEqFloat { left, right, .. }
+            | ClauseGuard::Or { left, right, .. }
+            | ClauseGuard::And { left, right, .. }
+            | ClauseGuard::AddInt { left, right, .. }
+            | ClauseGuard::AddFloat { left, right, .. }
+            | ClauseGuard::SubInt { left, right, .. }
+            | ClauseGuard::SubFloat { left, right, .. }
+            | ClauseGuard::MultInt { left, right, .. }
+            | ClauseGuard::MultFloat { left, right, .. }
+            | ClauseGuard::DivInt { left, right, .. }
+            | ClauseGuard::DivFloat { left, right, .. }
+            | ClauseGuard::RemainderInt { left, right, .. }
+            | ClauseGuard::Concatenate { left, right, .. } => {
+                self.clause_guard(left, clause, scope);
+                self.clause_guard(right, clause, scope);
+            }
+
+            ClauseGuard::Var { location, name, .. } => self.local_usage(name, *location, scope),
+
+            ClauseGuard::TupleIndex { tuple, .. } => self.clause_guard(tuple, clause, scope),
+
+            ClauseGuard::FieldAccess {
+                location,
+                label,
+                container,
+                ..
+            } => {
+                self.clause_guard(container, clause, scope);
+                self.record_label(&container.type_(), label, name_until(location.end, label));
+            }
+
+            ClauseGuard::Not { expression, .. } => self.clause_guard(expression, clause, scope),
+
+            // Module constants used in guards are replaced with their values
+            // by the type checker, so only the constants written within the
+            // clause itself are found here.
+            ClauseGuard::Constant(constant) => {
+                if clause.contains(constant.location().start) {
+                    self.constant(constant, scope)
+                }
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &TypedPattern, scope: &mut Scope) {
+        match pattern {
+            Pattern::Int { .. }
+            | Pattern::Float { .. }
+            | Pattern::String { .. }
+            | Pattern::Discard { .. } => (),
+
+            Pattern::Var { location, name } => {
+                self.local_definition(name, *location, *location, scope)
+            }
+
+            Pattern::VarUsage { location, name, .. } => self.local_usage(name, *location, scope),
+
+            Pattern::Assign {
+                name,
+                location,
+                pattern,
+            } => {
+                self.pattern(pattern, scope);
+                self.local_definition(name, *location, *location, scope);
+            }
+
+            Pattern::List { elements, tail, .. } => {
+                for element in elements {
+                    self.pattern(element, scope);
+                }
+                if let Some(tail) = tail {
+                    self.pattern(tail, scope);
+                }
+            }
+
+            Pattern::Constructor {
+                location,
+                name,
+                arguments,
+                module,
+                constructor:
+                    PatternConstructor::Record {
+                        name: constructor_name,
+                        ..
+                    },
+                type_,
+                ..
+            } => {
+                let record_type = type_.return_type().unwrap_or_else(|| type_.clone());
+                if let Some((type_module, _)) = named_type(&record_type) {
+                    let symbol = Symbol::ModuleValue {
+                        module: type_module,
+                        name: constructor_name.clone(),
+                    };
+                    let location = qualified_name(location.start, module, name);
+                    self.push(symbol, location, ReferenceKind::Usage);
+                }
+                for argument in arguments {
+                    if let Some(label) = &argument.label {
+                        let location = name_from(argument.location.start, label);
+                        self.record_label(&record_type, label, location);
+                    }
+                    self.pattern(&argument.value, scope);
+                }
+            }
+
+            Pattern::Tuple { elems, .. } => {
+                for element in elems {
+                    self.pattern(element, scope);
+                }
+            }
+
+            Pattern::BitString { segments, .. } => {
+                for segment in segments {
+                    self.pattern(&segment.value, scope);
+                    for option in &segment.options {
+                        if let BitStringSegmentOption::Size { value, .. } = option {
+                            self.pattern(value, scope);
+                        }
+                    }
+                }
+            }
+
+            Pattern::Concatenate {
+                right_location,
+                right_side_assignment,
+                ..
+            } => {
+                if let Some(name) = right_side_assignment.assigned_name() {
+                    let name = SmolStr::from(name);
+                    self.local_definition(&name, *right_location, *right_location, scope);
+                }
+            }
+        }
+    }
+
+    fn constant(&mut self, constant: &TypedConstant, scope: &Scope) {
+        match constant {
+            Constant::Int { .. } | Constant::Float { .. } | Constant::String { .. } => (),
+
+            Constant::Tuple { elements, .. } | Constant::List { elements, .. } => {
+                for element in elements {
+                    self.constant(element, scope);
+                }
+            }
+
+            Constant::Record {
+                location,
+                module,
+                name,
+                args,
+                tag,
+                typ,
+                ..
+            } => {
+                if let Some((type_module, _)) = named_type(typ) {
+                    let symbol = Symbol::ModuleValue {
+                        module: type_module,
+                        name: tag.clone(),
+                    };
+                    let location = qualified_name(location.start, module, name);
+                    self.push(symbol, location, ReferenceKind::Usage);
+                }
+                for argument in args {
+                    if let Some(label) = &argument.label {
+                        let location = name_from(argument.location.start, label);
+                        self.record_label(typ, label, location);
+                    }
+                    self.constant(&argument.value, scope);
+                }
+            }
+
+            Constant::BitString { segments, .. } => {
+                for BitStringSegment { value, options, .. } in segments {
+                    self.constant(value, scope);
+                    for option in options {
+                        if let BitStringSegmentOption::Size { value, .. } = option {
+                            self.constant(value, scope);
+                        }
+                    }
+                }
+            }
+
+            Constant::Var {
+                location,
+                module,
+                name,
+                constructor,
+                ..
+            } => {
+                let location = name_until(location.end, name);
+                // Constants in clause guards may refer to local variables
+                if module.is_none() && scope.contains_key(name) {
+                    return self.local_usage(name, location, scope);
+                }
+                let symbol = constructor
+                    .as_ref()
+                    .and_then(|constructor| self.resolve_value(&constructor.variant, name));
+                if let Some(symbol) = symbol {
+                    self.push(symbol, location, ReferenceKind::Usage);
+                }
+            }
+        }
+    }
+}
+
+/// If the expression is a record constructor then this returns the type of
+/// the record it constructs.
+fn record_constructor_return_type(fun: &TypedExpr) -> Option<Arc<Type>> {
+    let is_record = match fun {
+        TypedExpr::Var { constructor, .. } => {
+            matches!(constructor.variant, ValueConstructorVariant::Record { .. })
+        }
+        TypedExpr::ModuleSelect { constructor, .. } => {
+            matches!(constructor, ModuleValueConstructor::Record { .. })
+        }
+        _ => false,
+    };
+    if is_record {
+        fun.type_().return_type()
+    } else {
+        None
+    }
+}
+
+/// Returns the module and name of a named type, following any type variable
+/// links.
+pub(crate) fn named_type(type_: &Type) -> Option<(SmolStr, SmolStr)> {
+    match type_ {
+        Type::App { module, name, .. } => Some((module.clone(), name.clone())),
+        Type::Var { type_ } => match &*type_.borrow() {
+            TypeVar::Link { type_ } => named_type(type_),
+            TypeVar::Unbound { .. } | TypeVar::Generic { .. } => None,
+        },
+        Type::Fn { .. } | Type::Tuple { .. } => None,
+    }
+}
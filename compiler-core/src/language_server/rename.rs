@@ -0,0 +1,243 @@
+use smol_str::SmolStr;
+
+use crate::{
+    ast::{Function, SrcSpan, Statement},
+    build::Module,
+    erlang,
+    error::InvalidRenameReason,
+    parse::lexer::str_to_keyword,
+    Error, Result,
+};
+
+use super::reference::{module_references, Reference, ReferenceKind, Symbol};
+
+/// The edits to be made to the source code of a module to rename a symbol.
+/// Each span is to be replaced with the new name.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleRename {
+    pub module: SmolStr,
+    pub edits: Vec<SrcSpan>,
+}
+
+/// Returns the span of the reference if the symbol can be renamed from this
+/// position. Symbols defined outside of the given modules cannot be renamed,
+/// and neither can names given to symbols by unqualified import aliases.
+///
+pub fn prepare_rename<'a>(
+    modules: impl IntoIterator<Item = &'a Module>,
+    module: &Module,
+    reference: &Reference,
+) -> Option<SrcSpan> {
+    let mut modules = modules.into_iter();
+    if !modules.any(|module| module.name == reference.symbol.module()) {
+        return None;
+    }
+    let name = text_at(module, reference.location)?;
+    match &reference.symbol {
+        Symbol::ModuleValue { name: own_name, .. }
+        | Symbol::ModuleType { name: own_name, .. }
+        | Symbol::RecordLabel {
+            label: own_name, ..
+        } if own_name != name => None,
+        _ => Some(reference.location),
+    }
+}
+
+/// Works out the edits needed to rename a symbol to the new name in all the
+/// given modules, returning an error if the new name is not valid or would
+/// clash with an existing name.
+///
+pub fn rename<'a>(
+    modules: impl IntoIterator<Item = &'a Module>,
+    symbol: &Symbol,
+    new_name: &str,
+) -> Result<Vec<ModuleRename>> {
+    validate_name(symbol, new_name)?;
+
+    let mut renames = vec![];
+    for module in modules {
+        let references = module_references(module);
+        let own: Vec<_> = references.iter().filter(|r| &r.symbol == symbol).collect();
+        if own.is_empty() {
+            continue;
+        }
+
+        if clashes(module, symbol, &own, &references, new_name) {
+            return Err(invalid_rename(
+                symbol,
+                new_name,
+                InvalidRenameReason::AlreadyInUse,
+            ));
+        }
+
+        // Uses of the symbol via an unqualified import alias keep the alias.
+        let old_name = symbol_name(module, symbol, &own);
+        let edits: Vec<_> = own
+            .iter()
+            .filter(|reference| text_at(module, reference.location) == old_name.as_deref())
+            .map(|reference| reference.location)
+            .collect();
+        if !edits.is_empty() {
+            renames.push(ModuleRename {
+                module: module.name.clone(),
+                edits,
+            });
+        }
+    }
+    Ok(renames)
+}
+
+fn validate_name(symbol: &Symbol, name: &str) -> Result<()> {
+    let valid_format = match symbol {
+        Symbol::ModuleType { .. } => is_upname(name),
+        Symbol::ModuleValue { name: old_name, .. } if is_upname(old_name) => is_upname(name),
+        Symbol::ModuleValue { .. } | Symbol::RecordLabel { .. } | Symbol::LocalVariable { .. } => {
+            is_name(name)
+        }
+    };
+    if !valid_format {
+        Err(invalid_rename(symbol, name, InvalidRenameReason::Format))
+    } else if str_to_keyword(name).is_some() {
+        Err(invalid_rename(
+            symbol,
+            name,
+            InvalidRenameReason::GleamReservedWord,
+        ))
+    } else if erlang::is_erlang_reserved_word(name) {
+        Err(invalid_rename(
+            symbol,
+            name,
+            InvalidRenameReason::ErlangReservedWord,
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Whether renaming the symbol in this module would result in the new name
+/// referring to two different things in the same scope.
+fn clashes(
+    module: &Module,
+    symbol: &Symbol,
+    own: &[&Reference],
+    references: &[Reference],
+    new_name: &str,
+) -> bool {
+    let is_named = |reference: &&Reference| text_at(module, reference.location) == Some(new_name);
+    match symbol {
+        // Values share a namespace with local variables, and can be referred
+        // to without qualification where they are defined or imported.
+        Symbol::ModuleValue { .. } => {
+            let unqualified = own
+                .iter()
+                .any(|r| matches!(r.kind, ReferenceKind::Definition | ReferenceKind::Import));
+            unqualified
+                && references.iter().filter(is_named).any(|r| {
+                    matches!(
+                        r.symbol,
+                        Symbol::ModuleValue { .. } | Symbol::LocalVariable { .. }
+                    )
+                })
+        }
+
+        Symbol::ModuleType { .. } => {
+            let unqualified = own
+                .iter()
+                .any(|r| matches!(r.kind, ReferenceKind::Definition | ReferenceKind::Import));
+            unqualified
+                && references
+                    .iter()
+                    .filter(is_named)
+                    .any(|r| matches!(r.symbol, Symbol::ModuleType { .. }))
+        }
+
+        Symbol::RecordLabel {
+            module: label_module,
+            type_name,
+            ..
+        } => references.iter().filter(is_named).any(|r| {
+            matches!(&r.symbol, Symbol::RecordLabel { module, type_name: other, .. }
+                if module == label_module && other == type_name)
+        }),
+
+        // Local variables clash with any other value referred to by the new
+        // name within the same function.
+        Symbol::LocalVariable { definition, .. } => {
+            let function = match enclosing_function(module, *definition) {
+                Some(function) => function,
+                None => return false,
+            };
+            references
+                .iter()
+                .filter(is_named)
+                .filter(|r| function_contains(function, r.location))
+                .any(|r| {
+                    matches!(
+                        r.symbol,
+                        Symbol::ModuleValue { .. } | Symbol::LocalVariable { .. }
+                    )
+                })
+        }
+    }
+}
+
+fn enclosing_function(module: &Module, location: SrcSpan) -> Option<SrcSpan> {
+    module
+        .ast
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Function(Function {
+                location,
+                end_position,
+                ..
+            }) => Some(SrcSpan::new(location.start, *end_position)),
+            _ => None,
+        })
+        .find(|function| function_contains(*function, location))
+}
+
+fn function_contains(function: SrcSpan, location: SrcSpan) -> bool {
+    function.start <= location.start && location.end <= function.end
+}
+
+/// The name the symbol is defined with.
+fn symbol_name(module: &Module, symbol: &Symbol, own: &[&Reference]) -> Option<SmolStr> {
+    match symbol {
+        Symbol::ModuleValue { name, .. } | Symbol::ModuleType { name, .. } => Some(name.clone()),
+        Symbol::RecordLabel { label, .. } => Some(label.clone()),
+        Symbol::LocalVariable { .. } => own
+            .iter()
+            .find(|reference| reference.kind == ReferenceKind::Definition)
+            .or_else(|| own.first())
+            .and_then(|reference| text_at(module, reference.location))
+            .map(SmolStr::from),
+    }
+}
+
+fn text_at(module: &Module, location: SrcSpan) -> Option<&str> {
+    module
+        .code
+        .get(location.start as usize..location.end as usize)
+}
+
+fn invalid_rename(symbol: &Symbol, name: &str, reason: InvalidRenameReason) -> Error {
+    Error::InvalidRename {
+        name: name.into(),
+        kind: symbol.kind(),
+        reason,
+    }
+}
+
+fn is_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map(|c| c.is_ascii_lowercase()) == Some(true)
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn is_upname(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().map(|c| c.is_ascii_uppercase()) == Some(true)
+        && chars.all(|c| c.is_ascii_alphanumeric())
+}
@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use crate::{
+    ast::{Arg, CallArg, Function, SrcSpan, Statement, TypedExpr, TypedPattern},
+    build::Module,
+    type_::Type,
+};
+
+/// Returns the locations of the nodes of the typed AST that contain the byte
+/// index, from the innermost to the outermost, for expanding a selection.
+///
+/// Each location contains the ones before it, so nodes that do not nest
+/// within their parent in the source code, such as the expressions generated
+/// for a pipeline, are skipped.
+///
+pub fn selection_ranges(module: &Module, byte_index: u32) -> Vec<SrcSpan> {
+    let mut finder = Finder {
+        byte_index,
+        ranges: vec![],
+    };
+    for statement in &module.ast.statements {
+        match statement {
+            Statement::Function(Function {
+                location,
+                end_position,
+                arguments,
+                body,
+                ..
+            }) => {
+                let _ = finder.push(SrcSpan::new(location.start, end_position + 1));
+                let _ = finder.push(*location);
+                finder.arguments(arguments);
+                finder.expression(body);
+            }
+            _ => {
+                let _ = finder.push(statement.location());
+            }
+        }
+    }
+
+    let mut ranges: Vec<SrcSpan> = vec![];
+    for range in finder.ranges {
+        let nested = ranges.last().is_none_or(|parent| {
+            *parent != range && parent.start <= range.start && range.end <= parent.end
+        });
+        if nested {
+            ranges.push(range);
+        }
+    }
+    ranges.reverse();
+    ranges
+}
+
+struct Finder {
+    byte_index: u32,
+    /// The locations found, from the outermost to the innermost.
+    ranges: Vec<SrcSpan>,
+}
+
+impl Finder {
+    /// Records the location if it contains the byte index, returning whether
+    /// it does.
+    fn push(&mut self, location: SrcSpan) -> bool {
+        let contains = location.start <= self.byte_index && self.byte_index <= location.end;
+        if contains {
+            self.ranges.push(location);
+        }
+        contains
+    }
+
+    fn arguments(&mut self, arguments: &[Arg<Arc<Type>>]) {
+        for argument in arguments {
+            let _ = self.push(argument.location);
+        }
+    }
+
+    fn call_args<A>(&mut self, args: &[CallArg<A>], mut value: impl FnMut(&mut Self, &A)) {
+        for arg in args {
+            if self.push(arg.location) {
+                value(self, &arg.value);
+            }
+        }
+    }
+
+    fn expression(&mut self, expression: &TypedExpr) {
+        if !self.push(expression.location()) {
+            return;
+        }
+        match expression {
+            TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Var { .. }
+            | TypedExpr::ModuleSelect { .. }
+            | TypedExpr::Todo { .. }
+            | TypedExpr::Panic { .. } => (),
+
+            TypedExpr::Block { expressions, .. }
+            | TypedExpr::Pipeline { expressions, .. }
+            | TypedExpr::Tuple {
+                elems: expressions, ..
+            } => {
+                for expression in expressions {
+                    self.expression(expression);
+                }
+            }
+
+            TypedExpr::Fn { args, body, .. } => {
+                self.arguments(args);
+                self.expression(body);
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                for element in elements {
+                    self.expression(element);
+                }
+                if let Some(tail) = tail {
+                    self.expression(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expression(fun);
+                self.call_args(args, Self::expression);
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+
+            TypedExpr::Assignment { value, pattern, .. } => {
+                self.pattern(pattern);
+                self.expression(value);
+            }
+
+            TypedExpr::Case {
+                subjects, clauses, ..
+            } => {
+                for subject in subjects {
+                    self.expression(subject);
+                }
+                for clause in clauses {
+                    if self.push(clause.location()) {
+                        let patterns = clause
+                            .pattern
+                            .iter()
+                            .chain(clause.alternative_patterns.iter().flatten());
+                        for pattern in patterns {
+                            self.pattern(pattern);
+                        }
+                        self.expression(&clause.then);
+                    }
+                }
+            }
+
+            TypedExpr::RecordAccess { record, .. } => self.expression(record),
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expression(tuple),
+
+            TypedExpr::BitString { segments, .. } => {
+                for segment in segments {
+                    if self.push(segment.location) {
+                        self.expression(&segment.value);
+                    }
+                }
+            }
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expression(spread);
+                for arg in args {
+                    if self.push(arg.location) {
+                        self.expression(&arg.value);
+                    }
+                }
+            }
+
+            TypedExpr::NegateBool { value, .. } | TypedExpr::NegateInt { value, .. } => {
+                self.expression(value)
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &TypedPattern) {
+        if !self.push(pattern.location()) {
+            return;
+        }
+        match pattern {
+            TypedPattern::Int { .. }
+            | TypedPattern::Float { .. }
+            | TypedPattern::String { .. }
+            | TypedPattern::Var { .. }
+            | TypedPattern::VarUsage { .. }
+            | TypedPattern::Discard { .. }
+            | TypedPattern::Concatenate { .. } => (),
+
+            TypedPattern::Assign { pattern, .. } => self.pattern(pattern),
+
+            TypedPattern::List { elements, tail, .. } => {
+                for element in elements {
+                    self.pattern(element);
+                }
+                if let Some(tail) = tail {
+                    self.pattern(tail);
+                }
+            }
+
+            TypedPattern::Constructor { arguments, .. } => {
+                self.call_args(arguments, Self::pattern);
+            }
+
+            TypedPattern::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.pattern(elem);
+                }
+            }
+
+            TypedPattern::BitString { segments, .. } => {
+                for segment in segments {
+                    if self.push(segment.location) {
+                        self.pattern(&segment.value);
+                    }
+                }
+            }
+        }
+    }
+}
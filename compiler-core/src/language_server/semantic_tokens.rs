@@ -0,0 +1,773 @@
+use std::{collections::HashMap, sync::Arc};
+
+use smol_str::SmolStr;
+
+use crate::{
+    ast::{
+        Arg, ArgNames, AssignName, CallArg, CustomType, ExternalFunction, ExternalType, Function,
+        Import, ModuleConstant, Pattern, SrcSpan, Statement, TypeAlias, TypeAst, TypedExpr,
+        TypedPattern,
+    },
+    build::Module,
+    parse::{lexer::make_tokenizer, token::Token},
+    type_::{self, ModuleValueConstructor, Type, ValueConstructorVariant},
+};
+
+/// The kinds of semantic token, in the order they are given in the legend of
+/// the language server.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenType {
+    Namespace,
+    Type,
+    TypeParameter,
+    Constructor,
+    Function,
+    Parameter,
+    Variable,
+    Property,
+    Keyword,
+    Comment,
+    String,
+    Number,
+    Operator,
+}
+
+impl TokenType {
+    pub const ALL: [TokenType; 13] = [
+        TokenType::Namespace,
+        TokenType::Type,
+        TokenType::TypeParameter,
+        TokenType::Constructor,
+        TokenType::Function,
+        TokenType::Parameter,
+        TokenType::Variable,
+        TokenType::Property,
+        TokenType::Keyword,
+        TokenType::Comment,
+        TokenType::String,
+        TokenType::Number,
+        TokenType::Operator,
+    ];
+}
+
+/// Additional information about a semantic token, in the order they are
+/// given in the legend of the language server.
+///
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenModifier {
+    /// The token is the name given to the thing where it is defined.
+    Declaration,
+    /// The token is a constant.
+    Readonly,
+    /// The token is code that should not be left in a finished program, such
+    /// as `todo`.
+    Deprecated,
+    /// The token is a documentation comment.
+    Documentation,
+    /// The token is a function implemented in Erlang or JavaScript.
+    External,
+    /// The token is a name starting with an underscore, so it is not used.
+    Discarded,
+}
+
+impl TokenModifier {
+    pub const ALL: [TokenModifier; 6] = [
+        TokenModifier::Declaration,
+        TokenModifier::Readonly,
+        TokenModifier::Deprecated,
+        TokenModifier::Documentation,
+        TokenModifier::External,
+        TokenModifier::Discarded,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub location: SrcSpan,
+    pub type_: TokenType,
+    pub modifiers: Vec<TokenModifier>,
+}
+
+/// Returns the semantic tokens of a module in the order they appear.
+///
+/// The tokens are those of the lexer, with the names classified using the
+/// typed AST of the module and the interfaces of the modules it imports.
+///
+pub fn semantic_tokens(
+    module: &Module,
+    modules: &HashMap<SmolStr, &type_::Module>,
+) -> Vec<SemanticToken> {
+    let mut classifier = Classifier {
+        module_name: &module.name,
+        modules,
+        names: HashMap::new(),
+        parameters: vec![],
+    };
+    for statement in &module.ast.statements {
+        classifier.statement(statement);
+    }
+
+    let aliases: Vec<_> = module
+        .ast
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Import(import) => Some(import.variable_name()),
+            _ => None,
+        })
+        .collect();
+
+    let lexed: Vec<_> = make_tokenizer(&module.code).map_while(Result::ok).collect();
+    let mut tokens = Vec::with_capacity(lexed.len());
+    for (index, (start, token, end)) in lexed.iter().enumerate() {
+        let location = SrcSpan::new(*start, *end);
+        let next = lexed.get(index + 1).map(|(_, token, _)| token);
+        let (type_, modifiers) = match token {
+            Token::Name { name } | Token::UpName { name } | Token::DiscardName { name } => {
+                match classifier.classification(name, location) {
+                    Some(classification) => classification,
+                    None => default_classification(token, next, &aliases),
+                }
+            }
+
+            Token::Int { .. } | Token::Float { .. } => (TokenType::Number, vec![]),
+            Token::String { .. } => (TokenType::String, vec![]),
+
+            // The span of a comment starts after its slashes
+            Token::CommentNormal | Token::CommentDoc | Token::CommentModule => {
+                let (slashes, modifiers) = match token {
+                    Token::CommentNormal => (2, vec![]),
+                    Token::CommentDoc => (3, vec![TokenModifier::Documentation]),
+                    _ => (4, vec![TokenModifier::Documentation]),
+                };
+                tokens.push(SemanticToken {
+                    location: SrcSpan::new(start.saturating_sub(slashes), *end),
+                    type_: TokenType::Comment,
+                    modifiers,
+                });
+                continue;
+            }
+
+            Token::Todo | Token::Panic => (TokenType::Keyword, vec![TokenModifier::Deprecated]),
+
+            Token::As
+            | Token::Assert
+            | Token::Case
+            | Token::Const
+            | Token::External
+            | Token::Fn
+            | Token::If
+            | Token::Import
+            | Token::Let
+            | Token::Opaque
+            | Token::Pub
+            | Token::Type
+            | Token::Use => (TokenType::Keyword, vec![]),
+
+            Token::Plus
+            | Token::Minus
+            | Token::Star
+            | Token::Slash
+            | Token::Less
+            | Token::Greater
+            | Token::LessEqual
+            | Token::GreaterEqual
+            | Token::Percent
+            | Token::PlusDot
+            | Token::MinusDot
+            | Token::StarDot
+            | Token::SlashDot
+            | Token::LessDot
+            | Token::GreaterDot
+            | Token::LessEqualDot
+            | Token::GreaterEqualDot
+            | Token::LtGt
+            | Token::Bang
+            | Token::Equal
+            | Token::EqualEqual
+            | Token::NotEqual
+            | Token::Vbar
+            | Token::VbarVbar
+            | Token::AmperAmper
+            | Token::Pipe
+            | Token::RArrow
+            | Token::LArrow
+            | Token::DotDot => (TokenType::Operator, vec![]),
+
+            Token::LeftParen
+            | Token::RightParen
+            | Token::LeftSquare
+            | Token::RightSquare
+            | Token::LeftBrace
+            | Token::RightBrace
+            | Token::Colon
+            | Token::Comma
+            | Token::Hash
+            | Token::LtLt
+            | Token::GtGt
+            | Token::Dot
+            | Token::EndOfFile
+            | Token::EmptyLine => continue,
+        };
+        tokens.push(SemanticToken {
+            location,
+            type_,
+            modifiers,
+        });
+    }
+    tokens
+}
+
+/// The classification of a name that does not appear in the typed AST, such
+/// as the name of a module, or a type in an expression that has been desugared.
+fn default_classification(
+    token: &Token,
+    next: Option<&Token>,
+    aliases: &[SmolStr],
+) -> (TokenType, Vec<TokenModifier>) {
+    match token {
+        Token::Name { name } if next == Some(&Token::Dot) && aliases.contains(name) => {
+            (TokenType::Namespace, vec![])
+        }
+        Token::Name { .. } if next == Some(&Token::LeftParen) => (TokenType::Function, vec![]),
+        Token::UpName { .. } => (TokenType::Type, vec![]),
+        Token::DiscardName { .. } => (TokenType::Variable, vec![TokenModifier::Discarded]),
+        _ => (TokenType::Variable, vec![]),
+    }
+}
+
+type Classification = (TokenType, Vec<TokenModifier>);
+
+/// Classifies the names in the typed AST. As most nodes of the AST do not
+/// record the locations of the names within them, each name is recorded
+/// with the location of the smallest node that contains it, and is matched
+/// with a token of the same name within that location.
+struct Classifier<'a> {
+    module_name: &'a SmolStr,
+    modules: &'a HashMap<SmolStr, &'a type_::Module>,
+    names: HashMap<SmolStr, Vec<(SrcSpan, Classification)>>,
+    /// The locations where function parameters are defined.
+    parameters: Vec<SrcSpan>,
+}
+
+impl Classifier<'_> {
+    fn classification(&self, name: &str, token: SrcSpan) -> Option<Classification> {
+        self.names
+            .get(name)?
+            .iter()
+            .filter(|(location, _)| location.start <= token.start && token.end <= location.end)
+            .min_by_key(|(location, _)| location.end - location.start)
+            .map(|(_, classification)| classification.clone())
+    }
+
+    fn name(
+        &mut self,
+        name: &SmolStr,
+        location: SrcSpan,
+        type_: TokenType,
+        modifiers: Vec<TokenModifier>,
+    ) {
+        let modifiers = if name.starts_with('_') {
+            let mut modifiers = modifiers;
+            modifiers.push(TokenModifier::Discarded);
+            modifiers
+        } else {
+            modifiers
+        };
+        self.names
+            .entry(name.clone())
+            .or_default()
+            .push((location, (type_, modifiers)));
+    }
+
+    fn statement(&mut self, statement: &Statement<Arc<Type>, TypedExpr, SmolStr, SmolStr>) {
+        match statement {
+            Statement::Function(Function {
+                location,
+                name,
+                arguments,
+                body,
+                return_annotation,
+                ..
+            }) => {
+                self.name(
+                    name,
+                    *location,
+                    TokenType::Function,
+                    vec![TokenModifier::Declaration],
+                );
+                self.arguments(arguments);
+                if let Some(annotation) = return_annotation {
+                    self.type_ast(annotation);
+                }
+                self.expression(body);
+            }
+
+            Statement::ExternalFunction(ExternalFunction {
+                location,
+                name,
+                arguments,
+                return_,
+                ..
+            }) => {
+                self.name(
+                    name,
+                    *location,
+                    TokenType::Function,
+                    vec![TokenModifier::Declaration, TokenModifier::External],
+                );
+                for argument in arguments {
+                    if let Some(label) = &argument.label {
+                        self.name(label, argument.location, TokenType::Property, vec![]);
+                    }
+                    self.type_ast(&argument.annotation);
+                }
+                self.type_ast(return_);
+            }
+
+            Statement::CustomType(CustomType {
+                location,
+                name,
+                parameters,
+                constructors,
+                ..
+            }) => {
+                self.name(
+                    name,
+                    *location,
+                    TokenType::Type,
+                    vec![TokenModifier::Declaration],
+                );
+                for parameter in parameters {
+                    self.name(parameter, *location, TokenType::TypeParameter, vec![]);
+                }
+                for constructor in constructors {
+                    self.name(
+                        &constructor.name,
+                        constructor.location,
+                        TokenType::Constructor,
+                        vec![TokenModifier::Declaration],
+                    );
+                    for argument in &constructor.arguments {
+                        if let Some(label) = &argument.label {
+                            self.name(label, argument.location, TokenType::Property, vec![]);
+                        }
+                        self.type_ast(&argument.ast);
+                    }
+                }
+            }
+
+            Statement::TypeAlias(TypeAlias {
+                location,
+                alias,
+                parameters,
+                type_ast,
+                ..
+            }) => {
+                self.name(
+                    alias,
+                    *location,
+                    TokenType::Type,
+                    vec![TokenModifier::Declaration],
+                );
+                for parameter in parameters {
+                    self.name(parameter, *location, TokenType::TypeParameter, vec![]);
+                }
+                self.type_ast(type_ast);
+            }
+
+            Statement::ExternalType(ExternalType {
+                location,
+                name,
+                arguments,
+                ..
+            }) => {
+                self.name(
+                    name,
+                    *location,
+                    TokenType::Type,
+                    vec![TokenModifier::Declaration],
+                );
+                for argument in arguments {
+                    self.name(argument, *location, TokenType::TypeParameter, vec![]);
+                }
+            }
+
+            Statement::ModuleConstant(ModuleConstant {
+                location,
+                name,
+                annotation,
+                ..
+            }) => {
+                self.name(
+                    name,
+                    *location,
+                    TokenType::Variable,
+                    vec![TokenModifier::Declaration, TokenModifier::Readonly],
+                );
+                if let Some(annotation) = annotation {
+                    self.type_ast(annotation);
+                }
+            }
+
+            Statement::Import(import) => self.import(import),
+        }
+    }
+
+    fn import(&mut self, import: &Import<SmolStr>) {
+        let location = import.location;
+        for segment in import.module.split('/') {
+            self.name(&segment.into(), location, TokenType::Namespace, vec![]);
+        }
+        if let Some(as_name) = &import.as_name {
+            self.name(as_name, location, TokenType::Namespace, vec![]);
+        }
+
+        let interface = self.modules.get(&import.module);
+        for unqualified in &import.unqualified {
+            let value = interface.and_then(|interface| interface.values.get(&unqualified.name));
+            let (type_, modifiers) = match value.map(|value| &value.variant) {
+                Some(ValueConstructorVariant::Record { .. }) => (TokenType::Constructor, vec![]),
+                Some(ValueConstructorVariant::ModuleFn { module, .. }) => {
+                    (TokenType::Function, self.function_modifiers(module))
+                }
+                Some(ValueConstructorVariant::ModuleConstant { .. }) => {
+                    (TokenType::Variable, vec![TokenModifier::Readonly])
+                }
+                Some(ValueConstructorVariant::LocalVariable { .. }) | None => {
+                    (TokenType::Type, vec![])
+                }
+            };
+            // The alias of an unqualified import is after its location
+            let alias_location = SrcSpan::new(unqualified.location.start, location.end);
+            self.name(
+                &unqualified.name,
+                unqualified.location,
+                type_,
+                modifiers.clone(),
+            );
+            if let Some(as_name) = &unqualified.as_name {
+                self.name(as_name, alias_location, type_, modifiers);
+            }
+        }
+    }
+
+    /// Functions referred to by their module being something other than a
+    /// Gleam module are implemented in Erlang or JavaScript.
+    fn function_modifiers(&self, module: &SmolStr) -> Vec<TokenModifier> {
+        if module == self.module_name || self.modules.contains_key(module) {
+            vec![]
+        } else {
+            vec![TokenModifier::External]
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[Arg<Arc<Type>>]) {
+        for argument in arguments {
+            let location = argument.location;
+            let declaration = vec![TokenModifier::Declaration];
+            match &argument.names {
+                ArgNames::Discard { name } | ArgNames::Named { name } => {
+                    self.name(name, location, TokenType::Parameter, declaration)
+                }
+                ArgNames::LabelledDiscard { label, name }
+                | ArgNames::NamedLabelled { label, name, .. } => {
+                    // The label comes first, so it is given a smaller location
+                    // in case it is the same as the name.
+                    let label_location =
+                        SrcSpan::new(location.start, location.start + label.len() as u32);
+                    self.name(label, label_location, TokenType::Property, vec![]);
+                    self.name(name, location, TokenType::Parameter, declaration);
+                }
+            }
+            if argument.get_variable_name().is_some() {
+                self.parameters.push(location);
+            }
+            if let Some(annotation) = &argument.annotation {
+                self.type_ast(annotation);
+            }
+        }
+    }
+
+    fn type_ast(&mut self, type_ast: &TypeAst) {
+        match type_ast {
+            TypeAst::Constructor {
+                location,
+                module,
+                name,
+                arguments,
+            } => {
+                if let Some(module) = module {
+                    self.name(module, *location, TokenType::Namespace, vec![]);
+                }
+                self.name(name, *location, TokenType::Type, vec![]);
+                for argument in arguments {
+                    self.type_ast(argument);
+                }
+            }
+            TypeAst::Fn {
+                arguments, return_, ..
+            } => {
+                for argument in arguments {
+                    self.type_ast(argument);
+                }
+                self.type_ast(return_);
+            }
+            TypeAst::Var { location, name } => {
+                self.name(name, *location, TokenType::TypeParameter, vec![])
+            }
+            TypeAst::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.type_ast(elem);
+                }
+            }
+            TypeAst::Hole { location, name } => {
+                self.name(name, *location, TokenType::TypeParameter, vec![])
+            }
+        }
+    }
+
+    fn call_arg_label<A>(&mut self, arg: &CallArg<A>) {
+        if let Some(label) = &arg.label {
+            self.name(label, arg.location, TokenType::Property, vec![]);
+        }
+    }
+
+    fn expression(&mut self, expression: &TypedExpr) {
+        match expression {
+            TypedExpr::Int { .. }
+            | TypedExpr::Float { .. }
+            | TypedExpr::String { .. }
+            | TypedExpr::Todo { .. }
+            | TypedExpr::Panic { .. } => (),
+
+            TypedExpr::Var {
+                location,
+                constructor,
+                name,
+            } => {
+                let (type_, modifiers) = match &constructor.variant {
+                    ValueConstructorVariant::LocalVariable { location }
+                        if self.parameters.contains(location) =>
+                    {
+                        (TokenType::Parameter, vec![])
+                    }
+                    ValueConstructorVariant::LocalVariable { .. } => (TokenType::Variable, vec![]),
+                    ValueConstructorVariant::ModuleConstant { .. } => {
+                        (TokenType::Variable, vec![TokenModifier::Readonly])
+                    }
+                    ValueConstructorVariant::ModuleFn { module, .. } => {
+                        (TokenType::Function, self.function_modifiers(module))
+                    }
+                    ValueConstructorVariant::Record { .. } => (TokenType::Constructor, vec![]),
+                };
+                self.name(name, *location, type_, modifiers);
+            }
+
+            TypedExpr::ModuleSelect {
+                location,
+                label,
+                module_name,
+                module_alias,
+                constructor,
+                ..
+            } => {
+                self.name(module_alias, *location, TokenType::Namespace, vec![]);
+                let (type_, modifiers) = match constructor {
+                    ModuleValueConstructor::Record { .. } => (TokenType::Constructor, vec![]),
+                    ModuleValueConstructor::Fn { module, .. } if module != module_name => {
+                        (TokenType::Function, vec![TokenModifier::External])
+                    }
+                    ModuleValueConstructor::Fn { .. } => (TokenType::Function, vec![]),
+                    ModuleValueConstructor::Constant { .. } => {
+                        (TokenType::Variable, vec![TokenModifier::Readonly])
+                    }
+                };
+                // The label is given a smaller location in case it is the
+                // same as the module alias.
+                let label_location = SrcSpan::new(
+                    location.end.saturating_sub(label.len() as u32),
+                    location.end,
+                );
+                self.name(label, label_location, type_, modifiers);
+            }
+
+            TypedExpr::Block { expressions, .. } | TypedExpr::Pipeline { expressions, .. } => {
+                for expression in expressions {
+                    self.expression(expression);
+                }
+            }
+
+            TypedExpr::Assignment { value, pattern, .. } => {
+                self.pattern(pattern);
+                self.expression(value);
+            }
+
+            TypedExpr::Fn {
+                args,
+                body,
+                return_annotation,
+                ..
+            } => {
+                self.arguments(args);
+                if let Some(annotation) = return_annotation {
+                    self.type_ast(annotation);
+                }
+                self.expression(body);
+            }
+
+            TypedExpr::List { elements, tail, .. } => {
+                for element in elements {
+                    self.expression(element);
+                }
+                if let Some(tail) = tail {
+                    self.expression(tail);
+                }
+            }
+
+            TypedExpr::Call { fun, args, .. } => {
+                self.expression(fun);
+                for arg in args {
+                    self.call_arg_label(arg);
+                    self.expression(&arg.value);
+                }
+            }
+
+            TypedExpr::BinOp { left, right, .. } => {
+                self.expression(left);
+                self.expression(right);
+            }
+
+            TypedExpr::Case {
+                subjects, clauses, ..
+            } => {
+                for subject in subjects {
+                    self.expression(subject);
+                }
+                for clause in clauses {
+                    let patterns = clause
+                        .pattern
+                        .iter()
+                        .chain(clause.alternative_patterns.iter().flatten());
+                    for pattern in patterns {
+                        self.pattern(pattern);
+                    }
+                    self.expression(&clause.then);
+                }
+            }
+
+            TypedExpr::RecordAccess {
+                location,
+                label,
+                record,
+                ..
+            } => {
+                self.name(label, *location, TokenType::Property, vec![]);
+                self.expression(record);
+            }
+
+            TypedExpr::TupleIndex { tuple, .. } => self.expression(tuple),
+
+            TypedExpr::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.expression(elem);
+                }
+            }
+
+            TypedExpr::BitString { segments, .. } => {
+                for segment in segments {
+                    self.expression(&segment.value);
+                }
+            }
+
+            TypedExpr::RecordUpdate { spread, args, .. } => {
+                self.expression(spread);
+                for arg in args {
+                    self.name(&arg.label, arg.location, TokenType::Property, vec![]);
+                    self.expression(&arg.value);
+                }
+            }
+
+            TypedExpr::NegateBool { value, .. } | TypedExpr::NegateInt { value, .. } => {
+                self.expression(value)
+            }
+        }
+    }
+
+    fn pattern(&mut self, pattern: &TypedPattern) {
+        let declaration = vec![TokenModifier::Declaration];
+        match pattern {
+            Pattern::Int { .. } | Pattern::Float { .. } | Pattern::String { .. } => (),
+
+            Pattern::Var { location, name } => {
+                self.name(name, *location, TokenType::Variable, declaration)
+            }
+
+            Pattern::VarUsage { location, name, .. } => {
+                self.name(name, *location, TokenType::Variable, vec![])
+            }
+
+            Pattern::Assign {
+                name,
+                location,
+                pattern,
+            } => {
+                self.name(name, *location, TokenType::Variable, declaration);
+                self.pattern(pattern);
+            }
+
+            Pattern::Discard { name, location } => {
+                self.name(name, *location, TokenType::Variable, declaration)
+            }
+
+            Pattern::List { elements, tail, .. } => {
+                for element in elements {
+                    self.pattern(element);
+                }
+                if let Some(tail) = tail {
+                    self.pattern(tail);
+                }
+            }
+
+            Pattern::Constructor {
+                location,
+                name,
+                arguments,
+                module,
+                ..
+            } => {
+                if let Some(module) = module {
+                    self.name(module, *location, TokenType::Namespace, vec![]);
+                }
+                self.name(name, *location, TokenType::Constructor, vec![]);
+                for argument in arguments {
+                    self.call_arg_label(argument);
+                    self.pattern(&argument.value);
+                }
+            }
+
+            Pattern::Tuple { elems, .. } => {
+                for elem in elems {
+                    self.pattern(elem);
+                }
+            }
+
+            Pattern::BitString { segments, .. } => {
+                for segment in segments {
+                    self.pattern(&segment.value);
+                }
+            }
+
+            Pattern::Concatenate {
+                right_location,
+                right_side_assignment,
+                ..
+            } => {
+                let (AssignName::Variable(name) | AssignName::Discard(name)) =
+                    right_side_assignment;
+                self.name(name, *right_location, TokenType::Variable, declaration);
+            }
+        }
+    }
+}
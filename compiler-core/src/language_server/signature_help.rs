@@ -0,0 +1,450 @@
+use std::{collections::HashMap, sync::Arc};
+
+use smol_str::SmolStr;
+
+use crate::{
+    ast::{
+        CustomType, ExternalFunction, Function, Import, RecordConstructor, Statement,
+        TypedStatement,
+    },
+    build::Module,
+    parse::lexer::str_to_keyword,
+    type_::{
+        self, collapse_links, pretty::Printer, Type, ValueConstructor, ValueConstructorVariant,
+    },
+};
+
+use super::completion::{is_name_char, locals_in_scope, Documentation};
+
+/// The signature of the function being called at the cursor.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureHelp {
+    /// The printed signature, e.g. `add(to total: Int, Int) -> Int`.
+    pub label: String,
+    /// The start and end byte offsets of each of the parameters in the label.
+    pub parameters: Vec<(usize, usize)>,
+    /// The index of the parameter the argument at the cursor is given for.
+    pub active_parameter: Option<usize>,
+    pub documentation: Option<SmolStr>,
+}
+
+#[derive(Debug)]
+struct Parameter {
+    label: Option<SmolStr>,
+    name: Option<SmolStr>,
+    type_: Arc<Type>,
+}
+
+#[derive(Debug)]
+struct Callee {
+    parameters: Vec<Parameter>,
+    return_type: Arc<Type>,
+    documentation: Option<SmolStr>,
+}
+
+/// A call found in the source code before the cursor.
+#[derive(Debug)]
+struct Call<'a> {
+    qualifier: Option<&'a str>,
+    name: &'a str,
+    /// The arguments written so far, the last being the one at the cursor.
+    arguments: Vec<&'a str>,
+    /// Whether the call is on the right hand side of a `|>`, in which case the
+    /// first argument is given implicitly.
+    piped: bool,
+    /// Whether the call is on the right hand side of a `use`, in which case the
+    /// last argument is given implicitly, being the callback.
+    used: bool,
+}
+
+/// Returns the signature of the function or record constructor being called
+/// at the cursor.
+///
+/// As with completion, the source code may have been edited since the module
+/// was last compiled, so the call is found from the source code and the
+/// function being called from the compiled module.
+///
+pub fn signature_help(
+    module: &Module,
+    src: &str,
+    cursor: u32,
+    modules: &HashMap<SmolStr, &type_::Module>,
+    documentation: &HashMap<SmolStr, Documentation>,
+) -> Option<SignatureHelp> {
+    let call = find_call(src, cursor as usize)?;
+    let callee = match call.qualifier {
+        Some(qualifier) => {
+            let import = imports(module).find(|import| import.variable_name() == qualifier)?;
+            let value = modules.get(&import.module)?.values.get(call.name)?;
+            let documentation = documentation
+                .get(&import.module)
+                .and_then(|documentation| documentation.value(call.name));
+            interface_callee(value, documentation)?
+        }
+        None => local_callee(module, cursor, call.name)
+            .or_else(|| module_callee(module, call.name))
+            .or_else(|| imported_callee(module, call.name, modules, documentation))?,
+    };
+
+    let mut printer = Printer::new();
+    let mut label = format!("{}(", call.name);
+    let mut parameters = Vec::with_capacity(callee.parameters.len());
+    for (index, parameter) in callee.parameters.iter().enumerate() {
+        if index > 0 {
+            label.push_str(", ");
+        }
+        let start = label.len();
+        match (&parameter.label, &parameter.name) {
+            (Some(label_), Some(name)) if label_ != name => {
+                label.push_str(&format!("{label_} {name}: "))
+            }
+            (Some(name), _) | (None, Some(name)) => label.push_str(&format!("{name}: ")),
+            (None, None) => (),
+        }
+        label.push_str(&printer.pretty_print(&parameter.type_, 0));
+        parameters.push((start, label.len()));
+    }
+    label.push_str(") -> ");
+    label.push_str(&printer.pretty_print(&callee.return_type, 0));
+
+    Some(SignatureHelp {
+        label,
+        parameters,
+        active_parameter: active_parameter(&call, &callee.parameters),
+        documentation: callee.documentation,
+    })
+}
+
+/// Works out which parameter the argument at the cursor is for. Labelled
+/// arguments may be given in any order, while unlabelled ones are given for
+/// the parameters that have not been given yet, in order.
+fn active_parameter(call: &Call<'_>, parameters: &[Parameter]) -> Option<usize> {
+    let mut given = vec![false; parameters.len()];
+    if call.piped {
+        if let Some(first) = given.first_mut() {
+            *first = true;
+        }
+    }
+    if call.used {
+        if let Some(last) = given.last_mut() {
+            *last = true;
+        }
+    }
+
+    let mut active = None;
+    for argument in &call.arguments {
+        let index = match argument_label(argument) {
+            Some(label) => parameters
+                .iter()
+                .position(|parameter| parameter.label.as_deref() == Some(label)),
+            None => given.iter().position(|given| !given),
+        };
+        if let Some(given) = index.and_then(|index| given.get_mut(index)) {
+            *given = true;
+        }
+        active = index;
+    }
+    active
+}
+
+/// The label of an argument, if it is a labelled argument.
+fn argument_label(argument: &str) -> Option<&str> {
+    let argument = argument.trim_start();
+    let end = argument.find(|c| !is_name_char(c))?;
+    let (label, rest) = argument.split_at(end);
+    let rest = rest.trim_start();
+    let is_label = label.starts_with(|c: char| c.is_ascii_lowercase())
+        && rest.starts_with(':')
+        && !rest.starts_with("::");
+    is_label.then_some(label)
+}
+
+/// A call to a function held in a local variable.
+fn local_callee(module: &Module, cursor: u32, name: &str) -> Option<Callee> {
+    let type_ = locals_in_scope(module, cursor)
+        .into_iter()
+        .find(|(local, _)| local == name)?
+        .1?;
+    let (arguments, return_type) = collapse_links(type_).fn_types()?;
+    Some(Callee {
+        parameters: arguments
+            .into_iter()
+            .map(|type_| Parameter {
+                label: None,
+                name: None,
+                type_,
+            })
+            .collect(),
+        return_type,
+        documentation: None,
+    })
+}
+
+/// A call to a function or record constructor defined in the module, for
+/// which the names of the parameters are known.
+fn module_callee(module: &Module, name: &str) -> Option<Callee> {
+    let documentation = Documentation::from_statements(&module.ast.statements);
+    let documentation = documentation.value(name).cloned();
+    module
+        .ast
+        .statements
+        .iter()
+        .find_map(|statement| statement_callee(statement, &module.name, name))
+        .map(|callee| Callee {
+            documentation,
+            ..callee
+        })
+}
+
+fn statement_callee(statement: &TypedStatement, module: &SmolStr, name: &str) -> Option<Callee> {
+    match statement {
+        Statement::Function(Function {
+            name: function_name,
+            arguments,
+            return_type,
+            ..
+        }) if function_name == name => Some(Callee {
+            parameters: arguments
+                .iter()
+                .map(|argument| Parameter {
+                    label: argument.names.get_label().cloned(),
+                    name: argument.get_variable_name().cloned(),
+                    type_: argument.type_.clone(),
+                })
+                .collect(),
+            return_type: return_type.clone(),
+            documentation: None,
+        }),
+
+        Statement::ExternalFunction(ExternalFunction {
+            name: function_name,
+            arguments,
+            return_type,
+            ..
+        }) if function_name == name => Some(Callee {
+            parameters: arguments
+                .iter()
+                .map(|argument| Parameter {
+                    label: argument.label.clone(),
+                    name: None,
+                    type_: argument.type_.clone(),
+                })
+                .collect(),
+            return_type: return_type.clone(),
+            documentation: None,
+        }),
+
+        Statement::CustomType(CustomType {
+            name: type_name,
+            public,
+            constructors,
+            typed_parameters,
+            ..
+        }) => {
+            let RecordConstructor { arguments, .. } = constructors
+                .iter()
+                .find(|constructor| constructor.name == name)?;
+            Some(Callee {
+                parameters: arguments
+                    .iter()
+                    .map(|argument| Parameter {
+                        label: argument.label.clone(),
+                        name: None,
+                        type_: argument.type_.clone(),
+                    })
+                    .collect(),
+                return_type: Arc::new(Type::App {
+                    public: *public,
+                    module: module.clone(),
+                    name: type_name.clone(),
+                    args: typed_parameters.clone(),
+                }),
+                documentation: None,
+            })
+        }
+
+        Statement::Function(_)
+        | Statement::ExternalFunction(_)
+        | Statement::TypeAlias(_)
+        | Statement::ExternalType(_)
+        | Statement::Import(_)
+        | Statement::ModuleConstant(_) => None,
+    }
+}
+
+/// A call to a function or record constructor imported unqualified, or from
+/// the prelude.
+fn imported_callee(
+    module: &Module,
+    name: &str,
+    modules: &HashMap<SmolStr, &type_::Module>,
+    documentation: &HashMap<SmolStr, Documentation>,
+) -> Option<Callee> {
+    let imported = imports(module).find_map(|import| {
+        let unqualified = import
+            .unqualified
+            .iter()
+            .find(|unqualified| unqualified.is_value() && unqualified.variable_name() == name)?;
+        Some((import.module.clone(), unqualified.name.clone()))
+    });
+    let (module_name, name) = imported.unwrap_or_else(|| ("gleam".into(), name.into()));
+    let value = modules.get(&module_name)?.values.get(&name)?;
+    let documentation = documentation
+        .get(&module_name)
+        .and_then(|documentation| documentation.value(&name));
+    interface_callee(value, documentation)
+}
+
+/// A call to a function from the interface of another module, for which only
+/// the labels of the parameters are known.
+fn interface_callee(value: &ValueConstructor, documentation: Option<&SmolStr>) -> Option<Callee> {
+    let field_map = match &value.variant {
+        ValueConstructorVariant::ModuleFn { field_map, .. }
+        | ValueConstructorVariant::Record { field_map, .. } => field_map.as_ref(),
+        ValueConstructorVariant::LocalVariable { .. }
+        | ValueConstructorVariant::ModuleConstant { .. } => None,
+    };
+    let (arguments, return_type) = collapse_links(value.type_.clone()).fn_types()?;
+    let parameters = arguments
+        .into_iter()
+        .enumerate()
+        .map(|(index, type_)| Parameter {
+            label: field_map.and_then(|field_map| {
+                field_map
+                    .fields
+                    .iter()
+                    .find(|(_, field_index)| **field_index as usize == index)
+                    .map(|(label, _)| label.clone())
+            }),
+            name: None,
+            type_,
+        })
+        .collect();
+    Some(Callee {
+        parameters,
+        return_type,
+        documentation: documentation.cloned(),
+    })
+}
+
+fn imports(module: &Module) -> impl Iterator<Item = &Import<SmolStr>> {
+    module
+        .ast
+        .statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::Import(import) => Some(import),
+            _ => None,
+        })
+}
+
+/// Finds the call that the cursor is within the arguments of, by searching
+/// backwards for an unclosed opening parenthesis.
+fn find_call(src: &str, cursor: usize) -> Option<Call<'_>> {
+    let before = src.get(..cursor)?;
+    let open = unclosed_paren(before)?;
+    let arguments = split_arguments(before.get(open + 1..)?);
+
+    let before_paren = before.get(..open)?;
+    let name_start = before_paren.trim_end_matches(is_name_char).len();
+    let name = before_paren.get(name_start..)?;
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    if str_to_keyword(name).is_some() {
+        return None;
+    }
+
+    let mut callee_start = name_start;
+    let mut qualifier = None;
+    if let Some(before_dot) = before_paren.get(..name_start)?.strip_suffix('.') {
+        let qualifier_start = before_dot.trim_end_matches(is_name_char).len();
+        qualifier = before_dot.get(qualifier_start..);
+        callee_start = qualifier_start;
+    }
+
+    let before_callee = before.get(..callee_start)?.trim_end();
+    let piped = before_callee.ends_with("|>");
+    let used = before_callee.ends_with("<-");
+
+    Some(Call {
+        qualifier,
+        name,
+        arguments,
+        piped,
+        used,
+    })
+}
+
+/// The index of the opening parenthesis which has not been closed by the end
+/// of the source code. Searching stops at the start of a block, as a call
+/// cannot continue past it.
+fn unclosed_paren(src: &str) -> Option<usize> {
+    let mut open = vec![];
+    for (index, char) in code_chars(src) {
+        match char {
+            '(' | '[' | '{' => open.push((index, char)),
+            ')' | ']' | '}' => {
+                let _ = open.pop();
+            }
+            _ => (),
+        }
+    }
+    while let Some((index, char)) = open.pop() {
+        match char {
+            '(' => return Some(index),
+            '{' => return None,
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Splits the text of the arguments of a call on the commas that are not
+/// within nested brackets, strings or comments.
+fn split_arguments(src: &str) -> Vec<&str> {
+    let mut arguments = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, char) in code_chars(src) {
+        match char {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push(src.get(start..index).unwrap_or_default());
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    arguments.push(src.get(start..).unwrap_or_default());
+    arguments
+}
+
+/// The characters of the source code that are not within strings or
+/// comments, along with their byte indexes.
+fn code_chars(src: &str) -> Vec<(usize, char)> {
+    let mut code = vec![];
+    let mut in_string = false;
+    let mut chars = src.char_indices().peekable();
+    while let Some((index, char)) = chars.next() {
+        match char {
+            // The character after a backslash is escaped
+            '\\' if in_string => {
+                let _ = chars.next();
+            }
+            '"' => in_string = !in_string,
+            _ if in_string => (),
+            '/' if matches!(chars.peek(), Some((_, '/'))) => {
+                for (_, char) in chars.by_ref() {
+                    if char == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => code.push((index, char)),
+        }
+    }
+    code
+}
@@ -0,0 +1,281 @@
+use std::sync::Arc;
+
+use smol_str::SmolStr;
+
+use crate::{
+    ast::{
+        CustomType, ExternalFunction, ExternalType, Function, ModuleConstant, RecordConstructor,
+        SrcSpan, Statement, TypeAlias, TypedStatement,
+    },
+    build::Module,
+    type_::{self, prelude::fn_, pretty::Printer, Type, ValueConstructorVariant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Function,
+    Constant,
+    Type,
+    Constructor,
+    Field,
+}
+
+/// An entry in the outline of a module.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSymbol {
+    pub name: SmolStr,
+    pub kind: SymbolKind,
+    /// The printed type of the symbol, if it has one.
+    pub detail: Option<String>,
+    /// The span of the entire definition.
+    pub location: SrcSpan,
+    /// The span of the name of the symbol within the definition.
+    pub name_location: SrcSpan,
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// A top level definition found by a workspace symbol search.
+///
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceSymbol {
+    pub name: SmolStr,
+    pub kind: SymbolKind,
+    pub module: SmolStr,
+    pub package: SmolStr,
+    pub location: SrcSpan,
+}
+
+/// Returns an outline of the definitions of the module, in the order they
+/// appear in the source code.
+///
+pub fn document_symbols(module: &Module) -> Vec<DocumentSymbol> {
+    // The statements of a typed module are not in the order they were written.
+    let mut symbols: Vec<_> = module
+        .ast
+        .statements
+        .iter()
+        .filter_map(statement_symbol)
+        .collect();
+    symbols.sort_by_key(|symbol| symbol.location.start);
+    symbols
+}
+
+fn statement_symbol(statement: &TypedStatement) -> Option<DocumentSymbol> {
+    // The end position of functions and custom types is that of the closing
+    // brace, so one is added to include it.
+    match statement {
+        Statement::Import(_) => None,
+
+        Statement::Function(Function {
+            location,
+            end_position,
+            name,
+            name_location,
+            arguments,
+            return_type,
+            ..
+        }) => {
+            let arguments = arguments.iter().map(|arg| arg.type_.clone()).collect();
+            let type_ = fn_(arguments, return_type.clone());
+            Some(symbol(
+                name,
+                *name_location,
+                SymbolKind::Function,
+                Some(&type_),
+                SrcSpan::new(location.start, end_position + 1),
+            ))
+        }
+
+        Statement::ExternalFunction(ExternalFunction {
+            location,
+            name,
+            name_location,
+            arguments,
+            return_type,
+            ..
+        }) => {
+            let arguments = arguments.iter().map(|arg| arg.type_.clone()).collect();
+            let type_ = fn_(arguments, return_type.clone());
+            Some(symbol(
+                name,
+                *name_location,
+                SymbolKind::Function,
+                Some(&type_),
+                *location,
+            ))
+        }
+
+        // The location of a constant only covers its name, so the value is
+        // included explicitly.
+        Statement::ModuleConstant(ModuleConstant {
+            location,
+            name,
+            name_location,
+            value,
+            type_,
+            ..
+        }) => Some(symbol(
+            name,
+            *name_location,
+            SymbolKind::Constant,
+            Some(type_),
+            SrcSpan::new(location.start, value.location().end),
+        )),
+
+        Statement::TypeAlias(TypeAlias {
+            location,
+            alias: name,
+            name_location,
+            ..
+        })
+        | Statement::ExternalType(ExternalType {
+            location,
+            name,
+            name_location,
+            ..
+        }) => Some(symbol(
+            name,
+            *name_location,
+            SymbolKind::Type,
+            None,
+            *location,
+        )),
+
+        Statement::CustomType(CustomType {
+            location,
+            end_position,
+            name,
+            name_location,
+            constructors,
+            ..
+        }) => {
+            let mut type_ = symbol(
+                name,
+                *name_location,
+                SymbolKind::Type,
+                None,
+                SrcSpan::new(location.start, end_position + 1),
+            );
+            type_.children = constructors.iter().map(constructor_symbol).collect();
+            Some(type_)
+        }
+    }
+}
+
+fn constructor_symbol(constructor: &RecordConstructor<Arc<Type>>) -> DocumentSymbol {
+    let RecordConstructor {
+        location,
+        name,
+        arguments,
+        ..
+    } = constructor;
+    let name_location = SrcSpan::new(location.start, location.start + name.len() as u32);
+    let mut symbol = symbol(
+        name,
+        name_location,
+        SymbolKind::Constructor,
+        None,
+        *location,
+    );
+    symbol.children = arguments
+        .iter()
+        .filter_map(|argument| {
+            let label = argument.label.as_ref()?;
+            let start = argument.location.start;
+            Some(DocumentSymbol {
+                name: label.clone(),
+                kind: SymbolKind::Field,
+                detail: Some(Printer::new().pretty_print(&argument.type_, 0)),
+                location: SrcSpan::new(start, argument.ast.location().end),
+                name_location: SrcSpan::new(start, start + label.len() as u32),
+                children: vec![],
+            })
+        })
+        .collect();
+    symbol
+}
+
+fn symbol(
+    name: &SmolStr,
+    name_location: SrcSpan,
+    kind: SymbolKind,
+    type_: Option<&Type>,
+    location: SrcSpan,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name: name.clone(),
+        kind,
+        detail: type_.map(|type_| Printer::new().pretty_print(type_, 0)),
+        location,
+        name_location,
+        children: vec![],
+    }
+}
+
+/// Searches the public definitions of the given modules for those with names
+/// that fuzzy match the query, best matches first.
+///
+pub fn workspace_symbols<'a>(
+    modules: impl IntoIterator<Item = &'a type_::Module>,
+    query: &str,
+) -> Vec<WorkspaceSymbol> {
+    let mut found = vec![];
+    for module in modules {
+        let symbol = |name: &SmolStr, kind, location| WorkspaceSymbol {
+            name: name.clone(),
+            kind,
+            module: module.name.clone(),
+            package: module.package.clone(),
+            location,
+        };
+
+        for (name, type_) in &module.types {
+            found.push(symbol(name, SymbolKind::Type, type_.origin));
+        }
+
+        for (name, value) in &module.values {
+            let kind = match &value.variant {
+                ValueConstructorVariant::ModuleFn { .. } => SymbolKind::Function,
+                ValueConstructorVariant::ModuleConstant { .. } => SymbolKind::Constant,
+                ValueConstructorVariant::Record { .. } => SymbolKind::Constructor,
+                ValueConstructorVariant::LocalVariable { .. } => continue,
+            };
+            found.push(symbol(name, kind, value.variant.definition_location()));
+        }
+    }
+
+    let mut scored: Vec<_> = found
+        .into_iter()
+        .filter_map(|symbol| Some((fuzzy_score(query, &symbol.name)?, symbol)))
+        .collect();
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        a_score
+            .cmp(b_score)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.module.cmp(&b.module))
+            .then_with(|| a.location.start.cmp(&b.location.start))
+    });
+    scored.into_iter().map(|(_, symbol)| symbol).collect()
+}
+
+/// Scores how well the name matches the query, lower being better. The name
+/// matches if it contains all the characters of the query in order, ignoring
+/// case. Names that start with the query and names where the matched
+/// characters are close together are preferred.
+///
+fn fuzzy_score(query: &str, name: &str) -> Option<usize> {
+    let query = query.to_lowercase();
+    let name = name.to_lowercase();
+    let mut gaps = 0;
+    let mut position = 0;
+    for char in query.chars() {
+        let index = name.get(position..)?.find(char)?;
+        if position > 0 {
+            gaps += index;
+        }
+        position += index + char.len_utf8();
+    }
+    let prefix_penalty = if name.starts_with(&query) { 0 } else { 1000 };
+    Some(prefix_penalty + gaps * 10 + name.len())
+}
@@ -0,0 +1,118 @@
+use std::{path::PathBuf, sync::Arc, time::SystemTime};
+
+use crate::{
+    ast::SrcSpan,
+    build::{Module, Origin, Target},
+    type_::build_prelude,
+    uid::UniqueIdGenerator,
+    warning::{TypeWarningEmitter, VectorWarningEmitterIO, Warning, WarningEmitter},
+};
+
+mod call_hierarchy;
+mod code_action;
+mod code_lens;
+mod completion;
+mod definition;
+mod folding_range;
+mod formatting;
+mod hover;
+mod inlay_hints;
+mod reference;
+mod rename;
+mod selection_range;
+mod semantic_tokens;
+mod signature_help;
+mod symbols;
+
+/// Type check the given modules in order, each being able to import the ones
+/// that come before it.
+///
+fn compile_modules(sources: &[(&str, &str)]) -> Vec<Module> {
+    try_compile_modules(sources, &TypeWarningEmitter::null()).expect("should successfully infer")
+}
+
+/// The warnings from type checking the given modules.
+///
+fn compile_warnings(sources: &[(&str, &str)]) -> Vec<crate::type_::Warning> {
+    let io = Arc::new(VectorWarningEmitterIO::default());
+    let emitter =
+        TypeWarningEmitter::new(PathBuf::new(), "".into(), WarningEmitter::new(io.clone()));
+    let _ = try_compile_modules(sources, &emitter).expect("should successfully infer");
+    io.take()
+        .into_iter()
+        .map(|warning| match warning {
+            Warning::Type { warning, .. } => warning,
+        })
+        .collect()
+}
+
+/// The error from type checking the given modules, one of which has an error.
+///
+fn compile_error(sources: &[(&str, &str)]) -> crate::type_::Error {
+    match try_compile_modules(sources, &TypeWarningEmitter::null()) {
+        Ok(_) => panic!("should fail to infer"),
+        Err(error) => error,
+    }
+}
+
+fn try_compile_modules(
+    sources: &[(&str, &str)],
+    warnings: &TypeWarningEmitter,
+) -> Result<Vec<Module>, crate::type_::Error> {
+    let ids = UniqueIdGenerator::new();
+    let mut importable = im::HashMap::new();
+    // DUPE: preludeinsertion
+    // TODO: Currently we do this here and also in the tests. It would be better
+    // to have one place where we create all this required state for use in each
+    // place.
+    let _ = importable.insert("gleam".into(), build_prelude(&ids));
+
+    let mut modules = vec![];
+    for (name, code) in sources {
+        let (mut ast, extra) = crate::parse::parse_module(code).expect("syntax error");
+        ast.name = (*name).into();
+        let dependencies = ast.dependencies(Target::Erlang);
+        let ast = crate::analyse::infer_module(
+            Target::Erlang,
+            &ids,
+            ast,
+            Origin::Src,
+            &"thepackage".into(),
+            &importable,
+            warnings,
+        )?;
+        let _ = importable.insert((*name).into(), ast.type_info.clone());
+        modules.push(Module {
+            name: (*name).into(),
+            code: (*code).into(),
+            mtime: SystemTime::UNIX_EPOCH,
+            input_path: PathBuf::from(format!("/src/{name}.gleam")),
+            origin: Origin::Src,
+            ast,
+            extra,
+            dependencies,
+        });
+    }
+    Ok(modules)
+}
+
+fn compile_module(code: &str) -> Module {
+    compile_modules(&[("app", code)])
+        .pop()
+        .expect("compiled module")
+}
+
+/// The byte index of the nth occurrence of the text in the source code.
+fn index_of(code: &str, text: &str, nth: usize) -> u32 {
+    code.match_indices(text)
+        .nth(nth)
+        .map(|(index, _)| index as u32)
+        .expect("text in source code")
+}
+
+/// The spans of all the occurrences of the text in the source code.
+fn spans_of(code: &str, text: &str) -> Vec<SrcSpan> {
+    code.match_indices(text)
+        .map(|(index, _)| SrcSpan::new(index as u32, (index + text.len()) as u32))
+        .collect()
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+use smol_str::SmolStr;
+
+use super::*;
+use crate::language_server::call_hierarchy::{
+    function_at, incoming_calls, outgoing_calls, CallHierarchyFunction,
+};
+
+const MATHS: &str = "
+pub fn double(x) { x * 2 }
+
+pub fn quadruple(x) {
+  x
+  |> double
+  |> double
+}
+";
+
+const APP: &str = "
+import maths.{double}
+
+pub fn main() {
+  let x = maths.quadruple(1)
+  use y <- apply(double(x))
+  y
+}
+
+fn apply(x, f) { f(x) }
+";
+
+fn interfaces(modules: &[Module]) -> HashMap<SmolStr, &crate::type_::Module> {
+    modules
+        .iter()
+        .map(|module| (module.name.clone(), &module.ast.type_info))
+        .collect()
+}
+
+/// The name of each function with the text of each of its calls.
+fn calls_text(
+    code: &str,
+    calls: Vec<crate::language_server::call_hierarchy::Calls>,
+) -> Vec<(String, Vec<String>)> {
+    calls
+        .into_iter()
+        .map(|calls| {
+            let texts = calls
+                .locations
+                .iter()
+                .map(|location| {
+                    code.get(location.start as usize..location.end as usize)
+                        .expect("location in source code")
+                        .to_string()
+                })
+                .collect();
+            (calls.function.name.to_string(), texts)
+        })
+        .collect()
+}
+
+#[test]
+fn function_at_call() {
+    let modules = compile_modules(&[("maths", MATHS), ("app", APP)]);
+    let interfaces = interfaces(&modules);
+    let app = modules.last().expect("compiled module");
+    let function = function_at(app, index_of(APP, "double(x)", 0), &interfaces);
+    assert_eq!(
+        function,
+        Some(CallHierarchyFunction {
+            module: "maths".into(),
+            package: "thepackage".into(),
+            name: "double".into(),
+            location: SrcSpan::new(
+                index_of(MATHS, "pub fn double", 0),
+                index_of(MATHS, " {", 0)
+            ),
+        })
+    );
+}
+
+#[test]
+fn incoming_calls_through_pipes() {
+    let modules = compile_modules(&[("maths", MATHS), ("app", APP)]);
+    let interfaces = interfaces(&modules);
+    let app = modules.last().expect("compiled module");
+    let double =
+        function_at(app, index_of(APP, "double(x)", 0), &interfaces).expect("function at position");
+
+    let maths = modules.first().expect("compiled module");
+    assert_eq!(
+        calls_text(MATHS, incoming_calls(maths, &double.module, &double.name)),
+        vec![("quadruple".into(), vec!["double".into(), "double".into()])]
+    );
+    assert_eq!(
+        calls_text(APP, incoming_calls(app, &double.module, &double.name)),
+        vec![("main".into(), vec!["double".into()])]
+    );
+}
+
+#[test]
+fn outgoing_calls_through_use() {
+    let modules = compile_modules(&[("maths", MATHS), ("app", APP)]);
+    let interfaces = interfaces(&modules);
+    let app = modules.last().expect("compiled module");
+    assert_eq!(
+        calls_text(APP, outgoing_calls(app, "main", &interfaces)),
+        vec![
+            ("quadruple".into(), vec!["quadruple".into()]),
+            ("apply".into(), vec!["apply".into()]),
+            ("double".into(), vec!["double".into()]),
+        ]
+    );
+}
+
+#[test]
+fn functions_passed_as_values_are_not_called() {
+    let code = "
+pub fn double(x) { x * 2 }
+
+pub fn main() {
+  let f = double
+  apply(1, double)
+  f(1)
+}
+
+fn apply(x, f) { f(x) }
+";
+    let modules = compile_modules(&[("app", code)]);
+    let interfaces = interfaces(&modules);
+    let app = modules.last().expect("compiled module");
+    assert_eq!(
+        calls_text(code, incoming_calls(app, "app", "double")),
+        vec![]
+    );
+    assert_eq!(
+        calls_text(code, outgoing_calls(app, "main", &interfaces)),
+        vec![("apply".into(), vec!["apply".into()])]
+    );
+}
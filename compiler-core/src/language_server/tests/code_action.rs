@@ -0,0 +1,227 @@
+use super::*;
+use crate::language_server::code_action::{error_actions, warning_actions, CodeAction};
+
+/// The code actions for the type error in the last of the modules, given the
+/// modules before it which compile successfully.
+fn actions(sources: &[(&str, &str)]) -> Vec<CodeAction> {
+    let (_, code) = sources.last().expect("module");
+    let error = compile_error(sources);
+    error_actions(&error, code)
+}
+
+/// Applies the edits of the action to the source code.
+fn apply(action: &CodeAction, code: &str) -> String {
+    let mut code = code.to_string();
+    let mut edits = action.edits.clone();
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.location.start));
+    for edit in edits {
+        code.replace_range(
+            edit.location.start as usize..edit.location.end as usize,
+            &edit.new_text,
+        );
+    }
+    code
+}
+
+#[test]
+fn add_missing_case_clauses() {
+    let code = "
+pub type Animal {
+  Cat(name: String, lives: Int)
+  Dog(String)
+  Fish
+}
+
+pub fn main(animal) {
+  case animal {
+    Dog(_) -> 1
+  }
+}
+";
+    let actions = actions(&[("app", code)]);
+    let action = actions.first().expect("action");
+    assert_eq!(action.title, "Add missing case clauses");
+    assert_eq!(
+        apply(action, code),
+        "
+pub type Animal {
+  Cat(name: String, lives: Int)
+  Dog(String)
+  Fish
+}
+
+pub fn main(animal) {
+  case animal {
+    Dog(_) -> 1
+    Cat(_, _) -> todo
+    Fish -> todo
+  }
+}
+"
+    );
+}
+
+#[test]
+fn add_missing_case_clauses_for_imported_type() {
+    let wibble = "pub type Animal { Cat(String) Dog }";
+    let code = "import wibble.{Dog}
+pub fn main(animal: wibble.Animal) {
+  case animal { Dog -> 1 }
+}
+";
+    let actions = actions(&[("wibble", wibble), ("app", code)]);
+    let action = actions.first().expect("action");
+    assert_eq!(
+        apply(action, code),
+        "import wibble.{Dog}
+pub fn main(animal: wibble.Animal) {
+  case animal { Dog -> 1 wibble.Cat(_) -> todo }
+}
+"
+    );
+}
+
+#[test]
+fn add_missing_case_clauses_for_prelude_type() {
+    let code = "pub fn main(x) {
+  case x {
+    Ok(_) -> 1
+  }
+}
+
+pub fn y() { main(Error(1)) }
+";
+    let actions = actions(&[("app", code)]);
+    let action = actions.first().expect("action");
+    assert!(apply(action, code).contains("    Ok(_) -> 1\n    Error(_) -> todo\n  }"));
+}
+
+#[test]
+fn convert_let_to_let_assert() {
+    let code = "
+pub fn main(x) {
+  let Ok(y) = x
+  y
+}
+
+pub fn y() { main(Error(1)) }
+";
+    let actions = actions(&[("app", code)]);
+    let action = actions.first().expect("action");
+    assert_eq!(action.title, "Convert to `let assert`");
+    assert!(apply(action, code).contains("  let assert Ok(y) = x\n"));
+}
+
+/// The code actions for the warnings of the last of the modules.
+fn warning_fixes(sources: &[(&str, &str)]) -> Vec<CodeAction> {
+    let (_, code) = sources.last().expect("module");
+    warning_actions(&compile_warnings(sources), code)
+}
+
+fn find<'a>(actions: &'a [CodeAction], title: &str) -> &'a CodeAction {
+    actions
+        .iter()
+        .find(|action| action.title == title)
+        .expect("action")
+}
+
+#[test]
+fn remove_unused_unqualified_import() {
+    let wibble = "pub fn wobble() { 1 }\npub fn wubble() { 1 }\npub type Wibble { Wibble }";
+    let code = "import wibble.{wobble, wubble as w, Wibble}
+pub fn main() { w() }
+";
+    let actions = warning_fixes(&[("wibble", wibble), ("app", code)]);
+    assert_eq!(
+        apply(find(&actions, "Remove unused import `wobble`"), code),
+        "import wibble.{wubble as w, Wibble}
+pub fn main() { w() }
+"
+    );
+    assert_eq!(
+        apply(find(&actions, "Fix all unused code in module"), code),
+        "import wibble.{wubble as w}
+pub fn main() { w() }
+"
+    );
+}
+
+#[test]
+fn remove_unused_imports() {
+    let code = "import wibble.{wobble}
+import wibble/wubble
+import other
+
+pub fn main() { other.x }
+";
+    let actions = warning_fixes(&[
+        ("wibble", "pub fn wobble() { 1 }"),
+        ("wibble/wubble", "pub fn wobble() { 1 }"),
+        ("other", "pub const x = 1"),
+        ("app", code),
+    ]);
+    assert_eq!(
+        apply(find(&actions, "Remove unused import `wibble/wubble`"), code),
+        "import wibble.{wobble}
+import other
+
+pub fn main() { other.x }
+"
+    );
+    assert_eq!(
+        apply(find(&actions, "Fix all unused code in module"), code),
+        "import wibble
+import other
+
+pub fn main() { other.x }
+"
+    );
+}
+
+#[test]
+fn prefix_unused_variables() {
+    let code = "pub fn main(y, label x: Int) {
+  let z = y
+  case y { w -> 1 }
+}
+";
+    let actions = warning_fixes(&[("app", code)]);
+    assert_eq!(
+        apply(find(&actions, "Fix all unused code in module"), code),
+        "pub fn main(y, label _x: Int) {
+  let _z = y
+  case y { _w -> 1 }
+}
+"
+    );
+}
+
+#[test]
+fn remove_unused_private_function() {
+    let code = "pub fn main() {
+  1
+}
+
+/// Unused
+fn unused(x) {
+  let y = 1
+  2
+}
+
+fn used() { 3 }
+
+pub fn other() { used() }
+";
+    let actions = warning_fixes(&[("app", code)]);
+    assert_eq!(
+        apply(find(&actions, "Fix all unused code in module"), code),
+        "pub fn main() {
+  1
+}
+
+fn used() { 3 }
+
+pub fn other() { used() }
+"
+    );
+}
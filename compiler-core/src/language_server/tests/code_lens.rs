@@ -0,0 +1,55 @@
+use super::*;
+use crate::language_server::code_lens::{test_lenses, TestLens};
+
+fn test_module(code: &str) -> Module {
+    let mut module = compile_module(code);
+    module.origin = Origin::Test;
+    module
+}
+
+#[test]
+fn lenses_for_test_functions() {
+    let code = "
+pub fn addition_test() {
+  Nil
+}
+
+pub fn helper(x) { x }
+
+fn private_test() { Nil }
+
+pub fn subtraction_test() { Nil }
+";
+    assert_eq!(
+        test_lenses(&test_module(code)),
+        vec![
+            TestLens {
+                location: SrcSpan::new(0, 0),
+                title: "Run module",
+                functions: vec!["addition_test".into(), "subtraction_test".into()],
+            },
+            TestLens {
+                location: SrcSpan::new(
+                    index_of(code, "pub fn addition_test", 0),
+                    index_of(code, " {\n  Nil", 0)
+                ),
+                title: "Run test",
+                functions: vec!["addition_test".into()],
+            },
+            TestLens {
+                location: SrcSpan::new(
+                    index_of(code, "pub fn subtraction_test", 0),
+                    index_of(code, " { Nil }\n", 1)
+                ),
+                title: "Run test",
+                functions: vec!["subtraction_test".into()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn no_lenses_outside_test_directory() {
+    let code = "pub fn addition_test() { Nil }";
+    assert_eq!(test_lenses(&compile_module(code)), vec![]);
+}
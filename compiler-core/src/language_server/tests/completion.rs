@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+
+use super::*;
+use crate::language_server::completion::{completions, Completion, CompletionKind, Documentation};
+
+/// Completes at the position of the `|` in the source code, which is the
+/// source code of the last of the compiled modules as edited since it was
+/// compiled.
+fn complete(modules: &[Module], src: &str) -> Vec<Completion> {
+    let cursor = index_of(src, "|", 0);
+    let src = src.replace('|', "");
+    let prelude = build_prelude(&UniqueIdGenerator::new());
+    let mut interfaces: HashMap<_, _> = modules
+        .iter()
+        .map(|module| (module.name.clone(), &module.ast.type_info))
+        .collect();
+    let _ = interfaces.insert("gleam".into(), &prelude);
+    let documentation = modules
+        .iter()
+        .map(|module| (module.name.clone(), Documentation::parse(&module.code)))
+        .collect();
+    let module = modules.last().expect("module");
+    completions(module, &src, cursor, &interfaces, &documentation)
+}
+
+fn labels(completions: &[Completion], kind: CompletionKind) -> Vec<&str> {
+    completions
+        .iter()
+        .filter(|completion| completion.kind == kind)
+        .map(|completion| completion.label.as_str())
+        .collect()
+}
+
+fn find<'a>(completions: &'a [Completion], label: &str) -> &'a Completion {
+    completions
+        .iter()
+        .find(|completion| completion.label == label)
+        .expect("completion")
+}
+
+#[test]
+fn completes_local_variables_in_scope() {
+    let code = "
+pub fn main(x: Int) {
+  let y = x + 1
+  case y {
+    1 -> y
+    z -> z
+  }
+  let #(a, b) = #(1.0, \"\")
+  a
+}
+";
+    let modules = vec![compile_module(code)];
+    let src = code.replace("  a\n", "  |\n");
+    let found = complete(&modules, &src);
+    assert_eq!(
+        labels(&found, CompletionKind::Variable),
+        vec!["a", "b", "x", "y"]
+    );
+    assert_eq!(find(&found, "x").detail.as_deref(), Some("Int"));
+    assert_eq!(find(&found, "a").detail.as_deref(), Some("Float"));
+    assert_eq!(labels(&found, CompletionKind::Function), vec!["main"]);
+    assert!(labels(&found, CompletionKind::Constructor).contains(&"True"));
+}
+
+#[test]
+fn completes_module_values_with_documentation() {
+    let modules = compile_modules(&[
+        (
+            "wibble",
+            "/// Makes a wobble
+pub fn wobble(x: Int) -> String { \"\" }
+pub const size = 1
+fn hidden() { 1 }",
+        ),
+        ("app", "import wibble\npub fn main() { 1 }"),
+    ]);
+    let found = complete(&modules, "import wibble\npub fn main() { wibble.w| }");
+    assert_eq!(
+        found,
+        vec![
+            Completion {
+                label: "size".into(),
+                kind: CompletionKind::Constant,
+                detail: Some("Int".into()),
+                documentation: None,
+            },
+            Completion {
+                label: "wobble".into(),
+                kind: CompletionKind::Function,
+                detail: Some("fn(Int) -> String".into()),
+                documentation: Some(" Makes a wobble".into()),
+            },
+        ]
+    );
+}
+
+#[test]
+fn completes_unqualified_imports() {
+    let modules = compile_modules(&[
+        ("wibble", "pub fn wobble() { 1 }\npub type Thing { Thing }"),
+        (
+            "app",
+            "import wibble.{wobble as wob, Thing}\npub fn main() { 1 }",
+        ),
+    ]);
+    let found = complete(
+        &modules,
+        "import wibble.{wobble as wob, Thing}\npub fn main() { | }",
+    );
+    assert_eq!(
+        labels(&found, CompletionKind::Function),
+        vec!["main", "wob"]
+    );
+    assert!(labels(&found, CompletionKind::Constructor).contains(&"Thing"));
+    assert_eq!(labels(&found, CompletionKind::Module), vec!["wibble"]);
+}
+
+#[test]
+fn completes_record_fields() {
+    let code = "
+type Cat {
+  Cat(name: String, lives: Int)
+}
+
+fn main(cat: Cat) {
+  cat
+}
+";
+    let modules = vec![compile_module(code)];
+    let found = complete(&modules, &code.replace("  cat\n", "  cat.|\n"));
+    assert_eq!(labels(&found, CompletionKind::Field), vec!["lives", "name"]);
+    assert_eq!(find(&found, "name").detail.as_deref(), Some("String"));
+}
+
+#[test]
+fn completes_constructors_in_patterns() {
+    let code = "
+pub type Animal {
+  Cat
+  Dog
+}
+
+pub fn main(x) {
+  case x {
+    Cat -> 1
+    _ -> 2
+  }
+}
+";
+    let modules = vec![compile_module(code)];
+    let found = complete(&modules, &code.replace("    _ -> 2", "    D|"));
+    assert!(labels(&found, CompletionKind::Variable).is_empty());
+    assert!(labels(&found, CompletionKind::Function).is_empty());
+    let constructors = labels(&found, CompletionKind::Constructor);
+    assert!(constructors.contains(&"Cat"));
+    assert!(constructors.contains(&"Dog"));
+    assert!(constructors.contains(&"Ok"));
+}
+
+#[test]
+fn completes_types_in_annotations() {
+    let code = "
+/// A cat
+pub type Cat {
+  Cat
+}
+
+pub fn main(x) {
+  x
+}
+";
+    let modules = vec![compile_module(code)];
+
+    let found = complete(&modules, &code.replace("main(x)", "main(x: |)"));
+    let types = labels(&found, CompletionKind::Type);
+    assert!(types.contains(&"Cat"));
+    assert!(types.contains(&"Int"));
+    assert!(labels(&found, CompletionKind::Constructor).is_empty());
+
+    let found = complete(&modules, &code.replace("main(x)", "main(x) -> List(|)"));
+    assert!(labels(&found, CompletionKind::Type).contains(&"Cat"));
+}
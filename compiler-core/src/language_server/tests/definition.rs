@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use super::*;
+use crate::language_server::definition::{native_function_location, type_definition, Definition};
+
+fn type_definition_at(sources: &[(&str, &str)], text: &str) -> Option<Definition> {
+    let modules = compile_modules(sources);
+    let interfaces: HashMap<_, _> = modules
+        .iter()
+        .map(|module| (module.name.clone(), &module.ast.type_info))
+        .collect();
+    let module = modules.last().expect("compiled module");
+    type_definition(module, index_of(&module.code, text, 0), &interfaces)
+}
+
+#[test]
+fn type_definition_of_variable() {
+    let other = "
+pub type Pet {
+  Cat
+}
+
+pub fn cat() { Cat }
+";
+    let code = "
+import other
+
+pub fn main() {
+  let pet = other.cat()
+  pet
+}
+";
+    let definition = type_definition_at(&[("other", other), ("app", code)], "pet\n}");
+    assert_eq!(
+        definition,
+        Some(Definition {
+            module: "other".into(),
+            package: "thepackage".into(),
+            location: SrcSpan::new(index_of(other, "pub type", 0), index_of(other, " {", 0)),
+        })
+    );
+}
+
+#[test]
+fn type_definition_of_function_return() {
+    let code = "
+pub type Box(a) {
+  Box(a)
+}
+
+pub fn main() {
+  Box(1)
+}
+";
+    let definition = type_definition_at(&[("app", code)], "main");
+    assert_eq!(
+        definition.map(|definition| definition.module),
+        Some("app".into())
+    );
+}
+
+#[test]
+fn no_type_definition_for_prelude_types() {
+    let code = "pub fn main() { 1 }";
+    assert_eq!(type_definition_at(&[("app", code)], "1"), None);
+}
+
+#[test]
+fn erlang_function_location() {
+    let code = "-module(ffi).
+-export([add/2, 'if'/0]).
+
+add_one(X) -> X + 1.
+
+add(X, Y) -> X + Y.
+
+'if'() -> nil.
+";
+    assert_eq!(
+        native_function_location(code, "add", Target::Erlang),
+        Some(SrcSpan::new(
+            index_of(code, "add(X", 0),
+            index_of(code, "(X, Y", 0)
+        ))
+    );
+    assert_eq!(
+        native_function_location(code, "if", Target::Erlang),
+        Some(SrcSpan::new(
+            index_of(code, "if'()", 0),
+            index_of(code, "'()", 0)
+        ))
+    );
+    assert_eq!(native_function_location(code, "sub", Target::Erlang), None);
+}
+
+#[test]
+fn javascript_function_location() {
+    let code = "export function addOne(x) {
+  return x + 1;
+}
+
+export const add = (x, y) => x + y;
+
+async function wait() {}
+";
+    assert_eq!(
+        native_function_location(code, "add", Target::JavaScript),
+        Some(SrcSpan::new(
+            index_of(code, "add =", 0),
+            index_of(code, " = (x", 0)
+        ))
+    );
+    assert_eq!(
+        native_function_location(code, "wait", Target::JavaScript),
+        Some(SrcSpan::new(
+            index_of(code, "wait", 0),
+            index_of(code, "() {}", 0)
+        ))
+    );
+    assert_eq!(
+        native_function_location(code, "x", Target::JavaScript),
+        None
+    );
+}
@@ -0,0 +1,97 @@
+use super::*;
+use crate::language_server::folding_range::{folding_ranges, FoldingRangeKind};
+
+/// The first line of each folding range, with its kind.
+fn folded_lines(sources: &[(&str, &str)]) -> Vec<(String, FoldingRangeKind)> {
+    let modules = compile_modules(sources);
+    let module = modules.last().expect("compiled module");
+    folding_ranges(module)
+        .into_iter()
+        .map(|range| {
+            let text = module
+                .code
+                .get(range.location.start as usize..)
+                .and_then(|code| code.lines().next())
+                .expect("range in source code");
+            (text.to_string(), range.kind)
+        })
+        .collect()
+}
+
+#[test]
+fn folds_imports_comments_and_functions() {
+    let code = "//// Some
+//// module comments
+
+import one
+import two
+
+/// Some
+/// documentation
+pub fn main() {
+  one.x
+  two.y
+}
+
+pub fn short() { 1 }
+";
+    let sources = [
+        ("one", "pub const x = 1"),
+        ("two", "pub const y = 2"),
+        ("app", code),
+    ];
+    assert_eq!(
+        folded_lines(&sources),
+        vec![
+            (" Some".into(), FoldingRangeKind::Comment),
+            ("one".into(), FoldingRangeKind::Imports),
+            (" Some".into(), FoldingRangeKind::Comment),
+            ("pub fn main() {".into(), FoldingRangeKind::Region),
+        ]
+    );
+}
+
+#[test]
+fn folds_expressions() {
+    let code = "
+pub fn main(x) {
+  let list = [
+    1,
+    2,
+  ]
+  let pair = #(1, 2)
+  let block = {
+    list
+    pair
+  }
+  case x {
+    1 -> {
+      list
+      pair
+    }
+    _ -> {
+      let f = fn() {
+        Nil
+      }
+      pair
+    }
+  }
+}
+";
+    let lines: Vec<_> = folded_lines(&[("app", code)])
+        .into_iter()
+        .map(|(line, _)| line)
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "pub fn main(x) {",
+            "[",
+            "list",
+            "case x {",
+            "1 -> {",
+            "_ -> {",
+            "fn() {",
+        ]
+    );
+}
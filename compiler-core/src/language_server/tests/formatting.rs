@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use super::*;
+use crate::language_server::{code_action::Edit, formatting::format_range};
+
+/// The code after formatting the statements overlapping the nth occurrence of
+/// the text.
+fn format_at(code: &str, text: &str, nth: usize) -> String {
+    let start = index_of(code, text, nth);
+    format_location(code, SrcSpan::new(start, start + text.len() as u32))
+}
+
+fn format_location(code: &str, location: SrcSpan) -> String {
+    let edits =
+        format_range(&code.into(), Path::new("src/app.gleam"), location).expect("formatted");
+    let mut code = code.to_string();
+    for Edit { location, new_text } in edits.into_iter().rev() {
+        code.replace_range(location.start as usize..location.end as usize, &new_text);
+    }
+    code
+}
+
+#[test]
+fn formats_only_overlapping_statements() {
+    let code = "pub fn one() {
+      1 }
+
+pub fn two() {
+      2 }
+
+pub fn three() {
+      3 }
+";
+    assert_eq!(
+        format_at(code, "2", 0),
+        "pub fn one() {
+      1 }
+
+pub fn two() {
+  2
+}
+
+pub fn three() {
+      3 }
+"
+    );
+}
+
+#[test]
+fn formats_statements_across_selection() {
+    let code = "import one
+import   two
+pub fn main() {   one.x }
+";
+    assert_eq!(
+        format_at(code, "two\npub", 0),
+        "import one
+import two
+
+pub fn main() {
+  one.x
+}
+"
+    );
+}
+
+#[test]
+fn empty_location_formats_enclosing_statement() {
+    let code = "pub fn one() {
+      1 }
+
+pub type   Two {
+  Two }
+";
+    let index = index_of(code, "}", 1);
+    assert_eq!(
+        format_location(code, SrcSpan::new(index, index)),
+        "pub fn one() {
+      1 }
+
+pub type Two {
+  Two
+}
+"
+    );
+}
+
+#[test]
+fn no_edit_when_comments_move() {
+    let code = "pub fn one() {
+      1 }
+
+//// A module comment
+pub fn two() {
+      2 }
+";
+    assert_eq!(format_at(code, "1", 0), code);
+}
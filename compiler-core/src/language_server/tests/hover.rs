@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use super::*;
+use crate::language_server::{
+    completion::Documentation,
+    hover::{hover, Hover},
+};
+
+/// The hover for the nth occurrence of the text in the last of the modules.
+fn hover_at(sources: &[(&str, &str)], text: &str, nth: usize) -> Option<Hover> {
+    let mut modules = compile_modules(sources);
+    for module in &mut modules {
+        module.attach_doc_and_module_comments();
+    }
+    let interfaces: HashMap<_, _> = modules
+        .iter()
+        .map(|module| (module.name.clone(), &module.ast.type_info))
+        .collect();
+    let documentation = |name: &str| {
+        modules
+            .iter()
+            .find(|module| module.name == name)
+            .map(Documentation::from_module)
+    };
+    let module = modules.last().expect("module");
+    hover(
+        module,
+        index_of(&module.code, text, nth),
+        &interfaces,
+        &documentation,
+    )
+}
+
+fn code_and_documentation(hover: Option<Hover>) -> Option<(String, Option<String>)> {
+    hover.map(|hover| {
+        (
+            hover.code,
+            hover.documentation.map(|doc| doc.trim().to_string()),
+        )
+    })
+}
+
+#[test]
+fn pattern_variables() {
+    let code = "pub fn main(x) {
+  case x {
+    #(first, _) -> first + 1
+  }
+}
+";
+    let hover = hover_at(&[("app", code)], "first", 0).expect("hover");
+    assert_eq!(hover.code, "Int");
+    assert_eq!(
+        Some(hover.location),
+        spans_of(code, "first").first().copied()
+    );
+}
+
+#[test]
+fn unused_pattern_variables() {
+    let code = "pub fn main(x: Result(List(String), Nil)) {
+  case x {
+    Ok([first, ..]) -> 1
+    Ok([]) | Error(_) -> 0
+  }
+}
+";
+    let hover = hover_at(&[("app", code)], "first", 0);
+    assert_eq!(code_and_documentation(hover), Some(("String".into(), None)));
+}
+
+#[test]
+fn shadowed_pattern_variables() {
+    let code = "pub fn main(x: #(Int, Float)) {
+  let #(count, ratio) = x
+  let count = ratio
+  count
+}
+";
+    let hover = hover_at(&[("app", code)], "count", 0);
+    assert_eq!(code_and_documentation(hover), Some(("Int".into(), None)));
+}
+
+#[test]
+fn function_arguments() {
+    let code = "pub fn main(name: String) {
+  Nil
+}
+";
+    let hover = hover_at(&[("app", code)], "name", 0);
+    assert_eq!(code_and_documentation(hover), Some(("String".into(), None)));
+}
+
+#[test]
+fn type_annotations() {
+    let code = "
+/// A pet
+pub type Pet {
+  Cat(name: String)
+  Dog
+}
+
+pub fn main(pet: Pet) {
+  pet
+}
+";
+    let hover = hover_at(&[("app", code)], "Pet", 1);
+    assert_eq!(
+        code_and_documentation(hover),
+        Some((
+            "pub type Pet {\n  Cat(name: String)\n  Dog\n}".into(),
+            Some("A pet".into())
+        ))
+    );
+}
+
+#[test]
+fn imported_type_annotations() {
+    let pets = "
+/// A pet
+pub type Pet(a) {
+  Cat(name: String, toy: a)
+  Dog
+}
+";
+    let code = "import pets
+
+pub fn main(pet: pets.Pet(Int)) {
+  pet
+}
+";
+    let hover = hover_at(&[("pets", pets), ("app", code)], "Pet", 0);
+    assert_eq!(
+        code_and_documentation(hover),
+        Some((
+            "pub type Pet(a) {\n  Cat(String, a)\n  Dog\n}".into(),
+            Some("A pet".into())
+        ))
+    );
+}
+
+#[test]
+fn imports() {
+    let one = "//// The first module
+//// of many
+
+pub const x = 1
+";
+    let code = "import one
+
+pub fn main() {
+  one.x
+}
+";
+    let hover = hover_at(&[("one", one), ("app", code)], "one", 0).expect("hover");
+    assert_eq!(hover.code, "import one");
+    assert_eq!(
+        hover.documentation.map(|doc| doc.to_string()),
+        Some(" The first module\n of many".into())
+    );
+    assert_eq!(Some(hover.location), spans_of(code, "one").first().copied());
+}
+
+#[test]
+fn constants() {
+    let one = "
+/// The answer
+pub const answer = 42
+";
+    let code = "import one
+
+const greeting = \"Hello\"
+
+pub fn main() {
+  #(greeting, one.answer)
+}
+";
+    let sources = [("one", one), ("app", code)];
+    assert_eq!(
+        code_and_documentation(hover_at(&sources, "greeting", 1)),
+        Some(("const greeting: String = \"Hello\"".into(), None))
+    );
+    assert_eq!(
+        code_and_documentation(hover_at(&sources, "answer", 0)),
+        Some((
+            "pub const answer: Int = 42".into(),
+            Some("The answer".into())
+        ))
+    );
+}
+
+#[test]
+fn record_constructors_in_patterns() {
+    let code = "
+pub type Pet {
+  /// A cat
+  Cat(name: String)
+  Dog
+}
+
+pub fn main(pet) {
+  case pet {
+    Cat(..) -> 1
+    Dog -> 2
+  }
+}
+";
+    assert_eq!(
+        code_and_documentation(hover_at(&[("app", code)], "Cat", 1)),
+        Some(("Cat(name: String)".into(), Some("A cat".into())))
+    );
+}
+
+#[test]
+fn imported_functions() {
+    let one = "
+/// Adds one
+pub fn inc(x: Int) -> Int {
+  x + 1
+}
+";
+    let code = "import one
+
+pub fn main() {
+  one.inc(1)
+}
+";
+    assert_eq!(
+        code_and_documentation(hover_at(&[("one", one), ("app", code)], "inc", 0)),
+        Some(("pub fn inc(Int) -> Int".into(), Some("Adds one".into())))
+    );
+}
+
+#[test]
+fn expressions() {
+    let code = "pub fn main() {
+  [1, 2]
+}
+";
+    let hover = hover_at(&[("app", code)], "[", 0);
+    assert_eq!(
+        code_and_documentation(hover),
+        Some(("List(Int)".into(), None))
+    );
+}
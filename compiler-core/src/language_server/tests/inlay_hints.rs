@@ -0,0 +1,72 @@
+use super::*;
+use crate::language_server::inlay_hints::inlay_hints;
+
+/// The hints for the module, each shown inserted into the line it is on.
+fn hinted_lines(code: &str) -> Vec<String> {
+    let module = compile_module(code);
+    let mut code = code.to_string();
+    for hint in inlay_hints(&module).iter().rev() {
+        let padding = if hint.padding_left { " " } else { "" };
+        code.insert_str(
+            hint.position as usize,
+            &format!("{padding}«{}»", hint.label),
+        );
+    }
+    code.lines()
+        .filter(|line| line.contains('«'))
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn hints_for_unannotated_lets_arguments_and_returns() {
+    let code = "
+pub fn main(x, y: Int, label z) {
+  let a = x + y
+  let b: Int = a
+  let #(c, _) = #(z, 1.0)
+  let f = fn(d) { d <> c }
+  let _ = f
+  b
+}
+";
+    assert_eq!(
+        hinted_lines(code),
+        vec![
+            "pub fn main(x«: Int», y: Int, label z«: String») «-> Int» {",
+            "  let a«: Int» = x + y",
+            "  let f«: fn(String) -> String» = fn(d«: String») «-> String» { d <> c }",
+        ]
+    );
+}
+
+#[test]
+fn no_hints_for_annotated_function() {
+    let code = "pub fn main(x: Int) -> Int { x }";
+    assert!(hinted_lines(code).is_empty());
+}
+
+#[test]
+fn hints_for_generic_function() {
+    let code = "pub fn id(x) { x }";
+    assert_eq!(hinted_lines(code), vec!["pub fn id(x«: a») «-> a» { x }"]);
+}
+
+#[test]
+fn hints_for_multiline_pipelines() {
+    let code = "
+fn double(x: Int) -> Int { x * 2 }
+fn show(x: Int) -> String { \"\" }
+
+pub fn main() -> String {
+  1
+  |> double
+  |> show
+}
+
+pub fn other() -> String {
+  1 |> double |> show
+}
+";
+    assert_eq!(hinted_lines(code), vec!["  1 «Int»", "  |> double «Int»"]);
+}
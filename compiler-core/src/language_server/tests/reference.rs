@@ -0,0 +1,274 @@
+use super::*;
+use crate::language_server::reference::{
+    find_references, reference_at, Reference, ReferenceKind, Symbol,
+};
+
+fn locations(references: Vec<Reference>) -> Vec<SrcSpan> {
+    references
+        .into_iter()
+        .map(|reference| reference.location)
+        .collect()
+}
+
+#[test]
+fn module_function_references() {
+    let code = "
+pub fn wibble() { 1 }
+
+pub fn main() {
+  wibble() + wibble()
+}
+";
+    let module = compile_module(code);
+    let symbol = Symbol::ModuleValue {
+        module: "app".into(),
+        name: "wibble".into(),
+    };
+    assert_eq!(
+        locations(find_references(&module, &symbol)),
+        spans_of(code, "wibble")
+    );
+}
+
+#[test]
+fn reference_at_definition() {
+    let code = "pub fn wibble() { 1 }";
+    let module = compile_module(code);
+    let reference = reference_at(&module, index_of(code, "wibble", 0)).expect("reference");
+    assert_eq!(reference.kind, ReferenceKind::Definition);
+    assert_eq!(
+        reference.symbol,
+        Symbol::ModuleValue {
+            module: "app".into(),
+            name: "wibble".into()
+        }
+    );
+}
+
+#[test]
+fn local_variable_references_respect_shadowing() {
+    let code = "
+pub fn main(x) {
+  let y = x + 1
+  let y = y + 2
+  y
+}
+";
+    let module = compile_module(code);
+    let first = reference_at(&module, index_of(code, "y", 0)).expect("first y");
+    let second = reference_at(&module, index_of(code, "y", 1)).expect("second y");
+    assert_ne!(first.symbol, second.symbol);
+    assert_eq!(
+        locations(find_references(&module, &first.symbol)),
+        vec![
+            SrcSpan::new(index_of(code, "y", 0), index_of(code, "y", 0) + 1),
+            SrcSpan::new(index_of(code, "y", 2), index_of(code, "y", 2) + 1),
+        ]
+    );
+    assert_eq!(find_references(&module, &second.symbol).len(), 2);
+}
+
+#[test]
+fn labelled_argument_references() {
+    let code = "
+pub fn add(to total: Int) {
+  total + 1
+}
+";
+    let module = compile_module(code);
+    let reference = reference_at(&module, index_of(code, "total", 1)).expect("total");
+    assert_eq!(
+        locations(find_references(&module, &reference.symbol)),
+        spans_of(code, "total")
+    );
+}
+
+#[test]
+fn case_pattern_and_guard_references() {
+    let code = "
+pub fn main(x) {
+  case x {
+    [first, ..] if first > 1 -> first
+    _ -> 0
+  }
+}
+";
+    let module = compile_module(code);
+    let reference = reference_at(&module, index_of(code, "first", 0)).expect("first");
+    assert_eq!(
+        locations(find_references(&module, &reference.symbol)),
+        spans_of(code, "first")
+    );
+}
+
+#[test]
+fn alternative_pattern_references() {
+    let code = "
+pub fn main(x) {
+  case x {
+    [item] | [_, item] -> item
+    _ -> 0
+  }
+}
+";
+    let module = compile_module(code);
+    let reference = reference_at(&module, index_of(code, "item", 2)).expect("item");
+    assert_eq!(
+        locations(find_references(&module, &reference.symbol)),
+        spans_of(code, "item")
+    );
+}
+
+#[test]
+fn type_and_constructor_references() {
+    let code = "
+pub type Wibble {
+  Wobble(name: String)
+}
+
+pub fn new(name: String) -> Wibble {
+  Wobble(name: name)
+}
+
+pub fn get(wibble: Wibble) -> String {
+  let Wobble(name: name) = wibble
+  name
+}
+";
+    let module = compile_module(code);
+    let type_ = Symbol::ModuleType {
+        module: "app".into(),
+        name: "Wibble".into(),
+    };
+    assert_eq!(
+        locations(find_references(&module, &type_)),
+        spans_of(code, "Wibble")
+    );
+    let constructor = Symbol::ModuleValue {
+        module: "app".into(),
+        name: "Wobble".into(),
+    };
+    assert_eq!(
+        locations(find_references(&module, &constructor)),
+        spans_of(code, "Wobble")
+    );
+}
+
+#[test]
+fn record_label_references() {
+    let code = "
+pub type Cat {
+  Cat(whiskers: Int)
+}
+
+pub fn main(cat: Cat) {
+  let Cat(whiskers: w) = cat
+  let cat = Cat(..cat, whiskers: w)
+  cat.whiskers
+}
+";
+    let module = compile_module(code);
+    let symbol = Symbol::RecordLabel {
+        module: "app".into(),
+        type_name: "Cat".into(),
+        label: "whiskers".into(),
+    };
+    assert_eq!(
+        locations(find_references(&module, &symbol)),
+        spans_of(code, "whiskers")
+    );
+}
+
+#[test]
+fn references_across_modules() {
+    let modules = compile_modules(&[
+        ("wibble", "pub type Thing { Thing }\npub const value = 1"),
+        (
+            "app",
+            "import wibble.{Thing, value as v}
+pub fn main() -> wibble.Thing {
+  let _ = wibble.value + v
+  Thing
+}",
+        ),
+    ]);
+    let app = modules.get(1).expect("app module");
+    let code = app.code.as_str();
+
+    let value = Symbol::ModuleValue {
+        module: "wibble".into(),
+        name: "value".into(),
+    };
+    let references = find_references(app, &value);
+    assert_eq!(
+        references
+            .iter()
+            .map(|reference| reference.kind)
+            .collect::<Vec<_>>(),
+        vec![
+            ReferenceKind::Import,
+            ReferenceKind::Usage,
+            ReferenceKind::Usage
+        ]
+    );
+    assert_eq!(
+        references.get(2).map(|reference| reference.location),
+        Some(SrcSpan::new(
+            index_of(code, " v\n", 0) + 1,
+            index_of(code, " v\n", 0) + 2
+        ))
+    );
+
+    let type_ = Symbol::ModuleType {
+        module: "wibble".into(),
+        name: "Thing".into(),
+    };
+    assert_eq!(find_references(app, &type_).len(), 2);
+}
+
+#[test]
+fn references_through_module_alias() {
+    let modules = compile_modules(&[
+        ("wibble", "pub type Thing { Thing }\npub const value = 1"),
+        (
+            "app",
+            "import wibble.{value as v} as w
+pub fn main(thing: w.Thing) -> w.Thing {
+  let _ = v
+  case thing {
+    w.Thing -> w.Thing
+  }
+}",
+        ),
+    ]);
+    let app = modules.get(1).expect("app module");
+    let code = app.code.as_str();
+    let spans = spans_of(code, "Thing");
+
+    let type_ = Symbol::ModuleType {
+        module: "wibble".into(),
+        name: "Thing".into(),
+    };
+    assert_eq!(
+        locations(find_references(app, &type_)),
+        spans.get(0..2).expect("type spans")
+    );
+
+    let constructor = Symbol::ModuleValue {
+        module: "wibble".into(),
+        name: "Thing".into(),
+    };
+    assert_eq!(
+        locations(find_references(app, &constructor)),
+        spans.get(2..4).expect("constructor spans")
+    );
+
+    let value = reference_at(app, index_of(code, "v\n", 0)).expect("value");
+    assert_eq!(
+        value.symbol,
+        Symbol::ModuleValue {
+            module: "wibble".into(),
+            name: "value".into()
+        }
+    );
+}
@@ -0,0 +1,177 @@
+use super::*;
+use crate::{
+    error::InvalidRenameReason,
+    language_server::{
+        reference::{reference_at, Symbol},
+        rename::{prepare_rename, rename, ModuleRename},
+    },
+    Error,
+};
+
+fn rename_reason(modules: &[Module], symbol: &Symbol, name: &str) -> Option<InvalidRenameReason> {
+    match rename(modules, symbol, name) {
+        Err(Error::InvalidRename { reason, .. }) => Some(reason),
+        _ => None,
+    }
+}
+
+#[test]
+fn rename_module_function() {
+    let code = "
+pub fn wibble() { 1 }
+
+pub fn main() {
+  wibble() + wibble()
+}
+";
+    let modules = vec![compile_module(code)];
+    let symbol = Symbol::ModuleValue {
+        module: "app".into(),
+        name: "wibble".into(),
+    };
+    assert_eq!(
+        rename(&modules, &symbol, "wobble").expect("rename"),
+        vec![ModuleRename {
+            module: "app".into(),
+            edits: spans_of(code, "wibble"),
+        }]
+    );
+}
+
+#[test]
+fn rename_keeps_import_aliases() {
+    let modules = compile_modules(&[
+        ("wibble", "pub fn wobble() { 1 }"),
+        (
+            "app",
+            "import wibble.{wobble as w, wobble}
+pub fn main() { w() + wobble() + wibble.wobble() }",
+        ),
+    ]);
+    let app = modules.get(1).expect("app module");
+    let symbol = Symbol::ModuleValue {
+        module: "wibble".into(),
+        name: "wobble".into(),
+    };
+    let renames = rename(&modules, &symbol, "wubble").expect("rename");
+    assert_eq!(
+        renames.get(1),
+        Some(&ModuleRename {
+            module: "app".into(),
+            edits: spans_of(&app.code, "wobble"),
+        })
+    );
+}
+
+#[test]
+fn rename_local_variable() {
+    let code = "
+pub fn main(x) {
+  let y = x + 1
+  y
+}
+";
+    let modules = vec![compile_module(code)];
+    let module = modules.first().expect("module");
+    let reference = reference_at(module, index_of(code, "y", 1)).expect("y");
+    assert_eq!(
+        rename(&modules, &reference.symbol, "z").expect("rename"),
+        vec![ModuleRename {
+            module: "app".into(),
+            edits: spans_of(code, "y"),
+        }]
+    );
+}
+
+#[test]
+fn rename_rejects_invalid_names() {
+    let modules = vec![compile_module(
+        "pub type Wibble { Wobble }\npub fn main() { Wobble }",
+    )];
+    let function = Symbol::ModuleValue {
+        module: "app".into(),
+        name: "main".into(),
+    };
+    let type_ = Symbol::ModuleType {
+        module: "app".into(),
+        name: "Wibble".into(),
+    };
+    let constructor = Symbol::ModuleValue {
+        module: "app".into(),
+        name: "Wobble".into(),
+    };
+    assert_eq!(
+        rename_reason(&modules, &function, "Main"),
+        Some(InvalidRenameReason::Format)
+    );
+    assert_eq!(
+        rename_reason(&modules, &type_, "wibble"),
+        Some(InvalidRenameReason::Format)
+    );
+    assert_eq!(
+        rename_reason(&modules, &constructor, "Wob_ble"),
+        Some(InvalidRenameReason::Format)
+    );
+    assert_eq!(
+        rename_reason(&modules, &function, "case"),
+        Some(InvalidRenameReason::GleamReservedWord)
+    );
+    assert_eq!(
+        rename_reason(&modules, &function, "receive"),
+        Some(InvalidRenameReason::ErlangReservedWord)
+    );
+}
+
+#[test]
+fn rename_rejects_names_in_use() {
+    let code = "
+pub fn wibble() { 1 }
+
+pub fn wobble() { 2 }
+
+pub fn main(x) {
+  let y = x + wibble()
+  y
+}
+";
+    let modules = vec![compile_module(code)];
+    let module = modules.first().expect("module");
+    let function = Symbol::ModuleValue {
+        module: "app".into(),
+        name: "wibble".into(),
+    };
+    assert_eq!(
+        rename_reason(&modules, &function, "wobble"),
+        Some(InvalidRenameReason::AlreadyInUse)
+    );
+    let variable = reference_at(module, index_of(code, "y", 0)).expect("y");
+    assert_eq!(
+        rename_reason(&modules, &variable.symbol, "x"),
+        Some(InvalidRenameReason::AlreadyInUse)
+    );
+    assert!(rename(&modules, &variable.symbol, "z").is_ok());
+}
+
+#[test]
+fn prepare_rename_only_for_project_symbols() {
+    let modules = compile_modules(&[
+        ("wibble", "pub fn wobble() { 1 }"),
+        (
+            "app",
+            "import wibble.{wobble as w}
+pub fn main() { w() + wibble.wobble() }",
+        ),
+    ]);
+    let app = modules.get(1).expect("app module");
+    let code = app.code.as_str();
+
+    let alias = reference_at(app, index_of(code, "w()", 0)).expect("alias");
+    assert_eq!(prepare_rename(&modules, app, &alias), None);
+
+    let qualified = reference_at(app, index_of(code, "wobble()", 0)).expect("qualified");
+    assert_eq!(
+        prepare_rename(&modules, app, &qualified),
+        Some(qualified.location)
+    );
+    assert_eq!(prepare_rename(modules.get(1), app, &qualified), None);
+}
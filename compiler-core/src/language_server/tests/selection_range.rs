@@ -0,0 +1,51 @@
+use super::*;
+use crate::language_server::selection_range::selection_ranges;
+
+/// The text of each selection range containing the nth occurrence of the
+/// text, from the innermost to the outermost.
+fn selections(code: &str, text: &str, nth: usize) -> Vec<String> {
+    let module = compile_module(code);
+    selection_ranges(&module, index_of(code, text, nth))
+        .into_iter()
+        .map(|range| {
+            code.get(range.start as usize..range.end as usize)
+                .expect("range in source code")
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn expands_through_expressions() {
+    let code = "pub fn main(x) {
+  let y = add(x, 2)
+  y
+}
+
+fn add(a, b) { a + b }
+";
+    assert_eq!(
+        selections(code, "x, 2", 0),
+        vec![
+            "x",
+            "add(x, 2)",
+            "let y = add(x, 2)",
+            "let y = add(x, 2)\n  y",
+            "pub fn main(x) {\n  let y = add(x, 2)\n  y\n}",
+        ]
+    );
+}
+
+#[test]
+fn expands_through_patterns() {
+    let code = "pub fn main(x) {
+  case x {
+    [first, ..] -> first
+    _ -> 0
+  }
+}
+";
+    let selections = selections(code, "first", 0);
+    assert_eq!(selections.first().map(String::as_str), Some("first"));
+    assert!(selections.contains(&"[first, ..] -> first".to_string()));
+}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use super::*;
+use crate::language_server::semantic_tokens::{
+    semantic_tokens, SemanticToken, TokenModifier, TokenType,
+};
+
+/// The semantic tokens of the last module, with the text of each.
+fn tokens(sources: &[(&str, &str)]) -> Vec<(String, TokenType, Vec<TokenModifier>)> {
+    let modules = compile_modules(sources);
+    let interfaces: HashMap<_, _> = modules
+        .iter()
+        .map(|module| (module.name.clone(), &module.ast.type_info))
+        .collect();
+    let module = modules.last().expect("compiled module");
+    semantic_tokens(module, &interfaces)
+        .into_iter()
+        .map(
+            |SemanticToken {
+                 location,
+                 type_,
+                 modifiers,
+             }| {
+                let text = module
+                    .code
+                    .get(location.start as usize..location.end as usize)
+                    .expect("token in source code");
+                (text.to_string(), type_, modifiers)
+            },
+        )
+        .collect()
+}
+
+/// The classification of the nth token with the given text.
+fn token(
+    tokens: &[(String, TokenType, Vec<TokenModifier>)],
+    text: &str,
+    nth: usize,
+) -> (TokenType, Vec<TokenModifier>) {
+    tokens
+        .iter()
+        .filter(|(token, _, _)| token == text)
+        .nth(nth)
+        .map(|(_, type_, modifiers)| (*type_, modifiers.clone()))
+        .expect("token with text")
+}
+
+#[test]
+fn local_variables_and_module_functions() {
+    let code = "
+fn add(x, y) { x + y }
+
+pub fn main() {
+  let total = add(1, 2)
+  let _ignored = total
+  total
+}
+";
+    let tokens = tokens(&[("app", code)]);
+    assert_eq!(
+        token(&tokens, "add", 0),
+        (TokenType::Function, vec![TokenModifier::Declaration])
+    );
+    assert_eq!(
+        token(&tokens, "x", 0),
+        (TokenType::Parameter, vec![TokenModifier::Declaration])
+    );
+    assert_eq!(token(&tokens, "x", 1), (TokenType::Parameter, vec![]));
+    assert_eq!(token(&tokens, "+", 0), (TokenType::Operator, vec![]));
+    assert_eq!(token(&tokens, "add", 1), (TokenType::Function, vec![]));
+    assert_eq!(token(&tokens, "1", 0), (TokenType::Number, vec![]));
+    assert_eq!(
+        token(&tokens, "total", 0),
+        (TokenType::Variable, vec![TokenModifier::Declaration])
+    );
+    assert_eq!(token(&tokens, "total", 2), (TokenType::Variable, vec![]));
+    assert_eq!(
+        token(&tokens, "_ignored", 0),
+        (
+            TokenType::Variable,
+            vec![TokenModifier::Declaration, TokenModifier::Discarded]
+        )
+    );
+    assert_eq!(token(&tokens, "let", 0), (TokenType::Keyword, vec![]));
+}
+
+#[test]
+fn modules_constructors_and_labels() {
+    let other = "
+pub type Pet {
+  Cat(name: String)
+}
+
+pub fn name(pet: Pet) -> String { pet.name }
+";
+    let code = "
+import other.{Cat}
+
+pub fn main() {
+  let pet = other.Cat(name: \"Nubi\")
+  case pet {
+    Cat(name: n) -> other.name(Cat(..pet, name: n))
+  }
+}
+";
+    let tokens = tokens(&[("other", other), ("app", code)]);
+    assert_eq!(token(&tokens, "other", 0), (TokenType::Namespace, vec![]));
+    assert_eq!(token(&tokens, "Cat", 0), (TokenType::Constructor, vec![]));
+    assert_eq!(token(&tokens, "other", 1), (TokenType::Namespace, vec![]));
+    assert_eq!(token(&tokens, "Cat", 1), (TokenType::Constructor, vec![]));
+    assert_eq!(token(&tokens, "name", 0), (TokenType::Property, vec![]));
+    assert_eq!(token(&tokens, "\"Nubi\"", 0), (TokenType::String, vec![]));
+    assert_eq!(token(&tokens, "Cat", 2), (TokenType::Constructor, vec![]));
+    assert_eq!(token(&tokens, "name", 1), (TokenType::Property, vec![]));
+    assert_eq!(token(&tokens, "name", 2), (TokenType::Function, vec![]));
+    assert_eq!(token(&tokens, "name", 3), (TokenType::Property, vec![]));
+}
+
+#[test]
+fn types_externals_and_comments() {
+    let code = "
+//// A module
+
+/// Reverses a list
+pub external fn reverse(List(a)) -> List(a) = \"lists\" \"reverse\"
+
+pub const size: Int = 1
+
+pub fn main() {
+  // Not done yet
+  reverse([size])
+  todo
+}
+";
+    let tokens = tokens(&[("app", code)]);
+    assert_eq!(
+        token(&tokens, "//// A module", 0),
+        (TokenType::Comment, vec![TokenModifier::Documentation])
+    );
+    assert_eq!(
+        token(&tokens, "/// Reverses a list", 0),
+        (TokenType::Comment, vec![TokenModifier::Documentation])
+    );
+    assert_eq!(
+        token(&tokens, "reverse", 0),
+        (
+            TokenType::Function,
+            vec![TokenModifier::Declaration, TokenModifier::External]
+        )
+    );
+    assert_eq!(token(&tokens, "List", 0), (TokenType::Type, vec![]));
+    assert_eq!(token(&tokens, "a", 0), (TokenType::TypeParameter, vec![]));
+    assert_eq!(
+        token(&tokens, "size", 0),
+        (
+            TokenType::Variable,
+            vec![TokenModifier::Declaration, TokenModifier::Readonly]
+        )
+    );
+    assert_eq!(token(&tokens, "Int", 0), (TokenType::Type, vec![]));
+    assert_eq!(
+        token(&tokens, "// Not done yet", 0),
+        (TokenType::Comment, vec![])
+    );
+    assert_eq!(
+        token(&tokens, "reverse", 1),
+        (TokenType::Function, vec![TokenModifier::External])
+    );
+    assert_eq!(
+        token(&tokens, "size", 1),
+        (TokenType::Variable, vec![TokenModifier::Readonly])
+    );
+    assert_eq!(
+        token(&tokens, "todo", 0),
+        (TokenType::Keyword, vec![TokenModifier::Deprecated])
+    );
+}
@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use super::*;
+use crate::language_server::{
+    completion::Documentation,
+    signature_help::{signature_help, SignatureHelp},
+};
+
+/// Gets the signature help at the position of the last `|` in the source code,
+/// which is the source code of the last of the compiled modules as edited
+/// since it was compiled.
+fn help(modules: &[Module], src: &str) -> Option<SignatureHelp> {
+    let cursor = src.rfind('|').expect("cursor");
+    let src = format!("{}{}", &src[..cursor], &src[cursor + 1..]);
+    let cursor = cursor as u32;
+    let prelude = build_prelude(&UniqueIdGenerator::new());
+    let mut interfaces: HashMap<_, _> = modules
+        .iter()
+        .map(|module| (module.name.clone(), &module.ast.type_info))
+        .collect();
+    let _ = interfaces.insert("gleam".into(), &prelude);
+    let documentation = modules
+        .iter()
+        .map(|module| (module.name.clone(), Documentation::parse(&module.code)))
+        .collect();
+    let module = modules.last().expect("module");
+    signature_help(module, &src, cursor, &interfaces, &documentation)
+}
+
+fn parameters(help: &SignatureHelp) -> Vec<&str> {
+    help.parameters
+        .iter()
+        .map(|(start, end)| help.label.get(*start..*end).expect("parameter"))
+        .collect()
+}
+
+#[test]
+fn signature_of_module_function() {
+    let code = "
+/// Adds things up
+fn add(extra: Int, to total: Int, with label: String) -> Int {
+  total + extra
+}
+
+pub fn main() {
+  add(1, 2, \"\")
+}
+";
+    let mut module = compile_module(code);
+    module.attach_doc_and_module_comments();
+    let modules = vec![module];
+    let found = help(&modules, &code.replace("add(1, 2", "add(1, |2")).expect("help");
+    assert_eq!(
+        found.label,
+        "add(extra: Int, to total: Int, with label: String) -> Int"
+    );
+    assert_eq!(
+        parameters(&found),
+        vec!["extra: Int", "to total: Int", "with label: String"]
+    );
+    assert_eq!(found.active_parameter, Some(1));
+    assert_eq!(found.documentation.as_deref(), Some(" Adds things up"));
+}
+
+#[test]
+fn active_parameter_of_labelled_and_piped_arguments() {
+    let code = "
+fn go(a: Int, b b: Int, c c: Int) -> Int {
+  a + b + c
+}
+
+pub fn main() {
+  go(1, 2, 3)
+}
+";
+    let modules = vec![compile_module(code)];
+    let active = |src: &str| help(&modules, &code.replace("go(1, 2, 3)", src))?.active_parameter;
+    assert_eq!(active("go(c: 1, |"), Some(0));
+    assert_eq!(active("go(1, c: |"), Some(2));
+    assert_eq!(active("go(1, c: 3, |"), Some(1));
+    assert_eq!(active("1 |> go(|"), Some(1));
+    assert_eq!(active("go(1, [1, 2, |"), Some(1));
+    assert_eq!(active("go(1, #(1, 2), |"), Some(2));
+    assert_eq!(active("go(1, \"(,\", |"), Some(2));
+    assert_eq!(active("go(1, \"\\\"(,\", |"), Some(2));
+    assert_eq!(active("go(1, // (,\n |"), Some(1));
+    assert_eq!(active("go(1, 2, 3)|"), None);
+}
+
+#[test]
+fn signature_of_imported_function_and_constructor() {
+    let modules = compile_modules(&[
+        (
+            "wibble",
+            "/// Wobbles
+pub fn wobble(name: String, times times: Int) -> Nil { Nil }
+pub type Cat { Cat(name: String, lives: Int) }",
+        ),
+        ("app", "import wibble.{Cat}\npub fn main() { 1 }"),
+    ]);
+
+    let found = help(
+        &modules,
+        "import wibble.{Cat}\npub fn main() { wibble.wobble(1, | }",
+    )
+    .expect("help");
+    assert_eq!(found.label, "wobble(String, times: Int) -> Nil");
+    assert_eq!(found.active_parameter, Some(1));
+    assert_eq!(found.documentation.as_deref(), Some(" Wobbles"));
+
+    let found = help(&modules, "import wibble.{Cat}\npub fn main() { Cat(| }").expect("help");
+    assert_eq!(found.label, "Cat(name: String, lives: Int) -> Cat");
+
+    let found = help(&modules, "import wibble.{Cat}\npub fn main() { Ok(| }").expect("help");
+    assert_eq!(found.label, "Ok(a) -> Result(a, b)");
+}
+
+#[test]
+fn signature_of_local_function() {
+    let code = "
+pub fn main() {
+  let f = fn(x: Int, y: Float) { y }
+  f(1, 2.0)
+}
+";
+    let modules = vec![compile_module(code)];
+    let found = help(&modules, &code.replace("f(1, 2.0)", "f(|")).expect("help");
+    assert_eq!(found.label, "f(Int, Float) -> Float");
+    assert_eq!(found.active_parameter, Some(0));
+}
+
+#[test]
+fn active_parameter_of_use_callback() {
+    let code = "
+fn go(a: Int, b: Int, f: fn(Int) -> Int) -> Int {
+  f(a + b)
+}
+
+pub fn main() {
+  use x <- go(1, 2)
+  x
+}
+";
+    let modules = vec![compile_module(code)];
+    let active = |src: &str| help(&modules, &code.replace("go(1, 2)", src))?.active_parameter;
+    assert_eq!(active("go(|"), Some(0));
+    assert_eq!(active("go(1, |"), Some(1));
+    assert_eq!(active("go(1, 2, |"), None);
+}
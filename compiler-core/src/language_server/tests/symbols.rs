@@ -0,0 +1,128 @@
+use super::*;
+use crate::language_server::symbols::{
+    document_symbols, workspace_symbols, DocumentSymbol, SymbolKind,
+};
+
+fn outline(symbols: &[DocumentSymbol]) -> Vec<(String, SymbolKind, Option<String>)> {
+    symbols
+        .iter()
+        .flat_map(|symbol| {
+            std::iter::once((symbol.name.to_string(), symbol.kind, symbol.detail.clone()))
+                .chain(outline(&symbol.children))
+        })
+        .collect()
+}
+
+#[test]
+fn document_symbols_outline() {
+    let code = r#"
+import gleam
+
+pub type Cat {
+  Cat(name: String, Int)
+  Kitten
+}
+
+type Name =
+  String
+
+pub external type Dog
+
+pub external fn bark(Dog) -> Nil = "dog" "bark"
+
+const lives = 9
+
+pub fn main(cat: Cat) -> Int {
+  lives
+}
+"#;
+    let module = compile_module(code);
+    assert_eq!(
+        outline(&document_symbols(&module)),
+        vec![
+            ("Cat".into(), SymbolKind::Type, None),
+            ("Cat".into(), SymbolKind::Constructor, None),
+            ("name".into(), SymbolKind::Field, Some("String".into())),
+            ("Kitten".into(), SymbolKind::Constructor, None),
+            ("Name".into(), SymbolKind::Type, None),
+            ("Dog".into(), SymbolKind::Type, None),
+            (
+                "bark".into(),
+                SymbolKind::Function,
+                Some("fn(Dog) -> Nil".into())
+            ),
+            ("lives".into(), SymbolKind::Constant, Some("Int".into())),
+            (
+                "main".into(),
+                SymbolKind::Function,
+                Some("fn(Cat) -> Int".into())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn document_symbol_ranges() {
+    let code = "pub type Cat {
+  Cat(name: String)
+}
+
+const lives = 9
+
+pub fn main() {
+  lives
+}
+";
+    let module = compile_module(code);
+    let symbols = document_symbols(&module);
+    let span = |start: &str, end: &str| {
+        let start = index_of(code, start, 0);
+        let end = index_of(code, end, 0) + end.len() as u32;
+        SrcSpan::new(start, end)
+    };
+
+    let type_ = symbols.first().expect("type");
+    assert_eq!(type_.location, span("pub type", "\n}"));
+    assert_eq!(type_.name_location, span("Cat {", "Cat"));
+    let constructor = type_.children.first().expect("constructor");
+    assert_eq!(constructor.location, span("Cat(", "String)"));
+    let field = constructor.children.first().expect("field");
+    assert_eq!(field.location, span("name", "String"));
+    assert_eq!(field.name_location, span("name", "name"));
+
+    let constant = symbols.get(1).expect("constant");
+    assert_eq!(constant.location, span("lives =", "9"));
+
+    let function = symbols.get(2).expect("function");
+    assert_eq!(function.location, span("pub fn", "lives\n}"));
+    assert_eq!(function.name_location, span("main", "main"));
+}
+
+#[test]
+fn workspace_symbols_fuzzy_search() {
+    let modules = compile_modules(&[
+        (
+            "wibble",
+            "pub fn wobble() { 1 }
+pub fn other_wobble() { 1 }
+pub fn woo() { 1 }
+fn private_wobble() { 1 }
+pub type Wobbler { Wobbler }",
+        ),
+        ("app", "pub const wobbles = 1"),
+    ]);
+    let found = workspace_symbols(modules.iter().map(|module| &module.ast.type_info), "wbl");
+    assert_eq!(
+        found
+            .iter()
+            .map(|symbol| (symbol.name.as_str(), symbol.kind, symbol.module.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("wobble", SymbolKind::Function, "wibble"),
+            ("Wobbler", SymbolKind::Type, "wibble"),
+            ("Wobbler", SymbolKind::Constructor, "wibble"),
+            ("wobbles", SymbolKind::Constant, "app"),
+            ("other_wobble", SymbolKind::Function, "wibble"),
+        ]
+    );
+}
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LineNumbers {
     line_starts: Vec<u32>,
     length: u32,
@@ -52,7 +52,7 @@
 pub mod error;
 pub mod extra;
 pub mod lexer;
-mod token;
+pub mod token;
 
 use crate::ast::{
     Arg, ArgNames, AssignName, AssignmentKind, BinOp, BitStringSegment, BitStringSegmentOption,
@@ -79,10 +79,49 @@ mod tests;
 // Public Interface
 //
 pub fn parse_module(src: &str) -> Result<(UntypedModule, ModuleExtra), ParseError> {
+    let parsed = parse_module_with_recovery(src);
+    // A lexical error is returned in preference to any syntax error, as is
+    // done when parsing stops at the first error.
+    let index = parsed
+        .errors
+        .iter()
+        .position(|error| matches!(error.error, ParseErrorType::LexError { .. }))
+        .unwrap_or(0);
+    match parsed.errors.into_iter().nth(index) {
+        Some(error) => Err(error),
+        None => Ok((parsed.module, parsed.extra)),
+    }
+}
+
+/// A module parsed by `parse_module_with_recovery`.
+///
+#[derive(Debug)]
+pub struct Parsed {
+    /// The statements that could be parsed, which is all of them if there
+    /// are no errors.
+    pub module: UntypedModule,
+    pub extra: ModuleExtra,
+    /// The syntax errors in the order they were found.
+    pub errors: Vec<ParseError>,
+}
+
+/// Parses a module, carrying on after a syntax error from the start of the
+/// next top level statement so that all the syntax errors of the module can
+/// be reported at once.
+///
+/// Parsing stops at the first lexical error, as the tokens after it cannot be
+/// trusted.
+///
+pub fn parse_module_with_recovery(src: &str) -> Parsed {
     let lex = lexer::make_tokenizer(src);
     let mut parser = Parser::new(lex);
-    let module = parser.parse_module()?;
-    Ok((module, parser.extra))
+    let module = parser.parse_module();
+    let errors = parser.take_errors();
+    Parsed {
+        module,
+        extra: parser.extra,
+        errors,
+    }
 }
 
 //
@@ -93,7 +132,18 @@ pub fn parse_expression_sequence(src: &str) -> Result<UntypedExpr, ParseError> {
     let lex = lexer::make_tokenizer(src);
     let mut parser = Parser::new(lex);
     let expr = parser.parse_expression_seq();
-    let expr = parser.ensure_no_errors_or_remaining_input(expr)?;
+    let expr = parser.ensure_no_errors(expr)?;
+    if let Some((start, _, end)) = parser.next_tok() {
+        // there are still more tokens
+        let expected = vec!["An import, const, type, if block, or function.".into()];
+        return parse_error(
+            ParseErrorType::UnexpectedToken {
+                expected,
+                hint: None,
+            },
+            SrcSpan { start, end },
+        );
+    }
     if let Some((e, _)) = expr {
         Ok(e)
     } else {
@@ -108,8 +158,14 @@ pub fn parse_expression_sequence(src: &str) -> Result<UntypedExpr, ParseError> {
 pub struct Parser<T: Iterator<Item = LexResult>> {
     tokens: T,
     lex_errors: Vec<LexicalError>,
+    /// The syntax errors recovered from while parsing a module.
+    errors: Vec<ParseError>,
     tok0: Option<Spanned>,
     tok1: Option<Spanned>,
+    /// The number of unclosed brackets in the tokens read so far, including
+    /// `tok0` and `tok1`, used to find the start of the next statement when
+    /// recovering from an error.
+    depth: i32,
     extra: ModuleExtra,
 }
 impl<T> Parser<T>
@@ -120,8 +176,10 @@ where
         let mut parser = Parser {
             tokens: input,
             lex_errors: vec![],
+            errors: vec![],
             tok0: None,
             tok1: None,
+            depth: 0,
             extra: ModuleExtra::new(),
         };
         let _ = parser.next_tok();
@@ -129,40 +187,109 @@ where
         parser
     }
 
-    fn parse_module(&mut self) -> Result<UntypedModule, ParseError> {
-        let statements = Parser::series_of(self, &Parser::parse_target_group, None);
-        let statements = self.ensure_no_errors_or_remaining_input(statements)?;
-        Ok(Module {
+    fn parse_module(&mut self) -> UntypedModule {
+        let mut statements = vec![];
+        while self.tok0.is_some() && self.lex_errors.is_empty() {
+            let start = self.statement_start();
+            match self.parse_target_group() {
+                Ok(Some(group)) => statements.push(group),
+
+                // There are still more tokens but they are not a statement
+                Ok(None) => {
+                    if let Some((start, _, end)) = self.tok0 {
+                        let expected =
+                            vec!["An import, const, type, if block, or function.".into()];
+                        self.recovered(ParseError {
+                            error: ParseErrorType::UnexpectedToken {
+                                expected,
+                                hint: None,
+                            },
+                            location: SrcSpan { start, end },
+                        });
+                    }
+                    self.skip_to_next_statement(start);
+                }
+
+                Err(error) => {
+                    self.recovered(error);
+                    self.skip_to_next_statement(start);
+                }
+            }
+        }
+
+        Module {
             name: "".into(),
             documentation: vec![],
             type_info: (),
             statements,
-        })
+        }
     }
 
-    // The way the parser is currenly implemented, it cannot exit immediately while advancing
-    // the token stream upon seing a LexError. That is to avoid having to put `?` all over the
-    // place and instead we collect LexErrors in `self.lex_errors` and attempt to continue parsing.
-    // Once parsing has returned we want to surface an error in the order:
-    // 1) LexError, 2) ParseError, 3) More Tokens Left
-    fn ensure_no_errors_or_remaining_input<A>(
-        &mut self,
-        parse_result: Result<A, ParseError>,
-    ) -> Result<A, ParseError> {
-        let parse_result = self.ensure_no_errors(parse_result)?;
-        if let Some((start, _, end)) = self.next_tok() {
-            // there are still more tokens
-            let expected = vec!["An import, const, type, if block, or function.".into()];
-            return parse_error(
-                ParseErrorType::UnexpectedToken {
-                    expected,
-                    hint: None,
+    /// Records an error that parsing will carry on after. Errors found after
+    /// a lexical error are discarded as they are likely caused by it.
+    fn recovered(&mut self, error: ParseError) {
+        if self.lex_errors.is_empty() {
+            self.errors.push(error);
+        }
+    }
+
+    /// The errors found while parsing a module, with the first lexical error
+    /// after any syntax errors found before it.
+    fn take_errors(&mut self) -> Vec<ParseError> {
+        let mut errors = std::mem::take(&mut self.errors);
+        if let Some(error) = self.lex_errors.first() {
+            errors.push(ParseError {
+                location: error.location,
+                error: ParseErrorType::LexError {
+                    error: error.clone(),
                 },
-                SrcSpan { start, end },
-            );
+            });
+        }
+        errors
+    }
+
+    /// The position of the current token and the bracket depth at it, from
+    /// which a statement is about to be parsed.
+    fn statement_start(&self) -> (Option<u32>, i32) {
+        let depth = self.depth
+            - self
+                .tok0
+                .as_ref()
+                .map_or(0, |(_, token, _)| bracket_depth(token))
+            - self
+                .tok1
+                .as_ref()
+                .map_or(0, |(_, token, _)| bracket_depth(token));
+        (self.tok0.as_ref().map(|(start, _, _)| *start), depth)
+    }
+
+    /// Skips the tokens of a statement that could not be parsed, stopping at
+    /// the next token that could start a statement, or the `}` closing the
+    /// target group it is in.
+    ///
+    /// `fn` and `}` are only taken to be these when at the same bracket depth
+    /// as the statement, while keywords that can only start a statement are
+    /// taken to be one wherever they are so that unclosed brackets do not
+    /// stop parsing.
+    fn skip_to_next_statement(&mut self, (position, depth): (Option<u32>, i32)) {
+        // Always move past the token the statement started at, so that
+        // parsing makes progress.
+        let mut previous = None;
+        if self.statement_start().0 == position {
+            previous = self.next_tok();
+        }
+        while self.lex_errors.is_empty() {
+            let (_, token_depth) = self.statement_start();
+            let after_arrow = matches!(previous, Some((_, Token::RArrow, _)));
+            match &self.tok0 {
+                None if self.tok1.is_none() => return,
+                Some((_, Token::Fn, _)) if token_depth == depth && !after_arrow => return,
+                Some((_, Token::RightBrace, _)) if token_depth == depth && depth > 0 => return,
+                Some((_, token, _)) if only_starts_statement(token) => return,
+                _ => (),
+            }
+            previous = self.next_tok();
         }
-        // no errors
-        Ok(parse_result)
     }
 
     // The way the parser is currenly implemented, it cannot exit immediately
@@ -208,9 +335,22 @@ where
         }
     }
 
+    /// Parses statements until a token that cannot start one, recovering
+    /// from any errors in them.
     fn expect_statements(&mut self) -> Result<Vec<UntypedStatement>, ParseError> {
-        let statements = Parser::series_of(self, &Parser::parse_statement, None);
-        self.ensure_no_errors(statements)
+        let mut statements = vec![];
+        while self.lex_errors.is_empty() {
+            let start = self.statement_start();
+            match self.parse_statement() {
+                Ok(Some(statement)) => statements.push(statement),
+                Ok(None) => break,
+                Err(error) => {
+                    self.recovered(error);
+                    self.skip_to_next_statement(start);
+                }
+            }
+        }
+        self.ensure_no_errors(Ok(statements))
     }
 
     fn parse_statement(&mut self) -> Result<Option<UntypedStatement>, ParseError> {
@@ -1159,6 +1299,8 @@ where
     // a
     // 1
     // a.1
+    // a.field
+    // !a
     // { a }
     // a || b
     // a < b || b < c
@@ -1166,43 +1308,69 @@ where
         match self.tok0.take() {
             Some((start, Token::Name { name }, end)) => {
                 let _ = self.next_tok();
-                if let Some((dot_s, _)) = self.maybe_one(&Token::Dot) {
-                    match self.next_tok() {
+                let mut guard = ClauseGuard::Var {
+                    location: SrcSpan { start, end },
+                    type_: (),
+                    name,
+                };
+
+                // Tuple indexes and field accesses can be chained
+                while let Some((dot_s, _)) = self.maybe_one(&Token::Dot) {
+                    guard = match self.next_tok() {
                         Some((_, Token::Int { value }, int_e)) => {
                             let v = value.replace('_', "");
                             if let Ok(index) = u64::from_str(&v) {
-                                Ok(Some(ClauseGuard::TupleIndex {
+                                ClauseGuard::TupleIndex {
                                     location: SrcSpan {
                                         start: dot_s,
                                         end: int_e,
                                     },
                                     index,
                                     type_: (),
-                                    tuple: Box::new(ClauseGuard::Var {
-                                        location: SrcSpan { start, end },
-                                        type_: (),
-                                        name,
-                                    }),
-                                }))
+                                    tuple: Box::new(guard),
+                                }
                             } else {
-                                parse_error(
+                                return parse_error(
                                     ParseErrorType::InvalidTupleAccess,
                                     SrcSpan { start, end },
-                                )
+                                );
                             }
                         }
 
+                        Some((_, Token::Name { name: label }, end)) => ClauseGuard::FieldAccess {
+                            location: SrcSpan { start, end },
+                            index: None,
+                            label,
+                            type_: (),
+                            container: Box::new(guard),
+                        },
+
                         Some((start, _, end)) => {
-                            parse_error(ParseErrorType::InvalidTupleAccess, SrcSpan { start, end })
+                            return parse_error(
+                                ParseErrorType::InvalidTupleAccess,
+                                SrcSpan { start, end },
+                            )
                         }
-                        _ => self.next_tok_unexpected(vec!["A positive integer".into()]),
-                    }
-                } else {
-                    Ok(Some(ClauseGuard::Var {
-                        location: SrcSpan { start, end },
-                        type_: (),
-                        name,
-                    }))
+                        _ => {
+                            return self.next_tok_unexpected(vec![
+                                "A positive integer or a field name.".into(),
+                            ])
+                        }
+                    };
+                }
+                Ok(Some(guard))
+            }
+            Some((start, Token::Bang, _)) => {
+                let _ = self.next_tok();
+                match self.parse_case_clause_guard_unit()? {
+                    Some(expression) => Ok(Some(ClauseGuard::Not {
+                        location: SrcSpan {
+                            start,
+                            end: expression.location().end,
+                        },
+                        expression: Box::new(expression),
+                    })),
+                    None => self.next_tok_unexpected(vec!["A guard expression".into()]),
                 }
             }
             Some((_, Token::LeftBrace, _)) => {
@@ -1357,9 +1525,14 @@ where
         is_anon: bool,
     ) -> Result<Option<UntypedStatement>, ParseError> {
         let mut name = SmolStr::new("");
+        let mut name_location = SrcSpan { start, end: start };
         if !is_anon {
-            let (_, n, _) = self.expect_name()?;
+            let (name_start, n, name_end) = self.expect_name()?;
             name = n;
+            name_location = SrcSpan {
+                start: name_start,
+                end: name_end,
+            };
         }
         let _ = self.expect_one(&Token::LeftParen)?;
         let args = Parser::series_of(
@@ -1390,6 +1563,7 @@ where
             end_position: rbr_e - 1,
             public,
             name,
+            name_location,
             arguments: args,
             body,
             return_type: (),
@@ -1407,7 +1581,7 @@ where
         start: u32,
         public: bool,
     ) -> Result<Option<UntypedStatement>, ParseError> {
-        let (_, name, _) = self.expect_name()?;
+        let (name_start, name, name_end) = self.expect_name()?;
         let _ = self.expect_one(&Token::LeftParen)?;
         let args = Parser::series_of(self, &Parser::parse_external_fn_param, Some(&Token::Comma))?;
         let _ = self.expect_one(&Token::RightParen)?;
@@ -1423,6 +1597,10 @@ where
                 location: SrcSpan { start, end },
                 public,
                 name,
+                name_location: SrcSpan {
+                    start: name_start,
+                    end: name_end,
+                },
                 arguments: args,
                 module,
                 fun,
@@ -1526,7 +1704,7 @@ where
             // labeled name
             (
                 Some((start, Token::Name { name: label }, tok0_end)),
-                Some((_, Token::Name { name }, end)),
+                Some((name_start, Token::Name { name }, end)),
             ) => {
                 if is_anon {
                     return parse_error(
@@ -1540,7 +1718,19 @@ where
 
                 let _ = self.next_tok();
                 let _ = self.next_tok();
-                (start, ArgNames::NamedLabelled { name, label }, end)
+                let name_location = SrcSpan {
+                    start: name_start,
+                    end,
+                };
+                (
+                    start,
+                    ArgNames::NamedLabelled {
+                        name,
+                        label,
+                        name_location,
+                    },
+                    end,
+                )
             }
             // name
             (Some((start, Token::Name { name }, end)), t1) => {
@@ -1634,11 +1824,12 @@ where
         start: u32,
         public: bool,
     ) -> Result<Option<UntypedStatement>, ParseError> {
-        let (_, name, args, end) = self.expect_type_name()?;
+        let (name_location, name, args, end) = self.expect_type_name()?;
         Ok(Some(Statement::ExternalType(ExternalType {
             location: SrcSpan { start, end },
             public,
             name,
+            name_location,
             arguments: args,
             doc: None,
         })))
@@ -1659,7 +1850,7 @@ where
         public: bool,
         opaque: bool,
     ) -> Result<Option<UntypedStatement>, ParseError> {
-        let (_, name, parameters, end) = self.expect_type_name()?;
+        let (name_location, name, parameters, end) = self.expect_type_name()?;
         if self.maybe_one(&Token::LeftBrace).is_some() {
             // Custom Type
             let constructors = Parser::series_of(
@@ -1681,16 +1872,18 @@ where
                 // No separator
                 None,
             )?;
-            let _ = self.expect_one(&Token::RightBrace)?;
+            let (_, rbr_e) = self.expect_one(&Token::RightBrace)?;
             if constructors.is_empty() {
                 parse_error(ParseErrorType::NoConstructors, SrcSpan { start, end })
             } else {
                 Ok(Some(Statement::CustomType(CustomType {
                     doc: None,
                     location: SrcSpan { start, end },
+                    end_position: rbr_e - 1,
                     public,
                     opaque,
                     name,
+                    name_location,
                     parameters,
                     constructors,
                     typed_parameters: vec![],
@@ -1709,6 +1902,7 @@ where
                         },
                         public,
                         alias: name,
+                        name_location,
                         parameters,
                         type_ast: t,
                         type_: (),
@@ -1734,16 +1928,17 @@ where
     // examples:
     //   A
     //   A(one, two)
-    fn expect_type_name(&mut self) -> Result<(u32, SmolStr, Vec<SmolStr>, u32), ParseError> {
+    fn expect_type_name(&mut self) -> Result<(SrcSpan, SmolStr, Vec<SmolStr>, u32), ParseError> {
         let (start, upname, end) = self.expect_upname()?;
+        let name_location = SrcSpan { start, end };
         if self.maybe_one(&Token::LeftParen).is_some() {
             let args =
                 Parser::series_of(self, &|p| Ok(Parser::maybe_name(p)), Some(&Token::Comma))?;
             let (_, par_e) = self.expect_one(&Token::RightParen)?;
             let args2 = args.into_iter().map(|(_, a, _)| a).collect();
-            Ok((start, upname, args2, par_e))
+            Ok((name_location, upname, args2, par_e))
         } else {
-            Ok((start, upname, vec![], end))
+            Ok((name_location, upname, vec![], end))
         }
     }
 
@@ -2087,6 +2282,7 @@ where
                 location: SrcSpan { start, end },
                 public,
                 name,
+                name_location: SrcSpan { start, end },
                 annotation,
                 value: Box::new(value),
                 type_: (),
@@ -2654,9 +2850,16 @@ where
         }
     }
 
-    // Error on the next token or EOF
+    // Error on the next token or EOF. Keywords that can only start a
+    // statement are not consumed so that parsing can recover from them.
     fn next_tok_unexpected<A>(&mut self, expected: Vec<SmolStr>) -> Result<A, ParseError> {
-        match self.next_tok() {
+        let token = match &self.tok0 {
+            Some((start, token, end)) if only_starts_statement(token) => {
+                Some((*start, token.clone(), *end))
+            }
+            _ => self.next_tok(),
+        };
+        match token {
             None => parse_error(ParseErrorType::UnexpectedEof, SrcSpan { start: 0, end: 0 }),
 
             Some((start, _, end)) => parse_error(
@@ -2698,6 +2901,7 @@ where
                 }
 
                 Some(Ok(tok)) => {
+                    self.depth += bracket_depth(&tok.1);
                     nxt = Some(tok);
                     break;
                 }
@@ -2713,6 +2917,25 @@ where
     }
 }
 
+/// How much a token changes the depth of nested brackets.
+fn bracket_depth(token: &Token) -> i32 {
+    match token {
+        Token::LeftParen | Token::LeftSquare | Token::LeftBrace | Token::LtLt => 1,
+        Token::RightParen | Token::RightSquare | Token::RightBrace | Token::GtGt => -1,
+        _ => 0,
+    }
+}
+
+/// Whether a token is a keyword that can only be the first of a top level
+/// statement. `fn` is not one as it also starts anonymous functions and
+/// function types.
+fn only_starts_statement(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Import | Token::Const | Token::Pub | Token::External | Token::Type | Token::If
+    )
+}
+
 fn concat_pattern_variable_left_hand_side_error<T>(start: u32, end: u32) -> Result<T, ParseError> {
     Err(ParseError {
         error: ParseErrorType::ConcatPatternVariableLeftHandSide,
@@ -2947,6 +3170,66 @@ fn clause_guard_reduction(
             right,
         },
 
+        Token::Plus => ClauseGuard::AddInt {
+            location,
+            left,
+            right,
+        },
+
+        Token::PlusDot => ClauseGuard::AddFloat {
+            location,
+            left,
+            right,
+        },
+
+        Token::Minus => ClauseGuard::SubInt {
+            location,
+            left,
+            right,
+        },
+
+        Token::MinusDot => ClauseGuard::SubFloat {
+            location,
+            left,
+            right,
+        },
+
+        Token::Star => ClauseGuard::MultInt {
+            location,
+            left,
+            right,
+        },
+
+        Token::StarDot => ClauseGuard::MultFloat {
+            location,
+            left,
+            right,
+        },
+
+        Token::Slash => ClauseGuard::DivInt {
+            location,
+            left,
+            right,
+        },
+
+        Token::SlashDot => ClauseGuard::DivFloat {
+            location,
+            left,
+            right,
+        },
+
+        Token::Percent => ClauseGuard::RemainderInt {
+            location,
+            left,
+            right,
+        },
+
+        Token::LtGt => ClauseGuard::Concatenate {
+            location,
+            left,
+            right,
+        },
+
         _ => panic!("Token could not be converted to Guard Op."),
     }
 }
@@ -14,7 +14,7 @@ macro_rules! assert_error {
         let error = crate::error::Error::Parse {
             src: $src.into(),
             path: PathBuf::from("/src/parse/error.gleam"),
-            error: result,
+            errors: vec1::vec1![result],
         };
         let result = error.pretty_string();
         insta::assert_snapshot!(insta::internals::AutoName, result, $src);
@@ -414,3 +414,101 @@ fn block_of_two() {
 fn nested_block() {
     assert_parse!(r#"{ 1 { 1.0 2.0 } 3 }"#);
 }
+
+macro_rules! assert_module_errors {
+    ($src:expr) => {
+        let parsed = crate::parse::parse_module_with_recovery($src);
+        let error = crate::error::Error::Parse {
+            src: $src.into(),
+            path: PathBuf::from("/src/parse/error.gleam"),
+            errors: vec1::Vec1::try_from_vec(parsed.errors).expect("should not parse"),
+        };
+        let result = error.pretty_string();
+        insta::assert_snapshot!(insta::internals::AutoName, result, $src);
+    };
+}
+
+fn recovered_statement_locations(src: &str) -> Vec<SrcSpan> {
+    crate::parse::parse_module_with_recovery(src)
+        .module
+        .statements
+        .iter()
+        .flat_map(|group| group.statements_ref())
+        .map(|statement| statement.location())
+        .collect()
+}
+
+#[test]
+fn several_syntax_errors() {
+    assert_module_errors!(
+        r#"fn one() { 1 + }
+
+pub fn two() {
+  2
+}
+
+const three =
+
+fn four() -> fn() -> Int {
+  fn() { 4 }
+}
+
+type Five {
+  Five(
+}
+"#
+    );
+}
+
+#[test]
+fn recovers_at_the_next_statement() {
+    let src = r#"fn one() { 1 + }
+pub fn two() -> fn() -> Int { fn() { 2 } }
+const three = [1, 2
+pub fn four() { 4 }
+type Five {
+"#;
+    assert_eq!(
+        recovered_statement_locations(src),
+        vec![SrcSpan::new(17, 44), SrcSpan::new(80, 93)]
+    );
+}
+
+#[test]
+fn recovers_within_target_groups() {
+    let src = r#"if erlang {
+  fn one() { 1 + }
+  pub fn two() { 2 }
+}
+fn three() { 3 }
+"#;
+    let parsed = crate::parse::parse_module_with_recovery(src);
+    assert_eq!(parsed.errors.len(), 1);
+    assert_eq!(
+        recovered_statement_locations(src),
+        vec![SrcSpan::new(33, 45), SrcSpan::new(54, 64)]
+    );
+}
+
+#[test]
+fn recovery_stops_at_lexical_errors() {
+    let src = r#"fn one() { 1 + }
+fn two() { "\x" }
+fn three() { 3 + }
+"#;
+    let errors = crate::parse::parse_module_with_recovery(src).errors;
+    let kinds: Vec<_> = errors
+        .iter()
+        .map(|error| matches!(error.error, ParseErrorType::LexError { .. }))
+        .collect();
+    assert_eq!(kinds, vec![false, true]);
+}
+
+#[test]
+fn parse_module_returns_lexical_error_before_syntax_errors() {
+    let src = r#"fn one() { 1 + }
+fn two() { "\x" }
+"#;
+    let error = crate::parse::parse_module(src).expect_err("should not parse");
+    assert!(matches!(error.error, ParseErrorType::LexError { .. }));
+}
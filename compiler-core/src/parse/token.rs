@@ -98,6 +98,12 @@ impl Token {
             | Self::GreaterEqualDot
             | Self::GreaterDot => Some(4),
 
+            Self::LtGt => Some(5),
+
+            Self::Plus | Self::PlusDot | Self::Minus | Self::MinusDot => Some(7),
+
+            Self::Star | Self::StarDot | Self::Slash | Self::SlashDot | Self::Percent => Some(8),
+
             _ => None,
         }
     }
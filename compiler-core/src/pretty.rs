@@ -175,6 +175,11 @@ pub enum Document<'a> {
 
     /// A string that is cheap to copy
     SmolStr(SmolStr),
+
+    /// Renders nothing, marking that the output that follows was generated
+    /// from the source code at the given byte index. Used to create source
+    /// maps
+    SourcePosition(u32),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -236,6 +241,8 @@ fn fits(
 
             Document::FlexBreak(doc) => docs.push_front((indent, mode, doc)),
 
+            Document::SourcePosition(_) => (),
+
             Document::Vec(vec) => {
                 for doc in vec.iter().rev() {
                     docs.push_front((indent, mode, doc));
@@ -350,6 +357,8 @@ fn format(
             Document::ForceBroken(document) => {
                 docs.push_front((indent, Mode::ForcedBroken, document));
             }
+
+            Document::SourcePosition(byte_index) => writer.source_position(*byte_index),
         }
     }
     Ok(())
@@ -359,6 +368,10 @@ pub fn nil<'a>() -> Document<'a> {
     Document::Vec(vec![])
 }
 
+pub fn source_position<'a>(byte_index: u32) -> Document<'a> {
+    Document::SourcePosition(byte_index)
+}
+
 pub fn line<'a>() -> Document<'a> {
     Document::Line(1)
 }
@@ -429,6 +442,7 @@ impl<'a> Document<'a> {
         use Document::*;
         match self {
             Line(n) => *n == 0,
+            SourcePosition(_) => true,
             SmolStr(s) => s.is_empty(),
             String(s) => s.is_empty(),
             Str(s) => s.is_empty(),
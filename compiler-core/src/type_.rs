@@ -1,5 +1,6 @@
 pub(crate) mod environment;
 pub(crate) mod error;
+pub(crate) mod exhaustiveness;
 pub(crate) mod expression;
 pub(crate) mod fields;
 pub(crate) mod hydrator;
@@ -1,4 +1,8 @@
-use crate::{ast::PIPE_VARIABLE, uid::UniqueIdGenerator, warning::TypeWarningEmitter};
+use crate::{
+    ast::{TypedClause, PIPE_VARIABLE},
+    uid::UniqueIdGenerator,
+    warning::TypeWarningEmitter,
+};
 
 use super::*;
 use std::collections::HashMap;
@@ -523,65 +527,27 @@ impl<'a> Environment<'a> {
             .collect()
     }
 
-    /// Checks that the given patterns are exhaustive for given type.
-    /// Currently only performs exhaustiveness checking for custom types,
-    /// only at the top level (without recursing into constructor arguments).
+    /// Checks that the rows of patterns, one pattern for each of the subjects,
+    /// match all possible values of the subjects. If they do not, examples of
+    /// the values that are not matched are returned.
     pub fn check_exhaustiveness(
-        &mut self,
-        patterns: Vec<Pattern<PatternConstructor, Arc<Type>>>,
-        value_typ: Arc<Type>,
+        &self,
+        rows: &[Vec<&TypedPattern>],
+        subjects: usize,
     ) -> Result<(), Vec<SmolStr>> {
-        match &*value_typ {
-            Type::App {
-                name: type_name,
-                module: module_name,
-                ..
-            } => {
-                let m = if module_name.is_empty() || module_name == self.current_module {
-                    None
-                } else {
-                    Some(module_name.as_str())
-                };
-
-                if let Ok(constructors) = self.get_constructors_for_type(m, type_name) {
-                    let mut unmatched_constructors: HashSet<SmolStr> =
-                        constructors.iter().cloned().collect();
-
-                    for p in &patterns {
-                        // ignore Assign patterns
-                        let mut pattern = p;
-                        while let Pattern::Assign {
-                            pattern: assign_pattern,
-                            ..
-                        } = pattern
-                        {
-                            pattern = assign_pattern;
-                        }
-
-                        match pattern {
-                            // If the pattern is a Discard or Var, all constructors are covered by it
-                            Pattern::Discard { .. } => return Ok(()),
-                            Pattern::Var { .. } => return Ok(()),
-                            // If the pattern is a constructor, remove it from unmatched patterns
-                            Pattern::Constructor {
-                                constructor: PatternConstructor::Record { name, .. },
-                                ..
-                            } => {
-                                let _ = unmatched_constructors.remove(name);
-                            }
-                            _ => return Ok(()),
-                        }
-                    }
-
-                    if !unmatched_constructors.is_empty() {
-                        return Err(unmatched_constructors.into_iter().sorted().collect());
-                    }
-                }
-                Ok(())
-            }
-            _ => Ok(()),
+        let unmatched = exhaustiveness::Checker::new(self).missing_patterns(rows, subjects);
+        if unmatched.is_empty() {
+            Ok(())
+        } else {
+            Err(unmatched)
         }
     }
+
+    /// The locations of the case clauses that can never be reached as the
+    /// clauses before them match all of the values they match.
+    pub fn unreachable_clauses(&self, clauses: &[TypedClause]) -> Vec<SrcSpan> {
+        exhaustiveness::Checker::new(self).unreachable_clauses(clauses)
+    }
 }
 
 /// Unify two types that should be the same.
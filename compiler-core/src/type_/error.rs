@@ -322,9 +322,72 @@ pub enum Warning {
         location: SrcSpan,
         name: SmolStr,
     },
+
+    UnreachableCaseClause {
+        location: SrcSpan,
+    },
 }
 
 impl Error {
+    /// The location of the error, if it is for a specific part of a module.
+    pub fn location(&self) -> Option<SrcSpan> {
+        match self {
+            Error::SrcImportingTest { location, .. }
+            | Error::BitStringSegmentError { location, .. }
+            | Error::UnknownVariable { location, .. }
+            | Error::UnknownType { location, .. }
+            | Error::UnknownModule { location, .. }
+            | Error::UnknownModuleType { location, .. }
+            | Error::UnknownModuleValue { location, .. }
+            | Error::UnknownModuleField { location, .. }
+            | Error::NotFn { location, .. }
+            | Error::UnknownRecordField { location, .. }
+            | Error::IncorrectArity { location, .. }
+            | Error::UpdateMultiConstructorType { location, .. }
+            | Error::UnnecessarySpreadOperator { location, .. }
+            | Error::IncorrectTypeArity { location, .. }
+            | Error::CouldNotUnify { location, .. }
+            | Error::RecursiveType { location, .. }
+            | Error::DuplicateImport { location, .. }
+            | Error::DuplicateTypeName { location, .. }
+            | Error::DuplicateConstName { location, .. }
+            | Error::DuplicateArgument { location, .. }
+            | Error::DuplicateField { location, .. }
+            | Error::PrivateTypeLeak { location, .. }
+            | Error::UnexpectedLabelledArg { location, .. }
+            | Error::PositionalArgumentAfterLabelled { location, .. }
+            | Error::IncorrectNumClausePatterns { location, .. }
+            | Error::NonLocalClauseGuardVariable { location, .. }
+            | Error::ExtraVarInAlternativePattern { location, .. }
+            | Error::MissingVarInAlternativePattern { location, .. }
+            | Error::DuplicateVarInPattern { location, .. }
+            | Error::OutOfBoundsTupleIndex { location, .. }
+            | Error::NotATuple { location, .. }
+            | Error::NotATupleUnbound { location, .. }
+            | Error::RecordAccessUnknownType { location, .. }
+            | Error::RecordUpdateInvalidConstructor { location, .. }
+            | Error::UnexpectedTypeHole { location, .. }
+            | Error::NotExhaustivePatternMatch { location, .. }
+            | Error::ArgumentNameAlreadyUsed { location, .. }
+            | Error::UnlabelledAfterlabelled { location, .. } => Some(*location),
+
+            // The error is reported at the second definition
+            Error::DuplicateName {
+                location_a,
+                location_b,
+                ..
+            } => Some(if location_a.start < location_b.start {
+                *location_b
+            } else {
+                *location_a
+            }),
+
+            Error::UnknownLabels { unknown, .. } => unknown.first().map(|(_, location)| *location),
+
+            Error::ReservedModuleName { .. } | Error::KeywordInModuleName { .. } => None,
+        }
+    }
+
     pub fn with_unify_error_situation(mut self, new_situation: UnifyErrorSituation) -> Self {
         match self {
             Error::CouldNotUnify {
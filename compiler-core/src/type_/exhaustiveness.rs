@@ -0,0 +1,652 @@
+//! Exhaustiveness checking of patterns.
+//!
+//! The patterns of a case expression are treated as a matrix, with a row for
+//! each clause (or alternative of a clause) and a column for each subject.
+//! Whether a row of patterns can match any value not matched by the rows
+//! before it (it is "useful") is found using the algorithm described by Luc
+//! Maranget in "Warnings for pattern matching". A case expression is
+//! exhaustive if a row of discards would not be useful, and the values it
+//! would match are the examples of the values that are not matched.
+//!
+//! Values are described by the constructors that build them. Custom types,
+//! tuples and lists (either empty or an element followed by another list)
+//! have a finite set of constructors, while ints, floats, strings and bit
+//! strings have so many that only a discard or variable can match all of
+//! them.
+//!
+//! Clauses with guards are never taken to match a value as the guard may
+//! fail.
+//!
+//! A clause is unreachable if none of its rows are useful given the rows of
+//! the clauses before it, as every value it matches is matched earlier.
+
+use std::sync::Arc;
+
+use itertools::Itertools;
+use smol_str::SmolStr;
+
+use super::{
+    collapse_links, environment::Environment, PatternConstructor, Type, ValueConstructorVariant,
+};
+use crate::ast::{
+    BitStringSegmentOption, Pattern, SrcSpan, TypedClause, TypedPattern,
+    TypedPatternBitStringSegment,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Constructor {
+    Variant {
+        /// The module and name of the custom type the constructor belongs to.
+        type_module: SmolStr,
+        type_name: SmolStr,
+        name: SmolStr,
+        arity: usize,
+    },
+    Tuple(usize),
+    EmptyList,
+    /// An element followed by the rest of the list.
+    NonEmptyList,
+    /// An int, written in decimal so that literals with the same value are
+    /// the same constructor.
+    Int(SmolStr),
+    /// A float, written as Rust prints it so that literals with the same
+    /// value are the same constructor.
+    Float(SmolStr),
+    String(SmolStr),
+    /// Any string starting with the prefix.
+    StringPrefix(SmolStr),
+    /// Bit string patterns are not compared with one another, so each one is
+    /// its own constructor, numbered in the order they are found.
+    BitString(usize),
+}
+
+impl Constructor {
+    fn arity(&self) -> usize {
+        match self {
+            Constructor::Variant { arity, .. } => *arity,
+            Constructor::Tuple(size) => *size,
+            Constructor::NonEmptyList => 2,
+            Constructor::EmptyList
+            | Constructor::Int(_)
+            | Constructor::Float(_)
+            | Constructor::String(_)
+            | Constructor::StringPrefix(_)
+            | Constructor::BitString(_) => 0,
+        }
+    }
+
+    /// Whether every value built by the other constructor is also built by
+    /// this one.
+    fn covers(&self, other: &Constructor) -> bool {
+        match (self, other) {
+            (
+                Constructor::StringPrefix(prefix),
+                Constructor::String(string) | Constructor::StringPrefix(string),
+            ) => string.starts_with(prefix.as_str()),
+            _ => self == other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pat {
+    Wildcard,
+    Constructor(Constructor, Vec<Pat>),
+}
+
+type Row = Vec<Pat>;
+
+/// The most examples of values that are not matched to show in an error.
+const MAX_WITNESSES: usize = 5;
+
+pub struct Checker<'a, 'b> {
+    environment: &'a Environment<'b>,
+    bit_strings: usize,
+}
+
+impl<'a, 'b> Checker<'a, 'b> {
+    pub fn new(environment: &'a Environment<'b>) -> Self {
+        Self {
+            environment,
+            bit_strings: 0,
+        }
+    }
+
+    /// Examples of the values that are matched by none of the rows of
+    /// patterns, one pattern for each of the subjects. Each is written as
+    /// Gleam code, with the constructors named as they would be in the
+    /// current module.
+    pub fn missing_patterns(
+        &mut self,
+        rows: &[Vec<&TypedPattern>],
+        subjects: usize,
+    ) -> Vec<SmolStr> {
+        let matrix = rows.iter().map(|row| self.row(row)).collect_vec();
+        self.witnesses(&matrix, &vec![Pat::Wildcard; subjects], MAX_WITNESSES)
+            .iter()
+            .map(|witness| {
+                witness
+                    .iter()
+                    .map(|pattern| self.print(pattern))
+                    .join(", ")
+                    .into()
+            })
+            .unique()
+            .collect()
+    }
+
+    /// The locations of the clauses that can never match as the clauses
+    /// before them match all of the values they do.
+    pub fn unreachable_clauses(&mut self, clauses: &[TypedClause]) -> Vec<SrcSpan> {
+        let mut matrix = vec![];
+        let mut unreachable = vec![];
+        for clause in clauses {
+            let rows = std::iter::once(&clause.pattern)
+                .chain(clause.alternative_patterns.iter())
+                .map(|patterns| {
+                    patterns
+                        .iter()
+                        .map(|pattern| self.pattern(pattern))
+                        .collect()
+                })
+                .collect_vec();
+            if !rows.iter().any(|row: &Row| self.is_useful(&matrix, row)) {
+                unreachable.push(clause.location());
+            }
+            if clause.guard.is_none() {
+                matrix.extend(rows);
+            }
+        }
+        unreachable
+    }
+
+    fn row(&mut self, patterns: &[&TypedPattern]) -> Row {
+        patterns
+            .iter()
+            .map(|pattern| self.pattern(pattern))
+            .collect()
+    }
+
+    fn pattern(&mut self, pattern: &TypedPattern) -> Pat {
+        match pattern {
+            Pattern::Var { .. } | Pattern::VarUsage { .. } | Pattern::Discard { .. } => {
+                Pat::Wildcard
+            }
+
+            Pattern::Assign { pattern, .. } => self.pattern(pattern),
+
+            Pattern::Int { value, .. } => {
+                Pat::Constructor(Constructor::Int(int_value(value)), vec![])
+            }
+
+            Pattern::Float { value, .. } => {
+                Pat::Constructor(Constructor::Float(float_value(value)), vec![])
+            }
+
+            Pattern::String { value, .. } => {
+                Pat::Constructor(Constructor::String(value.clone()), vec![])
+            }
+
+            // Every string starts with the empty string
+            Pattern::Concatenate {
+                left_side_string, ..
+            } if left_side_string.is_empty() => Pat::Wildcard,
+
+            Pattern::Concatenate {
+                left_side_string, ..
+            } => Pat::Constructor(Constructor::StringPrefix(left_side_string.clone()), vec![]),
+
+            Pattern::BitString { segments, .. } if matches_any_bit_string(segments) => {
+                Pat::Wildcard
+            }
+
+            Pattern::BitString { .. } => {
+                self.bit_strings += 1;
+                Pat::Constructor(Constructor::BitString(self.bit_strings), vec![])
+            }
+
+            Pattern::Tuple { elems, .. } => Pat::Constructor(
+                Constructor::Tuple(elems.len()),
+                elems.iter().map(|element| self.pattern(element)).collect(),
+            ),
+
+            Pattern::List { elements, tail, .. } => {
+                let tail = match tail {
+                    Some(tail) => self.pattern(tail),
+                    None => Pat::Constructor(Constructor::EmptyList, vec![]),
+                };
+                elements.iter().rev().fold(tail, |tail, element| {
+                    Pat::Constructor(Constructor::NonEmptyList, vec![self.pattern(element), tail])
+                })
+            }
+
+            Pattern::Constructor {
+                arguments,
+                constructor: PatternConstructor::Record { name, .. },
+                type_,
+                ..
+            } => {
+                let (type_module, type_name) = match custom_type(type_) {
+                    Some(type_) => type_,
+                    None => return Pat::Wildcard,
+                };
+                let constructor = Constructor::Variant {
+                    type_module,
+                    type_name,
+                    name: name.clone(),
+                    arity: arguments.len(),
+                };
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.pattern(&argument.value))
+                    .collect();
+                Pat::Constructor(constructor, arguments)
+            }
+        }
+    }
+
+    /// Whether the row matches any value that is matched by none of the rows
+    /// of the matrix. This stops as soon as one such value is found, so it is
+    /// used rather than `witnesses` unless the values are to be shown.
+    fn is_useful(&self, matrix: &[Row], row: &[Pat]) -> bool {
+        // A row of wildcards matches every value the row could
+        if matrix
+            .iter()
+            .any(|row| row.iter().all(|pattern| *pattern == Pat::Wildcard))
+        {
+            return false;
+        }
+
+        let (first, rest) = match row.split_first() {
+            Some(split) => split,
+            None => return matrix.is_empty(),
+        };
+
+        match first {
+            Pat::Constructor(constructor, arguments) => {
+                let row = arguments.iter().chain(rest).cloned().collect_vec();
+                self.is_useful(&specialise(matrix, constructor), &row)
+            }
+
+            // When every constructor of the type is in the first column the
+            // wildcard is useful if it is useful for any one of them.
+            Pat::Wildcard => match self.complete_constructors(matrix) {
+                Some(all) => all.iter().any(|constructor| {
+                    let row = vec![Pat::Wildcard; constructor.arity()]
+                        .into_iter()
+                        .chain(rest.iter().cloned())
+                        .collect_vec();
+                    self.is_useful(&specialise(matrix, constructor), &row)
+                }),
+
+                // Otherwise a value built by a missing constructor is matched
+                // only by the rows that start with a wildcard.
+                None => self.is_useful(&default_matrix(matrix), rest),
+            },
+        }
+    }
+
+    /// Up to `limit` of the values matched by the row that are matched by
+    /// none of the rows of the matrix.
+    fn witnesses(&self, matrix: &[Row], row: &[Pat], limit: usize) -> Vec<Row> {
+        if limit == 0 || !self.is_useful(matrix, row) {
+            return vec![];
+        }
+        let (first, rest) = match row.split_first() {
+            Some(split) => split,
+            None => return vec![vec![]],
+        };
+
+        match first {
+            Pat::Constructor(constructor, arguments) => {
+                let row = arguments.iter().chain(rest).cloned().collect_vec();
+                self.witnesses(&specialise(matrix, constructor), &row, limit)
+                    .into_iter()
+                    .map(|witness| rebuild(constructor, witness))
+                    .collect()
+            }
+
+            Pat::Wildcard => {
+                // When the constructors of the type can be listed each of
+                // them is used for the examples, as a wildcard would not say
+                // which values are missing.
+                if let Some(all) = self.all_constructors(&first_constructors(matrix)) {
+                    let mut witnesses = vec![];
+                    for constructor in &all {
+                        let row = vec![Pat::Wildcard; constructor.arity()]
+                            .into_iter()
+                            .chain(rest.iter().cloned())
+                            .collect_vec();
+                        let found = self.witnesses(
+                            &specialise(matrix, constructor),
+                            &row,
+                            limit - witnesses.len(),
+                        );
+                        witnesses.extend(
+                            found
+                                .into_iter()
+                                .map(|witness| rebuild(constructor, witness)),
+                        );
+                        if witnesses.len() >= limit {
+                            break;
+                        }
+                    }
+                    return witnesses;
+                }
+
+                // Otherwise only a wildcard matches all of the values, so it
+                // is useful if the rest of the row is useful for the rows that
+                // start with a wildcard.
+                self.witnesses(&default_matrix(matrix), rest, limit)
+                    .into_iter()
+                    .map(|witness| std::iter::once(Pat::Wildcard).chain(witness).collect())
+                    .collect()
+            }
+        }
+    }
+
+    /// All the constructors of the type of the first column of the matrix,
+    /// if every one of them is used in that column.
+    fn complete_constructors(&self, matrix: &[Row]) -> Option<Vec<Constructor>> {
+        let used = first_constructors(matrix);
+        let all = self.all_constructors(&used)?;
+        if all.iter().all(|constructor| used.contains(constructor)) {
+            Some(all)
+        } else {
+            None
+        }
+    }
+
+    /// All the constructors of the type the constructors are for, if there are
+    /// few enough of them to list.
+    fn all_constructors(&self, used: &[Constructor]) -> Option<Vec<Constructor>> {
+        match used.first()? {
+            Constructor::Variant {
+                type_module,
+                type_name,
+                ..
+            } => self.variants(type_module, type_name),
+            Constructor::Tuple(size) => Some(vec![Constructor::Tuple(*size)]),
+            Constructor::EmptyList | Constructor::NonEmptyList => {
+                Some(vec![Constructor::EmptyList, Constructor::NonEmptyList])
+            }
+            Constructor::Int(_)
+            | Constructor::Float(_)
+            | Constructor::String(_)
+            | Constructor::StringPrefix(_)
+            | Constructor::BitString(_) => None,
+        }
+    }
+
+    fn variants(&self, type_module: &SmolStr, type_name: &SmolStr) -> Option<Vec<Constructor>> {
+        let environment = self.environment;
+        let prelude = environment.importable_modules.get("gleam")?;
+        let (names, values) = if type_module == environment.current_module {
+            (
+                environment.module_types_constructors.get(type_name)?,
+                &environment.module_values,
+            )
+        } else if type_module.is_empty() {
+            // The prelude has no name within the types it defines
+            (prelude.types_constructors.get(type_name)?, &prelude.values)
+        } else {
+            let module = environment.importable_modules.get(type_module)?;
+            (module.types_constructors.get(type_name)?, &module.values)
+        };
+        // A type with no constructors cannot be matched on, such as an opaque
+        // type from another module.
+        if names.is_empty() {
+            return None;
+        }
+        names
+            .iter()
+            .map(|name| {
+                // The constructors of opaque types are only in scope in the
+                // module that defines them.
+                let value = values
+                    .get(name)
+                    .or_else(|| environment.scope.get(name))
+                    .or_else(|| prelude.values.get(name))?;
+                match &value.variant {
+                    ValueConstructorVariant::Record { arity, module, .. }
+                        if module == type_module =>
+                    {
+                        Some(Constructor::Variant {
+                            type_module: type_module.clone(),
+                            type_name: type_name.clone(),
+                            name: name.clone(),
+                            arity: *arity as usize,
+                        })
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn print(&self, pattern: &Pat) -> String {
+        let (constructor, arguments) = match pattern {
+            Pat::Wildcard => return "_".into(),
+            Pat::Constructor(constructor, arguments) => (constructor, arguments),
+        };
+        match constructor {
+            Constructor::Variant {
+                type_module, name, ..
+            } => {
+                let name = self.constructor_name(type_module, name);
+                if arguments.is_empty() {
+                    name
+                } else {
+                    format!("{name}({})", self.print_all(arguments))
+                }
+            }
+            Constructor::Tuple(_) => format!("#({})", self.print_all(arguments)),
+            Constructor::EmptyList | Constructor::NonEmptyList => self.print_list(pattern),
+            Constructor::Int(value) | Constructor::Float(value) => value.to_string(),
+            Constructor::String(value) => format!("\"{value}\""),
+            Constructor::StringPrefix(prefix) => format!("\"{prefix}\" <> _"),
+            Constructor::BitString(_) => "<<_:bit_string>>".into(),
+        }
+    }
+
+    fn print_all(&self, patterns: &[Pat]) -> String {
+        patterns
+            .iter()
+            .map(|pattern| self.print(pattern))
+            .join(", ")
+    }
+
+    fn print_list(&self, mut pattern: &Pat) -> String {
+        let mut elements = vec![];
+        loop {
+            match pattern {
+                Pat::Constructor(Constructor::NonEmptyList, arguments) => {
+                    match arguments.as_slice() {
+                        [element, tail] => {
+                            elements.push(self.print(element));
+                            pattern = tail;
+                        }
+                        _ => return "_".into(),
+                    }
+                }
+                Pat::Constructor(Constructor::EmptyList, _) => {
+                    return format!("[{}]", elements.join(", "))
+                }
+                _ => {
+                    elements.push("..".into());
+                    return format!("[{}]", elements.join(", "));
+                }
+            }
+        }
+    }
+
+    /// The name a constructor is referred to by in the current module, which
+    /// is qualified with the name of its module unless it has been imported
+    /// unqualified.
+    fn constructor_name(&self, type_module: &str, name: &SmolStr) -> String {
+        let environment = self.environment;
+        if type_module == environment.current_module {
+            return name.to_string();
+        }
+        let is_constructor = |value: &super::ValueConstructor| {
+            matches!(&value.variant, ValueConstructorVariant::Record {
+                module,
+                name: constructor,
+                ..
+            } if module == type_module && constructor == name)
+        };
+        if environment.scope.get(name).is_some_and(is_constructor) {
+            return name.to_string();
+        }
+        if let Some((alias, _)) = environment
+            .scope
+            .iter()
+            .find(|(_, value)| is_constructor(value))
+        {
+            return alias.to_string();
+        }
+        match environment
+            .imported_modules
+            .iter()
+            .find(|(_, (_, module))| module.name == type_module)
+        {
+            Some((alias, _)) => format!("{alias}.{name}"),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// The module and name of the custom type built by the constructor with the
+/// given type.
+fn custom_type(type_: &Arc<Type>) -> Option<(SmolStr, SmolStr)> {
+    match collapse_links(type_.clone()).as_ref() {
+        Type::Fn { retrn, .. } => custom_type(retrn),
+        Type::App { module, name, .. } => Some((module.clone(), name.clone())),
+        Type::Var { .. } | Type::Tuple { .. } => None,
+    }
+}
+
+/// The value of an int literal in decimal, so that `16` and `0x10` are the
+/// same. Ints in Gleam have no size limit, so the digits are converted one at
+/// a time.
+fn int_value(literal: &str) -> SmolStr {
+    let (negative, literal) = match literal.strip_prefix('-') {
+        Some(literal) => (true, literal),
+        None => (false, literal),
+    };
+    let radix = match literal.get(..2) {
+        Some("0b") => 2,
+        Some("0o") => 8,
+        Some("0x") => 16,
+        _ => 10,
+    };
+    let digits = if radix == 10 {
+        literal
+    } else {
+        literal.get(2..).unwrap_or_default()
+    };
+
+    // The decimal digits of the value, least significant first
+    let mut decimal: Vec<u32> = vec![];
+    for digit in digits.chars().filter_map(|char| char.to_digit(radix)) {
+        let mut carry = digit;
+        for decimal_digit in decimal.iter_mut() {
+            let value = *decimal_digit * radix + carry;
+            *decimal_digit = value % 10;
+            carry = value / 10;
+        }
+        while carry > 0 {
+            decimal.push(carry % 10);
+            carry /= 10;
+        }
+    }
+
+    let mut value: String = decimal
+        .iter()
+        .rev()
+        .filter_map(|digit| char::from_digit(*digit, 10))
+        .collect();
+    if value.is_empty() {
+        value.push('0');
+    } else if negative {
+        value.insert(0, '-');
+    }
+    value.into()
+}
+
+/// The value of a float literal, so that `1.0` and `1.00` are the same.
+fn float_value(literal: &str) -> SmolStr {
+    match literal.replace('_', "").parse::<f64>() {
+        // Adding zero turns `-0.0` into `0.0`, which it is equal to
+        Ok(value) => format!("{:?}", value + 0.0).into(),
+        Err(_) => literal.into(),
+    }
+}
+
+/// A single segment with the `bit_string` option, assigned to a variable or
+/// discarded, matches any bit string.
+fn matches_any_bit_string(segments: &[TypedPatternBitStringSegment]) -> bool {
+    match segments {
+        [segment] => {
+            matches!(
+                segment.options.as_slice(),
+                [BitStringSegmentOption::BitString { .. }]
+            ) && matches!(
+                segment.value.as_ref(),
+                Pattern::Var { .. } | Pattern::Discard { .. }
+            )
+        }
+        _ => false,
+    }
+}
+
+/// The rows of the matrix that match values built by the constructor, with
+/// their first pattern replaced by a pattern for each of the constructor's
+/// arguments.
+fn specialise(matrix: &[Row], constructor: &Constructor) -> Vec<Row> {
+    matrix
+        .iter()
+        .filter_map(|row| {
+            let (first, rest) = row.split_first()?;
+            let arguments = match first {
+                Pat::Wildcard => vec![Pat::Wildcard; constructor.arity()],
+                Pat::Constructor(first, arguments) if first.covers(constructor) => {
+                    arguments.clone()
+                }
+                Pat::Constructor(..) => return None,
+            };
+            Some(arguments.into_iter().chain(rest.iter().cloned()).collect())
+        })
+        .collect()
+}
+
+/// The constructors used by the first patterns of the rows of the matrix.
+fn first_constructors(matrix: &[Row]) -> Vec<Constructor> {
+    matrix
+        .iter()
+        .filter_map(|row| match row.first() {
+            Some(Pat::Constructor(constructor, _)) => Some(constructor.clone()),
+            _ => None,
+        })
+        .unique()
+        .collect()
+}
+
+/// The rows of the matrix that start with a wildcard, without it. These are
+/// the rows that match values built by constructors not in the first column.
+fn default_matrix(matrix: &[Row]) -> Vec<Row> {
+    matrix
+        .iter()
+        .filter(|row| matches!(row.first(), Some(Pat::Wildcard)))
+        .map(|row| row.iter().skip(1).cloned().collect())
+        .collect()
+}
+
+/// Replaces the patterns for the arguments of the constructor at the start
+/// of the row with the constructor.
+fn rebuild(constructor: &Constructor, mut row: Row) -> Row {
+    let rest = row.split_off(constructor.arity().min(row.len()));
+    std::iter::once(Pat::Constructor(constructor.clone(), row))
+        .chain(rest)
+        .collect()
+}
@@ -758,14 +758,9 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 .map_err(|e| convert_unify_error(e, value.type_defining_location()))?;
         }
 
-        // We currently only do only limited exhaustiveness checking of custom types
-        // at the top level of patterns.
         // Do not perform exhaustiveness checking if user explicitly used `let assert ... = ...`.
         if kind.performs_exhaustiveness_check() {
-            if let Err(unmatched) = self
-                .environment
-                .check_exhaustiveness(vec![pattern.clone()], collapse_links(value_typ.clone()))
-            {
+            if let Err(unmatched) = self.environment.check_exhaustiveness(&[vec![&pattern]], 1) {
                 return Err(Error::NotExhaustivePatternMatch {
                     location,
                     unmatched,
@@ -814,9 +809,13 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
             typed_clauses.push(typed_clause);
         }
 
-        if let Err(unmatched) =
-            self.check_case_exhaustiveness(subjects_count, &subject_types, &typed_clauses)
-        {
+        for location in self.environment.unreachable_clauses(&typed_clauses) {
+            self.environment
+                .warnings
+                .emit(Warning::UnreachableCaseClause { location });
+        }
+
+        if let Err(unmatched) = self.check_case_exhaustiveness(subjects_count, &typed_clauses) {
             return Err(Error::NotExhaustivePatternMatch {
                 location,
                 unmatched,
@@ -909,6 +908,23 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         }
     }
 
+    /// Infers the operands of a binary operator in a guard, both of which
+    /// must have the operand type, and builds the typed guard from them.
+    fn infer_binop_clause_guard(
+        &mut self,
+        left: UntypedClauseGuard,
+        right: UntypedClauseGuard,
+        operand_type: Arc<Type>,
+        guard: impl FnOnce(Box<TypedClauseGuard>, Box<TypedClauseGuard>) -> TypedClauseGuard,
+    ) -> Result<TypedClauseGuard, Error> {
+        let left = self.infer_clause_guard(left)?;
+        unify(operand_type.clone(), left.type_())
+            .map_err(|e| convert_unify_error(e, left.location()))?;
+        let right = self.infer_clause_guard(right)?;
+        unify(operand_type, right.type_()).map_err(|e| convert_unify_error(e, right.location()))?;
+        Ok(guard(Box::new(left), Box::new(right)))
+    }
+
     fn infer_clause_guard(&mut self, guard: UntypedClauseGuard) -> Result<TypedClauseGuard, Error> {
         match guard {
             ClauseGuard::Var { location, name, .. } => {
@@ -1186,6 +1202,176 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
                 })
             }
 
+            ClauseGuard::AddInt {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, int(), |left, right| {
+                ClauseGuard::AddInt {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::AddFloat {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, float(), |left, right| {
+                ClauseGuard::AddFloat {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::SubInt {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, int(), |left, right| {
+                ClauseGuard::SubInt {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::SubFloat {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, float(), |left, right| {
+                ClauseGuard::SubFloat {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::MultInt {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, int(), |left, right| {
+                ClauseGuard::MultInt {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::MultFloat {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, float(), |left, right| {
+                ClauseGuard::MultFloat {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::DivInt {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, int(), |left, right| {
+                ClauseGuard::DivInt {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::DivFloat {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, float(), |left, right| {
+                ClauseGuard::DivFloat {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::RemainderInt {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, int(), |left, right| {
+                ClauseGuard::RemainderInt {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::Concatenate {
+                location,
+                left,
+                right,
+                ..
+            } => self.infer_binop_clause_guard(*left, *right, string(), |left, right| {
+                ClauseGuard::Concatenate {
+                    location,
+                    left,
+                    right,
+                }
+            }),
+
+            ClauseGuard::Not {
+                location,
+                expression,
+            } => {
+                let expression = self.infer_clause_guard(*expression)?;
+                unify(bool(), expression.type_())
+                    .map_err(|e| convert_unify_error(e, expression.location()))?;
+                Ok(ClauseGuard::Not {
+                    location,
+                    expression: Box::new(expression),
+                })
+            }
+
+            ClauseGuard::FieldAccess {
+                location,
+                label,
+                container,
+                ..
+            } => {
+                let container = self.infer_clause_guard(*container)?;
+                let RecordAccessor {
+                    index,
+                    label,
+                    type_,
+                } = self.infer_record_accessor(
+                    container.type_(),
+                    container.location(),
+                    label,
+                    location,
+                    FieldAccessUsage::Other,
+                )?;
+                Ok(ClauseGuard::FieldAccess {
+                    location,
+                    index: Some(index),
+                    label,
+                    type_,
+                    container: Box::new(container),
+                })
+            }
+
             ClauseGuard::Constant(constant) => {
                 self.infer_const(&None, constant).map(ClauseGuard::Constant)
             }
@@ -1275,26 +1461,50 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         location: SrcSpan,
         usage: FieldAccessUsage,
     ) -> Result<TypedExpr, Error> {
-        let record = Box::new(record);
+        let RecordAccessor {
+            index,
+            label,
+            type_: typ,
+        } =
+            self.infer_record_accessor(record.type_(), record.location(), label, location, usage)?;
+
+        Ok(TypedExpr::RecordAccess {
+            record: Box::new(record),
+            label,
+            index,
+            location,
+            typ,
+        })
+    }
 
+    /// Finds the accessor for the field with the label of a record with the
+    /// given type, with its type instantiated for this record.
+    fn infer_record_accessor(
+        &mut self,
+        record_type: Arc<Type>,
+        record_location: SrcSpan,
+        label: SmolStr,
+        location: SrcSpan,
+        usage: FieldAccessUsage,
+    ) -> Result<RecordAccessor, Error> {
         // If we don't yet know the type of the record then we cannot use any accessors
-        if record.type_().is_unbound() {
+        if record_type.is_unbound() {
             return Err(Error::RecordAccessUnknownType {
-                location: record.location(),
+                location: record_location,
             });
         }
 
         // Error constructor helper function
         let unknown_field = |fields| Error::UnknownRecordField {
             usage,
-            typ: record.type_(),
+            typ: record_type.clone(),
             location,
             label: label.clone(),
             fields,
         };
 
         // Check to see if it's a Type that can have accessible fields
-        let accessors = match collapse_links(record.type_()).as_ref() {
+        let accessors = match collapse_links(record_type.clone()).as_ref() {
             // A type in the current module which may have fields
             Type::App { module, name, .. } if module == self.environment.current_module => {
                 self.environment.accessors.get(name)
@@ -1328,16 +1538,14 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         let accessor_record_type = accessors.type_.clone();
         let mut type_vars = hashmap![];
         let accessor_record_type = self.instantiate(accessor_record_type, &mut type_vars);
-        let typ = self.instantiate(typ, &mut type_vars);
-        unify(accessor_record_type, record.type_())
-            .map_err(|e| convert_unify_error(e, record.location()))?;
+        let type_ = self.instantiate(typ, &mut type_vars);
+        unify(accessor_record_type, record_type)
+            .map_err(|e| convert_unify_error(e, record_location))?;
 
-        Ok(TypedExpr::RecordAccess {
-            record,
-            label,
+        Ok(RecordAccessor {
             index,
-            location,
-            typ,
+            label,
+            type_,
         })
     }
 
@@ -1348,14 +1556,15 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         args: Vec<UntypedRecordUpdateArg>,
         location: SrcSpan,
     ) -> Result<TypedExpr, Error> {
-        let (module, name) = match self.infer(constructor.clone())? {
+        let typed_constructor = self.infer(constructor.clone())?;
+        let (module, name) = match &typed_constructor {
             TypedExpr::ModuleSelect {
                 module_alias,
                 label,
                 ..
-            } => (Some(module_alias), label),
+            } => (Some(module_alias.clone()), label.clone()),
 
-            TypedExpr::Var { name, .. } => (None, name),
+            TypedExpr::Var { name, .. } => (None, name.clone()),
 
             constructor => {
                 return Err(Error::RecordUpdateInvalidConstructor {
@@ -1463,6 +1672,7 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
         Ok(TypedExpr::RecordUpdate {
             location,
             typ: spread.type_(),
+            constructor: Box::new(typed_constructor),
             spread: Box::new(spread),
             args,
         })
@@ -2039,42 +2249,22 @@ impl<'a, 'b> ExprTyper<'a, 'b> {
     fn check_case_exhaustiveness(
         &mut self,
         subjects_count: usize,
-        subjects: &[Arc<Type>],
         typed_clauses: &[Clause<TypedExpr, PatternConstructor, Arc<Type>, SmolStr>],
     ) -> Result<(), Vec<SmolStr>> {
-        // Because exhaustiveness checking in presence of multiple subjects is similar
-        // to full exhaustiveness checking of tuples or other nested record patterns,
-        // and we currently only do only limited exhaustiveness checking of custom types
-        // at the top level of patterns, only consider case expressions with one subject.
-        if subjects_count != 1 {
-            return Ok(());
-        }
-        let subject_type = subjects
-            .get(0)
-            .expect("Asserted there's one case subject but found none");
-        let value_typ = collapse_links(subject_type.clone());
-
         // Currently guards in exhaustiveness checking are assumed that they can fail,
         // so we go through all clauses and pluck out only the patterns
         // for clauses that don't have guards.
-        let mut patterns = Vec::new();
-        for clause in typed_clauses {
-            if let Clause { guard: None, .. } = clause {
-                // clause.pattern is a list of patterns for all subjects
-                if let Some(pattern) = clause.pattern.get(0) {
-                    patterns.push(pattern.clone());
-                }
-                // A clause can be built with alternative patterns as well, e.g. `Audio(_) | Text(_) ->`.
-                // We're interested in all patterns so we build a flattened list.
-                for alternative_pattern in &clause.alternative_patterns {
-                    // clause.alternative_pattern is a list of patterns for all subjects
-                    if let Some(pattern) = alternative_pattern.get(0) {
-                        patterns.push(pattern.clone());
-                    }
-                }
-            }
-        }
-        self.environment.check_exhaustiveness(patterns, value_typ)
+        // A clause can be built with alternative patterns as well, e.g. `Audio(_) | Text(_) ->`,
+        // each of which is a row with a pattern for each of the subjects.
+        let rows = typed_clauses
+            .iter()
+            .filter(|clause| clause.guard.is_none())
+            .flat_map(|clause| {
+                std::iter::once(&clause.pattern).chain(clause.alternative_patterns.iter())
+            })
+            .map(|patterns| patterns.iter().collect_vec())
+            .collect_vec();
+        self.environment.check_exhaustiveness(&rows, subjects_count)
     }
 }
 
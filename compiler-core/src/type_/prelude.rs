@@ -245,6 +245,10 @@ pub fn build_prelude(ids: &UniqueIdGenerator) -> Module {
         .types_constructors
         .insert(RESULT.into(), vec!["Ok".into(), "Error".into()]);
 
+    let _ = prelude
+        .types_constructors
+        .insert(NIL.into(), vec![NIL.into()]);
+
     let _ = prelude.values.insert(
         NIL.into(),
         value(
@@ -4,7 +4,9 @@ use crate::ast::{UntypedExpr, UntypedModule};
 mod assert;
 mod assignments;
 mod errors;
+mod exhaustiveness;
 mod functions;
+mod guards;
 mod imports;
 mod pretty;
 mod statement_if;
@@ -182,7 +184,35 @@ macro_rules! assert_module_error {
         let error = $crate::error::Error::Type {
             src: $src.into(),
             path: std::path::PathBuf::from("/src/one/two.gleam"),
-            error,
+            errors: vec1::vec1![error],
+        };
+        let output = error.pretty_string();
+        insta::assert_snapshot!(insta::internals::AutoName, output, $src);
+    };
+}
+
+#[macro_export]
+macro_rules! assert_module_errors {
+    ($src:expr) => {
+        let (ast, _) = $crate::parse::parse_module($src).expect("syntax error");
+        let mut modules = im::HashMap::new();
+        let ids = $crate::uid::UniqueIdGenerator::new();
+        // DUPE: preludeinsertion
+        let _ = modules.insert("gleam".into(), $crate::type_::build_prelude(&ids));
+        let errors = $crate::analyse::infer_module_with_recovery(
+            $crate::build::Target::Erlang,
+            &ids,
+            ast,
+            $crate::build::Origin::Src,
+            &"thepackage".into(),
+            &modules,
+            &$crate::warning::TypeWarningEmitter::null(),
+        )
+        .expect_err("should infer an error");
+        let error = $crate::error::Error::Type {
+            src: $src.into(),
+            path: std::path::PathBuf::from("/src/one/two.gleam"),
+            errors,
         };
         let output = error.pretty_string();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
@@ -198,7 +228,7 @@ macro_rules! assert_module_syntax_error {
         let error = $crate::error::Error::Parse {
             src: $src.into(),
             path: std::path::PathBuf::from("/src/one/two.gleam"),
-            error,
+            errors: vec1::vec1![error],
         };
 
         let output = error.pretty_string();
@@ -251,7 +281,7 @@ macro_rules! assert_error {
         let error = $crate::error::Error::Type {
             src: $src.into(),
             path: PathBuf::from("/src/one/two.gleam"),
-            error,
+            errors: vec1::vec1![error],
         };
         let output = error.pretty_string();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
@@ -298,7 +328,7 @@ macro_rules! assert_with_module_error {
         let error = $crate::error::Error::Type {
             src: $src.into(),
             path: PathBuf::from("/src/one/two.gleam"),
-            error,
+            errors: vec1::vec1![error],
         };
         let output = error.pretty_string();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
@@ -356,7 +386,7 @@ macro_rules! assert_with_module_error {
         let error = $crate::error::Error::Type {
             src: $src.into(),
             path: PathBuf::from("/src/one/two.gleam"),
-            error,
+            errors: vec1::vec1![error],
         };
         let output = error.pretty_string();
         insta::assert_snapshot!(insta::internals::AutoName, output, $src);
@@ -759,6 +789,7 @@ fn infer_module_type_retention_test() {
             types: HashMap::new(),
             types_constructors: HashMap::from([
                 ("Bool".into(), vec!["True".into(), "False".into()]),
+                ("Nil".into(), vec!["Nil".into()]),
                 ("Result".into(), vec!["Ok".into(), "Error".into()])
             ]),
             values: HashMap::new(),
@@ -964,26 +995,29 @@ fn pipe() {
 
 #[test]
 fn bit_strings() {
-    assert_infer!("let <<x>> = <<1>> x", "Int");
-    assert_infer!("let <<x>> = <<1>> x", "Int");
-    assert_infer!("let <<x:float>> = <<1>> x", "Float");
-    assert_infer!("let <<x:binary>> = <<1>> x", "BitString");
-    assert_infer!("let <<x:bytes>> = <<1>> x", "BitString");
+    assert_infer!("let assert <<x>> = <<1>> x", "Int");
+    assert_infer!("let assert <<x>> = <<1>> x", "Int");
+    assert_infer!("let assert <<x:float>> = <<1>> x", "Float");
+    assert_infer!("let assert <<x:binary>> = <<1>> x", "BitString");
+    assert_infer!("let assert <<x:bytes>> = <<1>> x", "BitString");
     assert_infer!("let <<x:bit_string>> = <<1>> x", "BitString");
     assert_infer!("let <<x:bits>> = <<1>> x", "BitString");
 
-    assert_infer!("let <<x:utf8_codepoint>> = <<128013:32>> x", "UtfCodepoint");
     assert_infer!(
-        "let <<x:utf16_codepoint>> = <<128013:32>> x",
+        "let assert <<x:utf8_codepoint>> = <<128013:32>> x",
+        "UtfCodepoint"
+    );
+    assert_infer!(
+        "let assert <<x:utf16_codepoint>> = <<128013:32>> x",
         "UtfCodepoint"
     );
     assert_infer!(
-        "let <<x:utf32_codepoint>> = <<128013:32>> x",
+        "let assert <<x:utf32_codepoint>> = <<128013:32>> x",
         "UtfCodepoint"
     );
 
     assert_infer!(
-        "let a = <<1>> let <<x:binary>> = <<1, a:2-bit_string>> x",
+        "let a = <<1>> let assert <<x:binary>> = <<1, a:2-bit_string>> x",
         "BitString"
     );
     assert_infer!(
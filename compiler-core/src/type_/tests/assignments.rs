@@ -45,13 +45,13 @@ fn let_7() {
 
 #[test]
 fn let_8() {
-    assert_infer!("let [1, 2, ..x]: List(Int) = [1,2,3] x", "List(Int)",);
+    assert_infer!("let assert [1, 2, ..x]: List(Int) = [1,2,3] x", "List(Int)",);
 }
 
 #[test]
 fn let_9() {
     assert_infer!(
-        "let #(5, [..x]): #(Int, List(Int)) = #(5, [1,2,3]) x",
+        "let assert #(5, [..x]): #(Int, List(Int)) = #(5, [1,2,3]) x",
         "List(Int)",
     );
 }
@@ -59,7 +59,7 @@ fn let_9() {
 #[test]
 fn let_10() {
     assert_infer!(
-        "let #(5.0, [..x]): #(Float, List(Int)) = #(5.0, [1,2,3]) x",
+        "let assert #(5.0, [..x]): #(Float, List(Int)) = #(5.0, [1,2,3]) x",
         "List(Int)",
     );
 }
@@ -76,32 +76,32 @@ fn let_12() {
 
 #[test]
 fn let_13() {
-    assert_infer!("let [a] = [1] a", "Int");
+    assert_infer!("let assert [a] = [1] a", "Int");
 }
 
 #[test]
 fn let_14() {
-    assert_infer!("let [a, 2] = [1] a", "Int");
+    assert_infer!("let assert [a, 2] = [1] a", "Int");
 }
 
 #[test]
 fn let_15() {
-    assert_infer!("let [a, .. b] = [1] a", "Int");
+    assert_infer!("let assert [a, .. b] = [1] a", "Int");
 }
 
 #[test]
 fn let_16() {
-    assert_infer!("let [a, .. _] = [1] a", "Int");
+    assert_infer!("let assert [a, .. _] = [1] a", "Int");
 }
 
 #[test]
 fn let_17() {
-    assert_infer!("fn(x) { let [a] = x a }", "fn(List(a)) -> a");
+    assert_infer!("fn(x) { let assert [a] = x a }", "fn(List(a)) -> a");
 }
 
 #[test]
 fn let_18() {
-    assert_infer!("fn(x) { let [a] = x a + 1 }", "fn(List(Int)) -> Int");
+    assert_infer!("fn(x) { let assert [a] = x a + 1 }", "fn(List(Int)) -> Int");
 }
 
 #[test]
@@ -126,7 +126,7 @@ fn let_22() {
 
 #[test]
 fn let_23() {
-    assert_infer!("let [] = [] 1", "Int");
+    assert_infer!("let assert [] = [] 1", "Int");
 }
 
 // // https://github.com/gleam-lang/gleam/issues/1991
@@ -1,6 +1,7 @@
 use super::*;
 use crate::{
-    assert_error, assert_module_error, assert_module_syntax_error, assert_with_module_error,
+    assert_error, assert_module_error, assert_module_errors, assert_module_syntax_error,
+    assert_with_module_error,
 };
 
 #[test]
@@ -1778,3 +1779,71 @@ fn() {
 "
     );
 }
+
+#[test]
+fn errors_in_several_functions() {
+    assert_module_errors!(
+        r#"fn one() -> Int {
+  "one"
+}
+
+fn two() {
+  2 + 2.0
+}
+
+fn three() -> Int {
+  3
+}
+"#
+    );
+}
+
+// The failed function has the type of its annotations, so functions using it
+// in a way that matches them have no errors.
+#[test]
+fn function_using_failed_function() {
+    assert_module_errors!(
+        r#"fn one(x: a) {
+  1 + 1.0
+  x
+}
+
+fn two() -> String {
+  one("two")
+}
+
+fn three() -> Int {
+  one(3)
+}
+
+fn four() -> Int {
+  five()
+}
+
+fn five() -> Int {
+  "five"
+}
+
+fn six() -> String {
+  five()
+}
+"#
+    );
+}
+
+// Functions are checked after the functions they use, but the errors are
+// reported in the order they appear in the module.
+#[test]
+fn errors_in_order_of_location() {
+    assert_module_errors!(
+        r#"fn one() -> Int {
+  two()
+  1 + 1.0
+}
+
+fn two() -> Int {
+  "two"
+}
+"#
+    );
+}
@@ -0,0 +1,231 @@
+use super::*;
+use crate::{assert_module_error, assert_module_infer, assert_with_module_error};
+
+#[test]
+fn nested_custom_types() {
+    assert_module_infer!(
+        r#"
+pub fn main(x) {
+  case x {
+    Ok(Ok(_)) -> 1
+    Ok(Error(_)) -> 2
+    Error(Nil) -> 3
+  }
+}
+"#,
+        vec![("main", "fn(Result(Result(a, b), Nil)) -> Int")],
+    );
+}
+
+#[test]
+fn tuples() {
+    assert_module_infer!(
+        r#"
+pub fn main(x) {
+  case x {
+    #(True, _) -> 1
+    #(_, True) -> 2
+    #(False, False) -> 3
+  }
+}
+"#,
+        vec![("main", "fn(#(Bool, Bool)) -> Int")],
+    );
+}
+
+#[test]
+fn lists() {
+    assert_module_infer!(
+        r#"
+pub fn main(x) {
+  case x {
+    [] -> 0
+    [_] -> 1
+    [_, _, ..] -> 2
+  }
+}
+"#,
+        vec![("main", "fn(List(a)) -> Int")],
+    );
+}
+
+#[test]
+fn multiple_subjects() {
+    assert_module_infer!(
+        r#"
+pub fn main(x, y) {
+  case x, y {
+    True, _ -> 1
+    False, Ok(_) -> 2
+    _, Error(_) -> 3
+  }
+}
+"#,
+        vec![("main", "fn(Bool, Result(a, b)) -> Int")],
+    );
+}
+
+#[test]
+fn bit_string_segment_matches_everything() {
+    assert_module_infer!(
+        r#"
+pub fn main(x) {
+  let <<y:bit_string>> = x
+  y
+}
+"#,
+        vec![("main", "fn(BitString) -> BitString")],
+    );
+}
+
+#[test]
+fn empty_string_prefix_matches_everything() {
+    assert_module_infer!(
+        r#"
+pub fn main(x) {
+  case x {
+    "" <> rest -> rest
+  }
+}
+"#,
+        vec![("main", "fn(String) -> String")],
+    );
+}
+
+#[test]
+fn many_subjects_with_catch_all_clause() {
+    assert_module_infer!(
+        r#"
+pub fn main(x) {
+  case x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x {
+    True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True -> 1
+    _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _ -> 2
+  }
+}
+"#,
+        vec![("main", "fn(Bool) -> Int")],
+    );
+}
+
+#[test]
+fn nested_constructor_not_matched() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  case x {
+    Ok(True) -> 1
+    Error(_) -> 2
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn tuple_not_matched() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  case x {
+    #(True, _) -> 1
+    #(_, True) -> 2
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn list_not_matched() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  case x {
+    [] -> 0
+    [_, _] -> 2
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn multiple_subjects_not_matched() {
+    assert_module_error!(
+        r#"
+pub fn main(x, y) {
+  case x, y {
+    True, Ok(_) -> 1
+    False, _ -> 2
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn string_prefix_not_matched() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  case x {
+    "a" <> _ -> 1
+    "b" -> 2
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn int_not_matched() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  let 1 = x
+  x
+}
+"#
+    );
+}
+
+#[test]
+fn imported_constructor_not_matched() {
+    assert_with_module_error!(
+        ("animal", "pub type Animal { Cat(String) Dog }"),
+        r#"
+import animal.{Dog}
+pub fn main(x) {
+  case x {
+    Dog -> 1
+  }
+}
+"#,
+    );
+}
+
+#[test]
+fn several_missing_patterns() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  case x {
+    #([], Ok(_)) -> 1
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn many_subjects_not_matched() {
+    assert_module_error!(
+        r#"
+pub fn main(x) {
+  case x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x {
+    True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True -> 1
+  }
+}
+"#
+    );
+}
@@ -0,0 +1,130 @@
+use crate::{assert_module_error, assert_module_infer};
+
+#[test]
+fn int_arithmetic() {
+    assert_module_infer!(
+        "
+pub fn main(x, y) {
+  case x {
+    _ if x + 1 > y * 2 - y / 3 % 4 -> 1
+    _ -> 0
+  }
+}
+",
+        vec![("main", "fn(Int, Int) -> Int")],
+    );
+}
+
+#[test]
+fn float_arithmetic() {
+    assert_module_infer!(
+        "
+pub fn main(x, y) {
+  case x {
+    _ if x +. 1.0 >. y *. 2.0 -. y /. 3.0 -> 1
+    _ -> 0
+  }
+}
+",
+        vec![("main", "fn(Float, Float) -> Int")],
+    );
+}
+
+#[test]
+fn string_concatenation() {
+    assert_module_infer!(
+        r#"
+pub fn main(x, y) {
+  case x {
+    _ if x <> "!" == y -> 1
+    _ -> 0
+  }
+}
+"#,
+        vec![("main", "fn(String, String) -> Int")],
+    );
+}
+
+#[test]
+fn negation() {
+    assert_module_infer!(
+        "
+pub fn main(x, y) {
+  case x {
+    _ if !x && !{ y || x } -> 1
+    _ -> 0
+  }
+}
+",
+        vec![("main", "fn(Bool, Bool) -> Int")],
+    );
+}
+
+#[test]
+fn record_field_access() {
+    assert_module_infer!(
+        "
+pub type Person {
+  Person(name: String, age: Int)
+}
+
+pub fn main(people: #(Person, Person)) {
+  case people {
+    #(person, _) if person.age > 17 -> person.name
+    #(_, other) if other.name == \"Lucy\" -> other.name
+    _ -> \"\"
+  }
+}
+",
+        vec![
+            ("Person", "fn(String, Int) -> Person"),
+            ("main", "fn(#(Person, Person)) -> String")
+        ],
+    );
+}
+
+#[test]
+fn int_float_arithmetic() {
+    assert_module_error!(
+        "
+pub fn main(x) {
+  case x {
+    _ if x + 1.0 > 2 -> 1
+    _ -> 0
+  }
+}
+"
+    );
+}
+
+#[test]
+fn negation_of_int() {
+    assert_module_error!(
+        "
+pub fn main(x: Int) {
+  case x {
+    _ if !x -> 1
+    _ -> 0
+  }
+}
+"
+    );
+}
+
+#[test]
+fn unknown_field() {
+    assert_module_error!(
+        "
+pub type Person {
+  Person(name: String)
+}
+
+pub fn main(person: Person) {
+  case person {
+    _ if person.age > 17 -> 1
+    _ -> 0
+  }
+}
+"
+    );
+}
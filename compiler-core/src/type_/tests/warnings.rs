@@ -480,7 +480,7 @@ fn bit_pattern_var_use() {
     assert_no_warnings!(
         "
 pub fn main(x) {
-  let <<name_size:8, name:binary-size(name_size)>> = x
+  let assert <<name_size:8, name:binary-size(name_size)>> = x
   name
 }",
     );
@@ -525,3 +525,125 @@ pub const make_two = one.Two
 "
     );
 }
+
+#[test]
+fn unreachable_clause_after_discard() {
+    assert_warning!(
+        "
+pub fn main(x) {
+  case x {
+    _ -> 1
+    True -> 2
+  }
+}"
+    );
+}
+
+#[test]
+fn unreachable_duplicate_constructor_clause() {
+    assert_warning!(
+        "
+pub fn main(x) {
+  case x {
+    Ok(_) -> 1
+    Error(_) -> 2
+    Ok(1) -> 3
+  }
+}"
+    );
+}
+
+#[test]
+fn unreachable_string_clause_after_prefix() {
+    assert_warning!(
+        r#"
+pub fn main(x) {
+  case x {
+    "a" <> _ -> 1
+    "ab" -> 2
+    _ -> 3
+  }
+}"#
+    );
+}
+
+#[test]
+fn unreachable_int_clause_with_same_value() {
+    assert_warning!(
+        "
+pub fn main(x) {
+  case x {
+    16 -> 1
+    0x10 -> 2
+    _ -> 3
+  }
+}"
+    );
+}
+
+#[test]
+fn unreachable_float_clause_with_same_value() {
+    assert_warning!(
+        "
+pub fn main(x) {
+  case x {
+    1.0 -> 1
+    1.00 -> 2
+    _ -> 3
+  }
+}"
+    );
+}
+
+#[test]
+fn unreachable_clause_with_alternatives() {
+    assert_warning!(
+        "
+pub fn main(x) {
+  case x {
+    [] -> 1
+    [_, ..] -> 2
+    [_] | [] -> 3
+  }
+}"
+    );
+}
+
+#[test]
+fn unreachable_clause_with_many_subjects() {
+    assert_warning!(
+        "
+pub fn main(x) {
+  case x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x, x {
+    _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _, _ -> 1
+    True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True, True -> 2
+  }
+}"
+    );
+}
+
+#[test]
+fn clause_after_guarded_clause_is_reachable() {
+    assert_no_warnings!(
+        "
+pub fn main(x, y) {
+  case x {
+    _ if y -> 1
+    _ -> 2
+  }
+}"
+    );
+}
+
+#[test]
+fn clause_with_reachable_alternative_is_reachable() {
+    assert_no_warnings!(
+        "
+pub fn main(x) {
+  case x {
+    Ok(_) -> 1
+    Ok(_) | Error(_) -> 2
+  }
+}"
+    );
+}
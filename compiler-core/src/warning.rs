@@ -382,6 +382,26 @@ expression.",
                         extra_labels: Vec::new(),
                     }),
                 },
+
+                type_::Warning::UnreachableCaseClause { location } => Diagnostic {
+                    title: "Unreachable case clause".into(),
+                    text: "".into(),
+                    hint: Some("You can safely remove this clause.".into()),
+                    level: diagnostic::Level::Warning,
+                    location: Some(Location {
+                        src: src.clone(),
+                        path: path.to_path_buf(),
+                        label: diagnostic::Label {
+                            text: Some(
+                                "This clause cannot be reached as the clauses \
+before it match all of the same values."
+                                    .into(),
+                            ),
+                            span: *location,
+                        },
+                        extra_labels: Vec::new(),
+                    }),
+                },
             },
         }
     }
@@ -25,14 +25,46 @@ export interface BitStringStatic {
   isBitString(value: unknown): boolean;
 }
 
+export type Encoding = "utf8" | "utf16" | "utf32";
+
 export interface BitString {
   get __gleam_prelude_variant__(): "BitString";
+  readonly buffer: Uint8Array;
+  readonly bitSize: number;
   get length(): number;
   inspect(): string;
   byteAt(index: number): number;
   floatAt(index: number): number;
   intFromSlice(start: number, end: number): number;
   sliceAfter(index: number): BitString;
+  intFromBits(
+    start: number,
+    size: number,
+    isBigEndian?: boolean,
+    isSigned?: boolean
+  ): number | undefined;
+  floatFromBits(
+    start: number,
+    size?: number,
+    isBigEndian?: boolean
+  ): number | undefined;
+  sliceBits(start: number, end?: number): BitString | undefined;
+  stringFromBits(
+    start: number,
+    end: number,
+    encoding?: Encoding,
+    isBigEndian?: boolean
+  ): string | undefined;
+  codepointFromBits(
+    start: number,
+    encoding?: Encoding,
+    isBigEndian?: boolean
+  ): Utf8Codepoint | undefined;
+  codepointSizeFromBits(
+    start: number,
+    encoding?: Encoding,
+    isBigEndian?: boolean
+  ): number;
 }
 
 export interface Utf8Codepoint {
@@ -40,16 +72,38 @@ export interface Utf8Codepoint {
   inspect(): string;
 }
 
-export function toBitString(segments: Array<number | Uint8Array>): BitString;
+export function toBitString(
+  segments: Array<number | Uint8Array | BitString>
+): BitString;
 
-export function sizedInt(number: number, size: number): BitString;
+export function sizedInt(
+  number: number,
+  size: number,
+  isBigEndian?: boolean
+): Uint8Array | BitString;
 
-export function stringBits(string: string): Uint8Array;
+export function stringBits(
+  string: string,
+  encoding?: Encoding,
+  isBigEndian?: boolean
+): Uint8Array;
 
-export function codepointBits(codepoint: Utf8Codepoint): Uint8Array;
+export function codepointBits(
+  codepoint: Utf8Codepoint,
+  encoding?: Encoding,
+  isBigEndian?: boolean
+): Uint8Array;
 
 export function float64Bits(float: number): Uint8Array;
 
+export function sizedFloat(
+  float: number,
+  size: 16 | 32 | 64,
+  isBigEndian?: boolean
+): Uint8Array;
+
+export const isNativeBigEndian: boolean;
+
 export interface Result<T, E> {
   get __gleam_prelude_variant__(): "Ok" | "Error";
   isOk(): boolean;
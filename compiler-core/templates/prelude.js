@@ -115,8 +115,12 @@ export class BitString {
     return data?.__gleam_prelude_variant__ === "BitString";
   }
 
-  constructor(buffer) {
+  // The bit size only needs to be given for bit strings that are not a whole
+  // number of bytes long, in which case the unused low bits of the last byte
+  // must be zero.
+  constructor(buffer, bitSize = buffer.length * 8) {
     this.buffer = buffer;
+    this.bitSize = bitSize;
   }
 
   get __gleam_prelude_variant__() {
@@ -124,7 +128,13 @@ export class BitString {
   }
 
   inspect() {
-    return `<<${Array.from(this.buffer).join(", ")}>>`;
+    let bytes = Array.from(this.buffer);
+    let trailingBits = this.bitSize % 8;
+    if (trailingBits !== 0) {
+      let last = bytes.pop() >> (8 - trailingBits);
+      bytes.push(`${last}:size(${trailingBits})`);
+    }
+    return `<<${bytes.join(", ")}>>`;
   }
 
   get length() {
@@ -136,7 +146,7 @@ export class BitString {
   }
 
   floatAt(index) {
-    return byteArrayToFloat(this.buffer.slice(index, index + 8));
+    return this.floatFromBits(index * 8, 64);
   }
 
   intFromSlice(start, end) {
@@ -144,7 +154,111 @@ export class BitString {
   }
 
   sliceAfter(index) {
-    return new BitString(this.buffer.slice(index));
+    return new BitString(
+      this.buffer.slice(index),
+      Math.max(0, this.bitSize - index * 8)
+    );
+  }
+
+  // The methods below take offsets and sizes in bits and return `undefined`
+  // if the bit string is not long enough, so they can be used while a pattern
+  // is still being checked.
+
+  intFromBits(start, size, isBigEndian = true, isSigned = false) {
+    if (!hasBits(this, start, size)) return undefined;
+    let value = 0;
+    if (isBigEndian) {
+      value = readBits(this.buffer, start, size);
+    } else {
+      // The least significant byte comes first, with any bits left over
+      // being the most significant.
+      let scale = 1;
+      for (let offset = 0; offset < size; offset += 8) {
+        let chunk = Math.min(8, size - offset);
+        value += readBits(this.buffer, start + offset, chunk) * scale;
+        scale *= 256;
+      }
+    }
+    if (isSigned && size > 0 && value >= 2 ** (size - 1)) {
+      value -= 2 ** size;
+    }
+    return value;
+  }
+
+  floatFromBits(start, size = 64, isBigEndian = true) {
+    if (!hasBits(this, start, size)) return undefined;
+    let view = new DataView(this.bytesFromBits(start, size).buffer);
+    switch (size) {
+      case 64:
+        return view.getFloat64(0, !isBigEndian);
+      case 32:
+        return view.getFloat32(0, !isBigEndian);
+      case 16:
+        return float16ToNumber(view.getUint16(0, !isBigEndian));
+      default:
+        return undefined;
+    }
+  }
+
+  sliceBits(start, end = this.bitSize) {
+    if (!hasBits(this, start, end - start)) return undefined;
+    return new BitString(this.bytesFromBits(start, end - start), end - start);
+  }
+
+  stringFromBits(start, end, encoding = "utf8", isBigEndian = true) {
+    if (!hasBits(this, start, end - start) || (end - start) % 8 !== 0) {
+      return undefined;
+    }
+    let bytes = this.bytesFromBits(start, end - start);
+    return decodeString(bytes, encoding, isBigEndian);
+  }
+
+  codepointFromBits(start, encoding = "utf8", isBigEndian = true) {
+    let size = this.codepointSizeFromBits(start, encoding, isBigEndian);
+    if (size === 0) return undefined;
+    let end = start + size;
+    let string = this.stringFromBits(start, end, encoding, isBigEndian);
+    return new UtfCodepoint(string.codePointAt(0));
+  }
+
+  // The size in bits of the codepoint at the start offset, or 0 if there is
+  // not a valid codepoint there.
+  codepointSizeFromBits(start, encoding = "utf8", isBigEndian = true) {
+    let size;
+    if (encoding === "utf8") {
+      let first = this.intFromBits(start, 8);
+      if (first === undefined) return 0;
+      else if (first < 0x80) size = 8;
+      else if (first >= 0xc2 && first < 0xe0) size = 16;
+      else if (first >= 0xe0 && first < 0xf0) size = 24;
+      else if (first >= 0xf0 && first < 0xf5) size = 32;
+      else return 0;
+    } else if (encoding === "utf16") {
+      let first = this.intFromBits(start, 16, isBigEndian);
+      size = first >= 0xd800 && first < 0xdc00 ? 32 : 16;
+    } else {
+      size = 32;
+    }
+    let end = start + size;
+    let string = this.stringFromBits(start, end, encoding, isBigEndian);
+    return string === undefined ? 0 : size;
+  }
+
+  // The bits from the start offset as bytes, with any unused bits of the last
+  // byte set to zero.
+  bytesFromBits(start, size) {
+    if (start % 8 === 0) {
+      let bytes = this.buffer.slice(start / 8, Math.ceil((start + size) / 8));
+      if (size % 8 !== 0) bytes[bytes.length - 1] &= 0xff << (8 - (size % 8));
+      return bytes;
+    }
+    let bytes = new Uint8Array(Math.ceil(size / 8));
+    for (let offset = 0; offset < size; offset += 8) {
+      let chunk = Math.min(8, size - offset);
+      let bits = readBits(this.buffer, start + offset, chunk);
+      bytes[offset / 8] = bits << (8 - chunk);
+    }
+    return bytes;
   }
 }
 
@@ -163,39 +277,79 @@ export class UtfCodepoint {
 }
 
 export function toBitString(segments) {
-  let size = (segment) =>
-    segment instanceof Uint8Array ? segment.byteLength : 1;
-  let bytes = segments.reduce((acc, segment) => acc + size(segment), 0);
-  let view = new DataView(new ArrayBuffer(bytes));
+  let bitSize = 0;
+  for (let segment of segments) {
+    bitSize += segmentBitSize(segment);
+  }
+  let buffer = new Uint8Array(Math.ceil(bitSize / 8));
   let cursor = 0;
   for (let segment of segments) {
-    if (segment instanceof Uint8Array) {
-      new Uint8Array(view.buffer).set(segment, cursor);
-      cursor += segment.byteLength;
+    if (typeof segment === "number") {
+      writeBits(buffer, cursor, segment & 0xff, 8);
     } else {
-      view.setInt8(cursor, segment);
-      cursor++;
+      let bytes = segment instanceof Uint8Array ? segment : segment.buffer;
+      let size = segmentBitSize(segment);
+      if (cursor % 8 === 0) {
+        buffer.set(bytes.subarray(0, Math.ceil(size / 8)), cursor / 8);
+      } else {
+        for (let offset = 0; offset < size; offset += 8) {
+          let chunk = Math.min(8, size - offset);
+          let bits = bytes[offset / 8] >> (8 - chunk);
+          writeBits(buffer, cursor + offset, bits, chunk);
+        }
+      }
     }
+    cursor += segmentBitSize(segment);
   }
-  return new BitString(new Uint8Array(view.buffer));
+  return new BitString(buffer, bitSize);
 }
 
+function segmentBitSize(segment) {
+  if (typeof segment === "number") return 8;
+  if (segment instanceof Uint8Array) return segment.length * 8;
+  if (segment instanceof BitString) return segment.bitSize;
+  throw new globalThis.Error(`Invalid bit string segment: ${inspect(segment)}`);
+}
+
+// Returns a Uint8Array when the size is a whole number of bytes, and a
+// BitString otherwise.
 // Derived from this answer https://stackoverflow.com/questions/8482309/converting-javascript-integer-to-byte-array-and-back
-export function sizedInt(value, size) {
-  if (size < 0) {
+export function sizedInt(value, size, isBigEndian = true) {
+  if (size <= 0) {
     return new Uint8Array();
   }
-  if (size % 8 != 0) {
-    throw "Needs to be a byte size" + size;
-  }
-  var byteArray = new Uint8Array(size / 8);
-
-  for (var index = 0; index < byteArray.length; index++) {
-    var byte = value & 0xff;
+  // The bytes of the value, least significant first
+  let byteArray = new Uint8Array(Math.ceil(size / 8));
+  for (let index = 0; index < byteArray.length; index++) {
+    let byte = value & 0xff;
     byteArray[index] = byte;
     value = (value - byte) / 256;
   }
-  return byteArray.reverse();
+
+  let trailingBits = size % 8;
+  if (trailingBits === 0) {
+    return isBigEndian ? byteArray.reverse() : byteArray;
+  }
+
+  // The most significant byte only holds the bits left over, and comes first
+  // when big endian and last when little endian.
+  let last = byteArray.length - 1;
+  byteArray[last] &= (1 << trailingBits) - 1;
+  let buffer = new Uint8Array(byteArray.length);
+  let cursor = 0;
+  if (isBigEndian) {
+    writeBits(buffer, cursor, byteArray[last], trailingBits);
+    cursor += trailingBits;
+  }
+  for (let index = last - 1; index >= 0; index--) {
+    let byte = byteArray[isBigEndian ? index : last - 1 - index];
+    writeBits(buffer, cursor, byte, 8);
+    cursor += 8;
+  }
+  if (!isBigEndian) {
+    writeBits(buffer, cursor, byteArray[last], trailingBits);
+  }
+  return new BitString(buffer, size);
 }
 
 export function byteArrayToInt(byteArray) {
@@ -212,18 +366,161 @@ export function byteArrayToFloat(byteArray) {
   return new Float64Array(byteArray.reverse().buffer)[0];
 }
 
-export function stringBits(string) {
-  return new TextEncoder().encode(string);
+export function stringBits(string, encoding = "utf8", isBigEndian = true) {
+  if (encoding === "utf8") {
+    return new TextEncoder().encode(string);
+  }
+  let units =
+    encoding === "utf16"
+      ? Array.from({ length: string.length }, (_, i) => string.charCodeAt(i))
+      : Array.from(string, (character) => character.codePointAt(0));
+  let unitSize = encoding === "utf16" ? 2 : 4;
+  let bytes = new Uint8Array(units.length * unitSize);
+  let view = new DataView(bytes.buffer);
+  units.forEach((unit, index) => {
+    if (unitSize === 2) view.setUint16(index * 2, unit, !isBigEndian);
+    else view.setUint32(index * 4, unit, !isBigEndian);
+  });
+  return bytes;
 }
 
-export function codepointBits(codepoint) {
-  return stringBits(String.fromCodePoint(codepoint.value));
+export function codepointBits(
+  codepoint,
+  encoding = "utf8",
+  isBigEndian = true
+) {
+  let string = String.fromCodePoint(codepoint.value);
+  return stringBits(string, encoding, isBigEndian);
 }
 
 export function float64Bits(float) {
   return new Uint8Array(Float64Array.from([float]).buffer).reverse();
 }
 
+export function sizedFloat(float, size, isBigEndian = true) {
+  let bytes = new Uint8Array(size / 8);
+  let view = new DataView(bytes.buffer);
+  switch (size) {
+    case 64:
+      view.setFloat64(0, float, !isBigEndian);
+      return bytes;
+    case 32:
+      view.setFloat32(0, float, !isBigEndian);
+      return bytes;
+    case 16:
+      view.setUint16(0, numberToFloat16(float), !isBigEndian);
+      return bytes;
+    default:
+      throw new globalThis.Error(`Invalid float size: ${size}`);
+  }
+}
+
+export const isNativeBigEndian =
+  new Uint8Array(new Uint16Array([1]).buffer)[0] === 0;
+
+function hasBits(bitString, start, size) {
+  return (
+    Number.isInteger(start) &&
+    Number.isInteger(size) &&
+    start >= 0 &&
+    size >= 0 &&
+    start + size <= bitString.bitSize
+  );
+}
+
+// Reads up to 53 bits from the start offset as a big endian unsigned integer.
+function readBits(buffer, start, size) {
+  let value = 0;
+  let end = start + size;
+  while (start < end) {
+    let byte = buffer[Math.floor(start / 8)];
+    let bitOffset = start % 8;
+    let chunk = Math.min(8 - bitOffset, end - start);
+    let bits = (byte >> (8 - bitOffset - chunk)) & ((1 << chunk) - 1);
+    value = value * 2 ** chunk + bits;
+    start += chunk;
+  }
+  return value;
+}
+
+// Writes the low `size` bits of the value, at most 8, at the start offset.
+// The buffer must be zeroed at that position.
+function writeBits(buffer, start, value, size) {
+  while (size > 0) {
+    let index = Math.floor(start / 8);
+    let free = 8 - (start % 8);
+    let chunk = Math.min(free, size);
+    let bits = (value >> (size - chunk)) & ((1 << chunk) - 1);
+    buffer[index] |= bits << (free - chunk);
+    start += chunk;
+    size -= chunk;
+  }
+}
+
+function decodeString(bytes, encoding, isBigEndian) {
+  if (encoding === "utf8") {
+    try {
+      let decoder = new TextDecoder("utf-8", { fatal: true, ignoreBOM: true });
+      return decoder.decode(bytes);
+    } catch {
+      return undefined;
+    }
+  }
+  let unitSize = encoding === "utf16" ? 2 : 4;
+  if (bytes.length % unitSize !== 0) return undefined;
+  let view = new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+  let string = "";
+  for (let index = 0; index < bytes.length; index += unitSize) {
+    if (unitSize === 2) {
+      string += String.fromCharCode(view.getUint16(index, !isBigEndian));
+    } else {
+      let codepoint = view.getUint32(index, !isBigEndian);
+      if (codepoint > 0x10ffff || (codepoint >= 0xd800 && codepoint < 0xe000)) {
+        return undefined;
+      }
+      string += String.fromCodePoint(codepoint);
+    }
+  }
+  // Reject unpaired surrogates
+  let unpairedSurrogate =
+    /[\ud800-\udbff](?![\udc00-\udfff])|(?<![\ud800-\udbff])[\udc00-\udfff]/;
+  if (unpairedSurrogate.test(string)) return undefined;
+  return string;
+}
+
+function float16ToNumber(bits) {
+  let sign = bits & 0x8000 ? -1 : 1;
+  let exponent = (bits >> 10) & 0x1f;
+  let fraction = bits & 0x3ff;
+  if (exponent === 0) return sign * 2 ** -14 * (fraction / 1024);
+  if (exponent === 0x1f) return fraction ? NaN : sign * Infinity;
+  return sign * 2 ** (exponent - 15) * (1 + fraction / 1024);
+}
+
+function numberToFloat16(number) {
+  let view = new DataView(new ArrayBuffer(4));
+  view.setFloat32(0, number);
+  let bits = view.getUint32(0);
+  let sign = (bits >>> 16) & 0x8000;
+  let exponent = ((bits >>> 23) & 0xff) - 127 + 15;
+  let fraction = bits & 0x7fffff;
+  if (exponent === 0xff - 127 + 15) {
+    return sign | 0x7c00 | (fraction ? 0x200 : 0);
+  }
+  if (exponent <= 0) {
+    if (exponent < -10) return sign;
+    fraction = (fraction | 0x800000) >> (1 - exponent);
+    return sign | ((fraction + 0x1000) >> 13);
+  }
+  fraction += 0x1000;
+  if (fraction & 0x800000) {
+    fraction = 0;
+    exponent += 1;
+  }
+  if (exponent >= 0x1f) return sign | 0x7c00;
+  return sign | (exponent << 10) | (fraction >> 13);
+}
+
 export class Result extends CustomType {
   static isResult(data) {
     let variant = data?.__gleam_prelude_variant__;
@@ -59,7 +59,9 @@ pub fn prepare(path: &str) -> String {
     compiler.write_metadata = true;
     compiler.compile_beam_bytecode = false;
     compiler.copy_native_files = false;
-    let result = compiler.compile(&warning_emitter, &mut modules, &mut im::HashMap::new());
+    let result = compiler
+        .compile(&warning_emitter, &mut modules, &mut im::HashMap::new())
+        .into_result();
     match result {
         Ok(_) => {
             for path in initial_files {
@@ -12,6 +12,8 @@ import {
   divideInt,
   inspect,
   isEqual,
+  sizedFloat,
+  sizedInt,
   stringBits,
   toBitString,
   toList,
@@ -154,6 +156,75 @@ assertEqual(
   toBitString([codepointBits(new UtfCodepoint(128013))])
 );
 
+assertEqual(
+  new BitString(new Uint8Array([0, 97, 0, 0, 0, 98])),
+  toBitString([stringBits("a", "utf16"), stringBits("b", "utf32")])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([97, 0])),
+  toBitString([stringBits("a", "utf16", false)])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([0xd8, 0x3d, 0xdc, 0x0d])),
+  toBitString([codepointBits(new UtfCodepoint(128013), "utf16")])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([1, 2])),
+  toBitString([1, new BitString(new Uint8Array([2]))])
+);
+
+// Segments that are not a whole number of bytes
+
+assertEqual(
+  new BitString(new Uint8Array([160]), 3),
+  toBitString([sizedInt(5, 3)])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([191, 224]), 11),
+  toBitString([sizedInt(5, 3), 255])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([1, 2])),
+  toBitString([sizedInt(0, 4), sizedInt(1, 4), sizedInt(2, 8)])
+);
+
+assertNotEqual(
+  new BitString(new Uint8Array([160]), 3),
+  new BitString(new Uint8Array([160]), 4)
+);
+
+// Endianness and signedness
+
+assertEqual(
+  new BitString(new Uint8Array([2, 1])),
+  toBitString([sizedInt(258, 16, false)])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([35, 16]), 12),
+  toBitString([sizedInt(0x123, 12, false)])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([255, 254])),
+  toBitString([sizedInt(-2, 16)])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([63, 128, 0, 0])),
+  toBitString([sizedFloat(1.0, 32)])
+);
+
+assertEqual(
+  new BitString(new Uint8Array([0, 60])),
+  toBitString([sizedFloat(1.0, 16, false)])
+);
+
 // toList
 
 assertEqual(toList([]), List.fromArray([]));
@@ -304,7 +375,10 @@ const hasEqualsField2 = {
 assertEqual(new NoCustomEquals(1, 1), new NoCustomEquals(1, 1));
 assertNotEqual(new NoCustomEquals(1, 1), new NoCustomEquals(1, 2));
 // custom equals throws, fallback to structural equality
-assertEqual(new HasCustomEqualsThatThrows(1, 1), new HasCustomEqualsThatThrows(1, 1));
+assertEqual(
+  new HasCustomEqualsThatThrows(1, 1),
+  new HasCustomEqualsThatThrows(1, 1)
+);
 assertNotEqual(new HasCustomEqualsThatThrows(1, 1), new HasCustomEqualsThatThrows(1, 2));
 // custom equals works, use it
 assertEqual(new HasCustomEquals(1, 1), new HasCustomEquals(1, 1));
@@ -417,6 +491,10 @@ assertEqual(inspect(List.fromArray([new Ok(1), new Ok(2)])), "[Ok(1), Ok(2)]");
 
 assertEqual(inspect(new BitString(new Uint8Array([]))), "<<>>");
 assertEqual(inspect(new BitString(new Uint8Array([1, 2, 3]))), "<<1, 2, 3>>");
+assertEqual(
+  inspect(new BitString(new Uint8Array([1, 64]), 11)),
+  "<<1, 2:size(3)>>"
+);
 
 assertEqual(new BitString(new Uint8Array([1, 2, 3])).byteAt(0), 1);
 assertEqual(new BitString(new Uint8Array([1, 2, 3])).byteAt(2), 3);
@@ -431,6 +509,65 @@ assertEqual(
   new BitString(new Uint8Array([2, 3]))
 );
 
+assertEqual(new BitString(new Uint8Array([1, 2])).intFromBits(4, 8), 16);
+assertEqual(
+  new BitString(new Uint8Array([1, 2])).intFromBits(0, 16, false),
+  513
+);
+assertEqual(
+  new BitString(new Uint8Array([255])).intFromBits(0, 8, true, true),
+  -1
+);
+assertEqual(
+  new BitString(new Uint8Array([35, 16]), 12).intFromBits(0, 12, false),
+  0x123
+);
+assertEqual(new BitString(new Uint8Array([1])).intFromBits(4, 8), undefined);
+assertEqual(
+  new BitString(new Uint8Array([0, 60])).floatFromBits(0, 16, false),
+  1.0
+);
+assertEqual(
+  new BitString(new Uint8Array([63, 128, 0, 0])).floatFromBits(0, 32),
+  1.0
+);
+assertEqual(
+  new BitString(new Uint8Array([1, 2, 3])).sliceBits(4, 12),
+  new BitString(new Uint8Array([16]))
+);
+assertEqual(
+  new BitString(new Uint8Array([1, 2, 3])).sliceBits(20),
+  new BitString(new Uint8Array([48]), 4)
+);
+assertEqual(
+  new BitString(new Uint8Array([97, 98, 99])).stringFromBits(8, 24),
+  "bc"
+);
+assertEqual(
+  new BitString(new Uint8Array([0, 97])).stringFromBits(0, 16, "utf16"),
+  "a"
+);
+assertEqual(
+  new BitString(new Uint8Array([255, 97])).stringFromBits(0, 16),
+  undefined
+);
+assertEqual(
+  new BitString(new Uint8Array([240, 159, 144, 141])).codepointFromBits(0),
+  new UtfCodepoint(128013)
+);
+assertEqual(
+  new BitString(new Uint8Array([240, 159, 144, 141])).codepointSizeFromBits(0),
+  32
+);
+assertEqual(
+  new BitString(new Uint8Array([240, 159])).codepointSizeFromBits(0),
+  0
+);
+assertEqual(
+  new BitString(new Uint8Array([0xd8, 0x3d])).codepointSizeFromBits(0, "utf16"),
+  0
+);
+
 assertEqual(inspect(new UtfCodepoint(128013)), "//utfcodepoint(🐍)");
 
 assertEqual(